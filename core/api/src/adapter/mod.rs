@@ -2,10 +2,14 @@ use std::marker::PhantomData;
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use bytes::Bytes;
 
-use protocol::traits::executor::{ExecutorFactory, TrieDB};
+use protocol::traits::executor::{Executor, ExecutorFactory, ExecutorReadResp, TrieDB};
 use protocol::traits::{APIAdapter, Context, MemPool, Storage};
-use protocol::types::{Address, AssetID, Balance, Epoch, Hash, Receipt, SignedTransaction};
+use protocol::types::{
+    Address, AssetID, Balance, ContractAddress, Epoch, Hash, Receipt, SignedTransaction, StateDiff,
+    StateProof,
+};
 use protocol::ProtocolResult;
 
 pub struct DefaultAPIAdapter<EF, M, S, DB> {
@@ -26,6 +30,23 @@ impl<EF: ExecutorFactory<DB>, M: MemPool, S: Storage, DB: TrieDB> DefaultAPIAdap
             pin_ef: PhantomData,
         }
     }
+
+    async fn executor_at(
+        &self,
+        ctx: Context,
+        epoch_id: Option<u64>,
+    ) -> ProtocolResult<Box<dyn Executor>> {
+        let epoch: Epoch = self.get_epoch_by_id(ctx, epoch_id).await?;
+
+        EF::from_root(
+            epoch.header.chain_id,
+            epoch.header.state_root.clone(),
+            Arc::clone(&self.trie_db),
+            epoch.header.epoch_id,
+            0,
+            Address::User(epoch.header.proposer.clone()),
+        )
+    }
 }
 
 #[async_trait]
@@ -57,6 +78,14 @@ impl<EF: ExecutorFactory<DB>, M: MemPool, S: Storage, DB: TrieDB> APIAdapter
         self.storage.get_receipt(tx_hash).await
     }
 
+    async fn get_state_diff_by_tx_hash(
+        &self,
+        _ctx: Context,
+        tx_hash: Hash,
+    ) -> ProtocolResult<StateDiff> {
+        self.storage.get_state_diff(tx_hash).await
+    }
+
     async fn get_balance(
         &self,
         ctx: Context,
@@ -64,17 +93,55 @@ impl<EF: ExecutorFactory<DB>, M: MemPool, S: Storage, DB: TrieDB> APIAdapter
         id: &AssetID,
         epoch_id: Option<u64>,
     ) -> ProtocolResult<Balance> {
-        let epoch: Epoch = self.get_epoch_by_id(ctx.clone(), epoch_id).await?;
-
-        let executor = EF::from_root(
-            epoch.header.chain_id,
-            epoch.header.state_root.clone(),
-            Arc::clone(&self.trie_db),
-            epoch.header.epoch_id,
-            0,
-            Address::User(epoch.header.proposer.clone()),
-        )?;
+        let executor = self.executor_at(ctx, epoch_id).await?;
 
         executor.get_balance(address, id)
     }
+
+    async fn get_account_proof(
+        &self,
+        ctx: Context,
+        address: &Address,
+        epoch_id: Option<u64>,
+    ) -> ProtocolResult<StateProof> {
+        let executor = self.executor_at(ctx, epoch_id).await?;
+
+        executor.get_account_proof(address)
+    }
+
+    async fn get_asset_proof(
+        &self,
+        ctx: Context,
+        id: &AssetID,
+        epoch_id: Option<u64>,
+    ) -> ProtocolResult<StateProof> {
+        let executor = self.executor_at(ctx, epoch_id).await?;
+
+        executor.get_asset_proof(id)
+    }
+
+    async fn simulate(
+        &self,
+        ctx: Context,
+        signed_tx: SignedTransaction,
+        epoch_id: Option<u64>,
+    ) -> ProtocolResult<ExecutorReadResp> {
+        let mut executor = self.executor_at(ctx, epoch_id).await?;
+
+        executor.simulate(signed_tx)
+    }
+
+    async fn read_call(
+        &self,
+        ctx: Context,
+        caller: &Address,
+        contract: &ContractAddress,
+        method: &str,
+        args: Vec<Bytes>,
+        epoch_id: Option<u64>,
+    ) -> ProtocolResult<ExecutorReadResp> {
+        let mut executor = self.executor_at(ctx, epoch_id).await?;
+
+        executor.read_call(caller, contract, method, args)
+    }
 }
@@ -20,8 +20,9 @@ use protocol::traits::{APIAdapter, Context};
 
 use crate::config::GraphQLConfig;
 use crate::schema::{
-    Address, AssetID, Balance, Bytes, ContractType, Epoch, Hash, InputDeployAction,
-    InputRawTransaction, InputTransactionEncryption, InputTransferAction, Uint64,
+    AccountProof, Address, AssetID, AssetProof, Balance, Bytes, ContractType, Epoch, Hash,
+    InputDeployAction, InputRawTransaction, InputTransactionEncryption, InputTransferAction,
+    SimulateResult, StateDiff, Uint64,
 };
 use http::header::HeaderValue;
 use tide::middleware::{CorsMiddleware, CorsOrigin};
@@ -100,6 +101,149 @@ impl Query {
             Err(_) => Ok(Balance::from(protocol::types::Balance::from_bytes_be(b""))),
         }
     }
+
+    #[graphql(
+        name = "getAccountProof",
+        description = "Get the merkle proof of an account, which can be verified against \
+                       the state root of an epoch header"
+    )]
+    fn get_account_proof(
+        state_ctx: &State,
+        address: Address,
+        epoch_id: Option<Uint64>,
+    ) -> FieldResult<AccountProof> {
+        let epoch_id = opt_hex_to_u64(epoch_id.map(|id| id.as_hex()))?;
+        let address = protocol::types::Address::from_hex(&address.as_hex())?;
+
+        let proof = block_on(state_ctx.adapter.get_account_proof(
+            Context::new(),
+            &address,
+            epoch_id,
+        ))
+        .map_err(FieldError::from)?;
+        Ok(AccountProof::from(proof))
+    }
+
+    #[graphql(
+        name = "getAssetProof",
+        description = "Get the merkle proof of an asset, which can be verified against \
+                       the state root of an epoch header"
+    )]
+    fn get_asset_proof(
+        state_ctx: &State,
+        id: AssetID,
+        epoch_id: Option<Uint64>,
+    ) -> FieldResult<AssetProof> {
+        let epoch_id = opt_hex_to_u64(epoch_id.map(|id| id.as_hex()))?;
+        let id = protocol::types::AssetID::from_hex(&id.as_hex())?;
+
+        let proof = block_on(
+            state_ctx
+                .adapter
+                .get_asset_proof(Context::new(), &id, epoch_id),
+        )
+        .map_err(FieldError::from)?;
+        Ok(AssetProof::from(proof))
+    }
+
+    #[graphql(
+        name = "getStateDiff",
+        description = "Get the state changed by a transaction, which is only recorded by \
+                       the node with `trace_state` enabled"
+    )]
+    fn get_state_diff(state_ctx: &State, tx_hash: Hash) -> FieldResult<StateDiff> {
+        let tx_hash = protocol::types::Hash::from_hex(&tx_hash.as_hex())?;
+
+        let state_diff = block_on(
+            state_ctx
+                .adapter
+                .get_state_diff_by_tx_hash(Context::new(), tx_hash),
+        )
+        .map_err(FieldError::from)?;
+        Ok(StateDiff::from(state_diff))
+    }
+
+    #[graphql(
+        name = "simulateTransferTransaction",
+        description = "Execute a transfer transaction without sending it, \
+                       the signature is not verified"
+    )]
+    fn simulate_transfer_transaction(
+        state_ctx: &State,
+        input_raw: InputRawTransaction,
+        input_action: InputTransferAction,
+        input_encryption: InputTransactionEncryption,
+        epoch_id: Option<Uint64>,
+    ) -> FieldResult<SimulateResult> {
+        let epoch_id = opt_hex_to_u64(epoch_id.map(|id| id.as_hex()))?;
+        let action = cover_transfer_action(&input_action)?;
+        let signed_tx = cover_to_signed_tx(&action, &input_raw, &input_encryption)?;
+
+        let resp = block_on(
+            state_ctx
+                .adapter
+                .simulate(Context::new(), signed_tx, epoch_id),
+        )
+        .map_err(FieldError::from)?;
+        Ok(SimulateResult::from(resp))
+    }
+
+    #[graphql(
+        name = "simulateDeployTransaction",
+        description = "Execute a deployment contract transaction without sending it, \
+                       the signature is not verified"
+    )]
+    fn simulate_deploy_transaction(
+        state_ctx: &State,
+        input_raw: InputRawTransaction,
+        input_action: InputDeployAction,
+        input_encryption: InputTransactionEncryption,
+        epoch_id: Option<Uint64>,
+    ) -> FieldResult<SimulateResult> {
+        let epoch_id = opt_hex_to_u64(epoch_id.map(|id| id.as_hex()))?;
+        let action = cover_deploy_action(&input_action)?;
+        let signed_tx = cover_to_signed_tx(&action, &input_raw, &input_encryption)?;
+
+        let resp = block_on(
+            state_ctx
+                .adapter
+                .simulate(Context::new(), signed_tx, epoch_id),
+        )
+        .map_err(FieldError::from)?;
+        Ok(SimulateResult::from(resp))
+    }
+
+    #[graphql(
+        name = "readCall",
+        description = "Call a contract method without sending a transaction"
+    )]
+    fn read_call(
+        state_ctx: &State,
+        caller: Address,
+        contract: Address,
+        method: String,
+        args: Vec<Bytes>,
+        epoch_id: Option<Uint64>,
+    ) -> FieldResult<SimulateResult> {
+        let epoch_id = opt_hex_to_u64(epoch_id.map(|id| id.as_hex()))?;
+        let caller = protocol::types::Address::from_hex(&caller.as_hex())?;
+        let contract = protocol::types::ContractAddress::from_hex(&contract.as_hex())?;
+        let args = args
+            .iter()
+            .map(|arg| hex_to_vec_u8(&arg.as_hex()).map(bytes::Bytes::from))
+            .collect::<FieldResult<Vec<_>>>()?;
+
+        let resp = block_on(state_ctx.adapter.read_call(
+            Context::new(),
+            &caller,
+            &contract,
+            &method,
+            args,
+            epoch_id,
+        ))
+        .map_err(FieldError::from)?;
+        Ok(SimulateResult::from(resp))
+    }
 }
 
 struct Mutation;
@@ -337,6 +481,31 @@ fn cover_transfer_action(
     Ok(action)
 }
 
+fn cover_asset_params(
+    input_action: &InputDeployAction,
+) -> FieldResult<protocol::types::AssetParams> {
+    match (
+        &input_action.asset_name,
+        &input_action.asset_symbol,
+        &input_action.asset_supply,
+        &input_action.asset_decimals,
+    ) {
+        (Some(name), Some(symbol), Some(supply), Some(decimals)) => {
+            Ok(protocol::types::AssetParams {
+                name:     name.clone(),
+                symbol:   symbol.clone(),
+                supply:   protocol::types::Balance::from_bytes_be(
+                    hex_to_vec_u8(&supply.as_hex())?.as_ref(),
+                ),
+                decimals: hex_to_u64(&decimals.as_hex())?,
+            })
+        }
+        _ => Err(FieldError::from(
+            "asset name, symbol, supply and decimals are required by the asset contract",
+        )),
+    }
+}
+
 fn cover_deploy_action(
     input_action: &InputDeployAction,
 ) -> FieldResult<protocol::types::TransactionAction> {
@@ -346,9 +515,15 @@ fn cover_deploy_action(
         ContractType::Library => protocol::types::ContractType::Library,
     };
 
+    let asset_params = match contract_type {
+        protocol::types::ContractType::Asset => Some(cover_asset_params(input_action)?),
+        _ => None,
+    };
+
     let action = protocol::types::TransactionAction::Deploy {
         code: bytes::Bytes::from(hex_to_vec_u8(&input_action.code.as_hex())?),
         contract_type,
+        asset_params,
     };
 
     Ok(action)
@@ -1,10 +1,14 @@
 mod epoch;
+mod proof;
+mod state_diff;
 mod transaction;
 
 pub use epoch::{Epoch, EpochHeader};
+pub use proof::{AccountProof, AssetProof};
+pub use state_diff::{BalanceDiff, StateDiff, StorageDiff};
 pub use transaction::{
     ContractType, InputDeployAction, InputRawTransaction, InputTransactionEncryption,
-    InputTransferAction,
+    InputTransferAction, SimulateResult,
 };
 
 #[derive(GraphQLScalarValue, Clone)]
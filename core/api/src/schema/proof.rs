@@ -0,0 +1,57 @@
+use crate::schema::{Bytes, MerkleRoot};
+
+#[derive(GraphQLObject, Clone)]
+#[graphql(
+    description = "The merkle proof of an account. The world state is a two-level MPT, \
+                   the account is stored in the storage trie of the account contract \
+                   `0x23C000000000000000000000000000000000000001`, keyed by the fixed \
+                   encoded address."
+)]
+pub struct AccountProof {
+    #[graphql(description = "The state root which the proof is generated under")]
+    pub state_root: MerkleRoot,
+    #[graphql(
+        description = "The proof of the storage root of the account contract in the state trie"
+    )]
+    pub contract_proof: Vec<Bytes>,
+    #[graphql(description = "The proof of the account in the storage trie")]
+    pub storage_proof: Vec<Bytes>,
+}
+
+impl From<protocol::types::StateProof> for AccountProof {
+    fn from(proof: protocol::types::StateProof) -> Self {
+        AccountProof {
+            state_root:     MerkleRoot::from(proof.state_root),
+            contract_proof: proof.contract_proof.into_iter().map(Bytes::from).collect(),
+            storage_proof:  proof.storage_proof.into_iter().map(Bytes::from).collect(),
+        }
+    }
+}
+
+#[derive(GraphQLObject, Clone)]
+#[graphql(
+    description = "The merkle proof of an asset. The world state is a two-level MPT, \
+                   the asset is stored in the storage trie of the bank contract \
+                   `0x230000000000000000000000000000000000000002`, keyed by the fixed \
+                   encoded asset id."
+)]
+pub struct AssetProof {
+    #[graphql(description = "The state root which the proof is generated under")]
+    pub state_root: MerkleRoot,
+    #[graphql(
+        description = "The proof of the storage root of the bank contract in the state trie"
+    )]
+    pub contract_proof: Vec<Bytes>,
+    #[graphql(description = "The proof of the asset in the storage trie")]
+    pub storage_proof: Vec<Bytes>,
+}
+
+impl From<protocol::types::StateProof> for AssetProof {
+    fn from(proof: protocol::types::StateProof) -> Self {
+        AssetProof {
+            state_root:     MerkleRoot::from(proof.state_root),
+            contract_proof: proof.contract_proof.into_iter().map(Bytes::from).collect(),
+            storage_proof:  proof.storage_proof.into_iter().map(Bytes::from).collect(),
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use crate::schema::{Address, AssetID, Balance, Bytes, Hash};
+
+#[derive(GraphQLObject, Clone)]
+#[graphql(description = "The state changed by a transaction")]
+pub struct StateDiff {
+    pub tx_hash: Hash,
+    #[graphql(description = "Only the changed balances are listed, including the fee")]
+    pub balances: Vec<BalanceDiff>,
+    #[graphql(
+        description = "Every key written to the storages of the contracts, including the \
+                       native contracts. The storage roots of the contract accounts, which \
+                       are updated when the state is committed, are not listed"
+    )]
+    pub storages: Vec<StorageDiff>,
+}
+
+#[derive(GraphQLObject, Clone)]
+#[graphql(description = "The balance of an asset before and after the transaction")]
+pub struct BalanceDiff {
+    pub address: Address,
+    pub asset_id: AssetID,
+    #[graphql(description = "The balance before the transaction")]
+    pub before: Balance,
+    #[graphql(description = "The balance after the transaction")]
+    pub after: Balance,
+}
+
+#[derive(GraphQLObject, Clone)]
+#[graphql(description = "The fixed encoded value of a key in the storage of a contract")]
+pub struct StorageDiff {
+    #[graphql(description = "The address of the contract")]
+    pub address: Address,
+    #[graphql(description = "The fixed encoded key")]
+    pub key: Bytes,
+    #[graphql(description = "The value before the transaction, empty if the key is new")]
+    pub before: Bytes,
+    #[graphql(description = "The value after the transaction")]
+    pub after: Bytes,
+}
+
+impl From<protocol::types::StateDiff> for StateDiff {
+    fn from(diff: protocol::types::StateDiff) -> Self {
+        StateDiff {
+            tx_hash:  Hash::from(diff.tx_hash),
+            balances: diff.balances.into_iter().map(BalanceDiff::from).collect(),
+            storages: diff.storages.into_iter().map(StorageDiff::from).collect(),
+        }
+    }
+}
+
+impl From<protocol::types::BalanceDiff> for BalanceDiff {
+    fn from(diff: protocol::types::BalanceDiff) -> Self {
+        BalanceDiff {
+            address:  Address::from(diff.address),
+            asset_id: AssetID::from(diff.asset_id),
+            before:   Balance::from(diff.before),
+            after:    Balance::from(diff.after),
+        }
+    }
+}
+
+impl From<protocol::types::StorageDiff> for StorageDiff {
+    fn from(diff: protocol::types::StorageDiff) -> Self {
+        StorageDiff {
+            address: Address::from(diff.address),
+            key:     Bytes::from(diff.key),
+            before:  Bytes::from(diff.before),
+            after:   Bytes::from(diff.after),
+        }
+    }
+}
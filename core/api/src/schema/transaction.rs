@@ -1,3 +1,6 @@
+use protocol::traits::executor::ExecutorReadResp;
+use protocol::types::ReceiptResult;
+
 use crate::schema::{Address, AssetID, Balance, Bytes, Hash, Uint64};
 
 #[derive(GraphQLEnum, Clone)]
@@ -18,6 +21,38 @@ pub enum ContractType {
     Library,
 }
 
+#[derive(GraphQLObject, Clone)]
+#[graphql(description = "The result of executing a transaction without committing it")]
+pub struct SimulateResult {
+    #[graphql(description = "Whether the execution failed")]
+    pub is_error: bool,
+    #[graphql(description = "The return value of the contract call")]
+    pub return_value: Bytes,
+    #[graphql(description = "The reason of the failure")]
+    pub error_message: String,
+    #[graphql(
+        description = "The cycles consumed by the execution, which can be used as the `feeCycle`"
+    )]
+    pub cycles_used: Uint64,
+}
+
+impl From<ExecutorReadResp> for SimulateResult {
+    fn from(resp: ExecutorReadResp) -> Self {
+        let (is_error, return_value, error_message) = match resp.result {
+            ReceiptResult::Call { return_value, .. } => (false, return_value, "".to_owned()),
+            ReceiptResult::Fail { system, .. } => (true, bytes::Bytes::new(), system),
+            _ => (false, bytes::Bytes::new(), "".to_owned()),
+        };
+
+        SimulateResult {
+            is_error,
+            return_value: Bytes::from(return_value),
+            error_message,
+            cycles_used: Uint64::from(resp.cycles_used),
+        }
+    }
+}
+
 // #####################
 // GraphQLInputObject
 // #####################
@@ -78,4 +113,15 @@ pub struct InputDeployAction {
     pub code: Bytes,
     #[graphql(description = "The type of contract")]
     pub contract_type: ContractType,
+    #[graphql(description = "The name of the asset, required by the asset contract")]
+    pub asset_name: Option<String>,
+    #[graphql(description = "The symbol of the asset, required by the asset contract")]
+    pub asset_symbol: Option<String>,
+    #[graphql(
+        description = "The total supply of the asset, which belongs to the deployer, \
+                       required by the asset contract"
+    )]
+    pub asset_supply: Option<Balance>,
+    #[graphql(description = "The decimals of the asset, required by the asset contract")]
+    pub asset_decimals: Option<Uint64>,
 }
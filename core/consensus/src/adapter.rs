@@ -3,21 +3,34 @@ use std::marker::PhantomData;
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use log::debug;
+use bytes::Bytes;
+use futures::future::join_all;
+use log::{debug, info, warn};
 
+use core_executor::state_sync::StateSync;
 use protocol::traits::executor::{ExecutorExecResp, ExecutorFactory, TrieDB};
 use protocol::traits::{
-    ConsensusAdapter, Context, Gossip, MemPool, MessageTarget, MixedTxHashes, NodeInfo, Priority,
-    Rpc, Storage,
+    ConsensusAdapter, Context, Gossip, MemPool, MessageTarget, MixedTxHashes, NodeInfo, PeerTrust,
+    Priority, Rpc, Storage,
 };
 use protocol::types::{
-    Address, Epoch, Hash, MerkleRoot, Proof, Receipt, SignedTransaction, Validator,
+    Address, ChainParams, Epoch, Hash, MerkleRoot, Proof, Receipt, SignedTransaction, StateDiff,
+    Validator, ValidatorSet,
 };
 use protocol::ProtocolResult;
 
-use crate::fixed_types::{ConsensusRpcRequest, ConsensusRpcResponse, PullTxsRequest};
+use crate::fixed_types::{
+    ConsensusRpcRequest, ConsensusRpcResponse, FixedTrieNodes, PullTrieNodesRequest, PullTxsRequest,
+};
 use crate::{ConsensusError, MsgType};
 
+// The trie nodes pulled by a request.
+const TRIE_NODES_BATCH_SIZE: usize = 256;
+// The trie node requests in flight at the same time.
+const MAX_CONCURRENT_TRIE_PULLS: usize = 4;
+// The rounds of requests in a row which save no node before giving up.
+const MAX_STALLED_ROUNDS: usize = 10;
+
 pub struct OverlordConsensusAdapter<
     EF: ExecutorFactory<DB>,
     G: Gossip,
@@ -32,6 +45,9 @@ pub struct OverlordConsensusAdapter<
     storage: Arc<S>,
     trie_db: Arc<DB>,
 
+    // Trace the state diffs of the executed transactions.
+    trace_state: bool,
+
     pin_ef: PhantomData<EF>,
 }
 
@@ -39,7 +55,7 @@ pub struct OverlordConsensusAdapter<
 impl<EF, G, M, R, S, DB> ConsensusAdapter for OverlordConsensusAdapter<EF, G, M, R, S, DB>
 where
     EF: ExecutorFactory<DB>,
-    G: Gossip + Sync + Send,
+    G: Gossip + PeerTrust + Sync + Send,
     R: Rpc + Sync + Send,
     M: MemPool,
     S: Storage,
@@ -109,6 +125,9 @@ where
             cycles_price,
             coinbase,
         )?;
+        if self.trace_state {
+            executor.enable_state_trace();
+        }
         executor.exec(signed_txs)
     }
 
@@ -116,14 +135,37 @@ where
         self.mempool.flush(ctx, txs).await
     }
 
+    fn set_mempool_args(&self, epoch_id: u64, state_root: MerkleRoot, timeout_gap: u64) {
+        self.mempool.set_args(epoch_id, state_root, timeout_gap);
+    }
+
     async fn save_epoch(&self, _ctx: Context, epoch: Epoch) -> ProtocolResult<()> {
         self.storage.insert_epoch(epoch).await
     }
 
+    async fn save_executed_state_root(
+        &self,
+        _ctx: Context,
+        epoch_hash: Hash,
+        state_root: MerkleRoot,
+    ) -> ProtocolResult<()> {
+        self.storage
+            .insert_executed_state_root(epoch_hash, state_root)
+            .await
+    }
+
     async fn save_receipts(&self, _ctx: Context, receipts: Vec<Receipt>) -> ProtocolResult<()> {
         self.storage.insert_receipts(receipts).await
     }
 
+    async fn save_state_diffs(
+        &self,
+        _ctx: Context,
+        state_diffs: Vec<StateDiff>,
+    ) -> ProtocolResult<()> {
+        self.storage.insert_state_diffs(state_diffs).await
+    }
+
     async fn save_proof(&self, _ctx: Context, proof: Proof) -> ProtocolResult<()> {
         self.storage.update_latest_proof(proof).await
     }
@@ -194,6 +236,165 @@ where
         }
     }
 
+    async fn pull_proof(&self, ctx: Context, end: &str) -> ProtocolResult<Proof> {
+        debug!("consensus: send rpc pull proof");
+        let res = self
+            .rpc
+            .call::<ConsensusRpcRequest, ConsensusRpcResponse>(
+                ctx,
+                end,
+                ConsensusRpcRequest::PullProof,
+                Priority::High,
+            )
+            .await?;
+
+        match res {
+            ConsensusRpcResponse::PullProof(proof) => Ok(*proof),
+            _ => Err(ConsensusError::RpcErr(MsgType::RpcPullProof).into()),
+        }
+    }
+
+    async fn pull_epoch_range(
+        &self,
+        ctx: Context,
+        start: u64,
+        stop: u64,
+        end: &str,
+    ) -> ProtocolResult<Vec<(Epoch, Vec<SignedTransaction>)>> {
+        debug!("consensus: send rpc pull epoch range {} to {}", start, stop);
+        let res = self
+            .rpc
+            .call::<ConsensusRpcRequest, ConsensusRpcResponse>(
+                ctx,
+                end,
+                ConsensusRpcRequest::PullEpochRange(start, stop),
+                Priority::High,
+            )
+            .await?;
+
+        match res {
+            ConsensusRpcResponse::PullEpochRange(range) => Ok(range
+                .inner
+                .into_iter()
+                .map(|(epoch, txs)| (epoch, txs.inner))
+                .collect()),
+            _ => Err(ConsensusError::RpcErr(MsgType::RpcPullEpochRange).into()),
+        }
+    }
+
+    async fn pull_headers(
+        &self,
+        ctx: Context,
+        start: u64,
+        stop: u64,
+        end: &str,
+    ) -> ProtocolResult<Vec<Epoch>> {
+        debug!("consensus: send rpc pull headers {} to {}", start, stop);
+        let res = self
+            .rpc
+            .call::<ConsensusRpcRequest, ConsensusRpcResponse>(
+                ctx,
+                end,
+                ConsensusRpcRequest::PullHeaders(start, stop),
+                Priority::High,
+            )
+            .await?;
+
+        match res {
+            ConsensusRpcResponse::PullHeaders(epochs) => Ok(epochs.inner),
+            _ => Err(ConsensusError::RpcErr(MsgType::RpcPullHeaders).into()),
+        }
+    }
+
+    async fn pull_state(
+        &self,
+        peers: Vec<Context>,
+        state_root: MerkleRoot,
+        end: &str,
+    ) -> ProtocolResult<()> {
+        let mut state_sync = StateSync::new(Arc::clone(&self.trie_db), state_root.clone());
+        let mut round = 0;
+        let mut stalled = 0;
+
+        while !peers.is_empty() && stalled < MAX_STALLED_ROUNDS {
+            let mut batches = Vec::new();
+            while batches.len() < MAX_CONCURRENT_TRIE_PULLS {
+                let hashes = state_sync.missing_nodes(TRIE_NODES_BATCH_SIZE)?;
+                if hashes.is_empty() {
+                    break;
+                }
+                batches.push(hashes);
+            }
+            if batches.is_empty() {
+                break;
+            }
+
+            // Each batch goes to another peer, and to the next peers in the
+            // next rounds if it is not returned.
+            let pulls = batches.into_iter().enumerate().map(|(index, hashes)| {
+                let peer = peers[(round + index) % peers.len()].clone();
+                self.rpc.call::<PullTrieNodesRequest, FixedTrieNodes>(
+                    peer,
+                    end,
+                    PullTrieNodesRequest::new(hashes),
+                    Priority::High,
+                )
+            });
+
+            // The nodes of a batch are saved even if some of them are bad, only
+            // the missing ones are pulled again.
+            let saved = state_sync.saved_nodes();
+            for res in join_all(pulls).await.into_iter() {
+                match res {
+                    Ok(nodes) => {
+                        let nodes = nodes.inner.into_iter().map(Bytes::from).collect();
+                        let skipped = state_sync.save_nodes(nodes)?;
+                        if skipped > 0 {
+                            warn!("consensus: skip {} bad trie nodes of the peer", skipped);
+                        }
+                    }
+                    Err(e) => warn!("consensus: pull trie nodes failed, {}", e),
+                }
+            }
+            state_sync.retry_pending();
+
+            if state_sync.saved_nodes() == saved {
+                stalled += 1;
+            } else {
+                stalled = 0;
+            }
+            round += 1;
+            info!(
+                "consensus: pulled {} trie nodes of state {:?}",
+                state_sync.saved_nodes(),
+                state_root
+            );
+        }
+
+        state_sync.finish()?;
+        Ok(())
+    }
+
+    fn report_bad(&self, ctx: Context, reason: &str) {
+        self.network.report_bad(ctx, reason);
+    }
+
+    async fn get_governance(
+        &self,
+        node_info: NodeInfo,
+        state_root: MerkleRoot,
+    ) -> ProtocolResult<(ValidatorSet, ChainParams)> {
+        let executor = EF::from_root(
+            node_info.chain_id,
+            state_root,
+            Arc::clone(&self.trie_db),
+            0,
+            0,
+            Address::User(node_info.self_address),
+        )?;
+        Ok((executor.get_validator_set()?, executor.get_chain_params()?))
+    }
+
     async fn get_epoch_by_id(&self, _ctx: Context, epoch_id: u64) -> ProtocolResult<Epoch> {
         self.storage.get_epoch_by_epoch_id(epoch_id).await
     }
@@ -214,6 +415,7 @@ where
         mempool: Arc<M>,
         storage: Arc<S>,
         trie_db: Arc<DB>,
+        trace_state: bool,
     ) -> Self {
         OverlordConsensusAdapter {
             rpc,
@@ -221,6 +423,7 @@ where
             mempool,
             storage,
             trie_db,
+            trace_state,
 
             pin_ef: PhantomData,
         }
@@ -4,20 +4,24 @@ use async_trait::async_trait;
 use bincode::deserialize;
 use creep::Context;
 use futures::lock::Mutex;
-use log::{debug, info};
-use overlord::types::{AggregatedVote, Node, OverlordMsg, SignedProposal, SignedVote, Status};
+use log::{debug, info, warn};
+use overlord::types::{
+    AggregatedVote, Node, OverlordMsg, SignedProposal, SignedVote, Status, VoteType,
+};
 use overlord::{DurationConfig, Overlord, OverlordHandler};
 use parking_lot::RwLock;
 
-use common_crypto::{PrivateKey, Secp256k1PrivateKey};
+use common_crypto::BLS12381PrivateKey;
 
 use protocol::traits::{Consensus, ConsensusAdapter, CurrentConsensusStatus, NodeInfo};
-use protocol::types::{Address, Hash, Proof, Validator};
-use protocol::{fixed_codec::ProtocolFixedCodec, ProtocolResult};
+use protocol::types::Validator;
+use protocol::ProtocolResult;
 
 use crate::engine::ConsensusEngine;
 use crate::fixed_types::{FixedEpochID, FixedPill, FixedSignedTxs};
-use crate::util::OverlordCrypto;
+use crate::synchronization::{SyncProgress, Synchronization};
+use crate::util::{verify_qc, OverlordCrypto};
+use crate::wal::{ConsensusWal, WalInfo};
 use crate::{ConsensusError, MsgType};
 
 /// Provide consensus
@@ -29,6 +33,10 @@ pub struct OverlordConsensus<Adapter: ConsensusAdapter + 'static> {
     handler: OverlordHandler<FixedPill>,
     /// A consensus engine for synchronous.
     engine: Arc<ConsensusEngine<Adapter>>,
+    /// The synchronization of the epochs committed by others.
+    sync: Synchronization<Adapter>,
+    /// The write-ahead log shared with the engine.
+    wal: Arc<ConsensusWal>,
     /// Synchronization lock.
     lock: Mutex<()>,
 }
@@ -38,6 +46,7 @@ impl<Adapter: ConsensusAdapter + 'static> Consensus for OverlordConsensus<Adapte
     async fn set_proposal(&self, ctx: Context, proposal: Vec<u8>) -> ProtocolResult<()> {
         let signed_proposal: SignedProposal<FixedPill> = rlp::decode(&proposal)
             .map_err(|_| ConsensusError::DecodeErr(MsgType::SignedProposal))?;
+        self.wal.see_proposal(&signed_proposal);
         self.handler
             .send_msg(ctx, OverlordMsg::SignedProposal(signed_proposal))
             .map_err(|e| ConsensusError::OverlordErr(Box::new(e)))?;
@@ -56,6 +65,13 @@ impl<Adapter: ConsensusAdapter + 'static> Consensus for OverlordConsensus<Adapte
     async fn set_qc(&self, ctx: Context, qc: Vec<u8>) -> ProtocolResult<()> {
         let aggregated_vote: AggregatedVote =
             rlp::decode(&qc).map_err(|_| ConsensusError::DecodeErr(MsgType::AggregateVote))?;
+        // Overlord only verifies the signature against the hash it carries, so
+        // a quorum certificate relabeled to another vote is dropped here.
+        verify_qc(&self.engine.get_current_validators(), &aggregated_vote)?;
+        // Only a verified prevote quorum certificate may become the lock.
+        if aggregated_vote.vote_type == VoteType::Prevote {
+            self.wal.see_qc(&aggregated_vote);
+        }
         self.handler
             .send_msg(ctx, OverlordMsg::AggregatedVote(aggregated_vote))
             .map_err(|e| ConsensusError::OverlordErr(Box::new(e)))?;
@@ -63,17 +79,19 @@ impl<Adapter: ConsensusAdapter + 'static> Consensus for OverlordConsensus<Adapte
     }
 
     async fn update_epoch(&self, ctx: Context, msg: Vec<u8>) -> ProtocolResult<()> {
+        // Reveive the rich epoch ID, the peer is recorded for synchronization
+        // even if a synchronization is processing.
+        let epoch_id: FixedEpochID =
+            deserialize(&msg).map_err(|_| ConsensusError::DecodeErr(MsgType::RichEpochID))?;
+        let rich_epoch_id = epoch_id.inner - 1;
+        self.sync.report_peer(ctx.clone(), rich_epoch_id);
+
         let sync_lock = self.lock.try_lock();
         if sync_lock.is_none() {
             // Synchronization is processing.
             return Ok(());
         }
 
-        // Reveive the rich epoch ID.
-        let epoch_id: FixedEpochID =
-            deserialize(&msg).map_err(|_| ConsensusError::DecodeErr(MsgType::RichEpochID))?;
-        let rich_epoch_id = epoch_id.inner - 1;
-
         // TODO: fix to get_epoch_by_epoch_id()
         let current_epoch_id = self
             .engine
@@ -93,82 +111,10 @@ impl<Adapter: ConsensusAdapter + 'static> Consensus for OverlordConsensus<Adapte
 
         info!("self {}, chain {}", current_epoch_id, rich_epoch_id);
         info!("consensus: start synchronization");
-
-        let mut state_root = Hash::from_empty();
-        let mut current_hash = if current_epoch_id != 0 {
-            let current_epoch = self
-                .engine
-                .get_epoch_by_id(ctx.clone(), current_epoch_id)
-                .await?;
-            state_root = current_epoch.header.state_root.clone();
-            let tmp = Hash::digest(current_epoch.encode_fixed()?);
-
-            // Check epoch for the first time.
-            let epoch_header = self
-                .engine
-                .pull_epoch(ctx.clone(), current_epoch_id + 1)
-                .await?
-                .header;
-            self.check_proof(current_epoch_id + 1, epoch_header.proof.clone())?;
-            if tmp != epoch_header.pre_hash {
-                return Err(ConsensusError::SyncEpochHashErr(current_epoch_id + 1).into());
-            }
-            tmp
-        } else {
-            Hash::from_empty()
-        };
-
-        // Start to synchronization.
-        for id in (current_epoch_id + 1)..=rich_epoch_id {
-            info!("consensus: start synchronization epoch {}", id);
-
-            // First pull a new block.
-            debug!("consensus: synchronization pull epoch {}", id);
-            let epoch = self.engine.pull_epoch(ctx.clone(), id).await?;
-
-            // Check proof and previous hash.
-            debug!("consensus: synchronization check proof and previous hash");
-            let proof = epoch.header.proof.clone();
-            self.check_proof(id, proof.clone())?;
-            if id != 1 && current_hash != epoch.header.pre_hash {
-                return Err(ConsensusError::SyncEpochHashErr(id).into());
-            }
-            self.engine.save_proof(ctx.clone(), proof.clone()).await?;
-
-            // Then pull signed transactions.
-            debug!("consensus: synchronization pull signed transactions");
-            let txs = self
-                .engine
-                .pull_txs(ctx.clone(), epoch.ordered_tx_hashes.clone())
-                .await?;
-
-            // After get the signed transactions:
-            // 1. Execute the signed transactions.
-            // 2. Save the signed transactions.
-            // 3. Save the latest proof.
-            // 4. Save the new epoch.
-            // 5. Save the receipt.
-            debug!("consensus: synchronization executor the epoch");
-            let exec_resp = self
-                .engine
-                .exec(
-                    state_root.clone(),
-                    epoch.header.epoch_id,
-                    Address::User(epoch.header.proposer.clone()),
-                    txs.clone(),
-                )
-                .await?;
-            state_root = exec_resp.state_root.clone();
-
-            debug!("consensus: synchronization update the rich status");
-            self.engine
-                .update_status(epoch.header.epoch_id, epoch.clone(), proof, exec_resp, txs)
-                .await?;
-
-            // Update the previous hash and last epoch.
-            info!("consensus: finish synchronization {} epoch", id);
-            current_hash = Hash::digest(epoch.encode_fixed()?);
-        }
+        let rich_epoch_id = self
+            .sync
+            .sync(ctx.clone(), current_epoch_id, rich_epoch_id)
+            .await?;
 
         debug!(
             "consensus: synchronization send overlord rich status {}",
@@ -191,18 +137,26 @@ impl<Adapter: ConsensusAdapter + 'static> OverlordConsensus<Adapter> {
     pub fn new(
         current_consensus_status: CurrentConsensusStatus,
         node_info: NodeInfo,
-        priv_key: Secp256k1PrivateKey,
+        bls_priv_key: BLS12381PrivateKey,
         adapter: Arc<Adapter>,
+        wal: ConsensusWal,
+        fast_sync: bool,
     ) -> Self {
         let current_consensus_status = Arc::new(RwLock::new(current_consensus_status));
+        let wal = Arc::new(wal);
 
         let engine = Arc::new(ConsensusEngine::new(
             Arc::clone(&current_consensus_status),
             node_info.clone(),
             Arc::clone(&adapter),
+            Arc::clone(&wal),
         ));
 
-        let crypto = OverlordCrypto::new(priv_key.pub_key(), priv_key);
+        let crypto = OverlordCrypto::new(
+            node_info.self_address.clone(),
+            bls_priv_key,
+            Arc::clone(&current_consensus_status),
+        );
         let overlord = Overlord::new(
             node_info.self_address.as_bytes(),
             Arc::clone(&engine),
@@ -221,9 +175,20 @@ impl<Adapter: ConsensusAdapter + 'static> OverlordConsensus<Adapter> {
             )
             .unwrap();
 
+        // Replay the log of the epoch interrupted by a crash, so that overlord
+        // gets back the lock and the messages signed by the node.
+        let wal_info = wal.info();
+        if wal_info.epoch_id == current_consensus_status.read().epoch_id {
+            if let Err(e) = replay_wal(&overlord_handler, wal_info) {
+                warn!("consensus: replay the wal failed, {}", e);
+            }
+        }
+
         Self {
             inner: Arc::new(overlord),
             handler: overlord_handler,
+            sync: Synchronization::new(Arc::clone(&engine), fast_sync),
+            wal,
             lock: Mutex::new(()),
             engine,
         }
@@ -242,9 +207,45 @@ impl<Adapter: ConsensusAdapter + 'static> OverlordConsensus<Adapter> {
         Ok(())
     }
 
-    fn check_proof(&self, _epoch_id: u64, _proof: Proof) -> ProtocolResult<()> {
-        Ok(())
+    /// The progress of the running synchronization.
+    pub fn sync_progress(&self) -> Option<SyncProgress> {
+        self.sync.progress()
+    }
+}
+
+fn replay_wal(handler: &OverlordHandler<FixedPill>, wal_info: WalInfo) -> ProtocolResult<()> {
+    info!(
+        "consensus: replay the wal of epoch {} round {}",
+        wal_info.epoch_id, wal_info.round
+    );
+
+    let mut msgs = Vec::new();
+    for bytes in wal_info
+        .lock_proposal
+        .iter()
+        .chain(wal_info.proposals.iter())
+    {
+        let signed_proposal: SignedProposal<FixedPill> =
+            rlp::decode(bytes).map_err(|_| ConsensusError::DecodeErr(MsgType::SignedProposal))?;
+        msgs.push(OverlordMsg::SignedProposal(signed_proposal));
+    }
+    if let Some(bytes) = &wal_info.lock {
+        let aggregated_vote: AggregatedVote =
+            rlp::decode(bytes).map_err(|_| ConsensusError::DecodeErr(MsgType::AggregateVote))?;
+        msgs.push(OverlordMsg::AggregatedVote(aggregated_vote));
+    }
+    for bytes in wal_info.votes.iter() {
+        let signed_vote: SignedVote =
+            rlp::decode(bytes).map_err(|_| ConsensusError::DecodeErr(MsgType::SignedVote))?;
+        msgs.push(OverlordMsg::SignedVote(signed_vote));
+    }
+
+    for msg in msgs.into_iter() {
+        handler
+            .send_msg(Context::new(), msg)
+            .map_err(|e| ConsensusError::OverlordErr(Box::new(e)))?;
     }
+    Ok(())
 }
 
 fn gen_overlord_status(epoch_id: u64, interval: u64, validators: Vec<Validator>) -> Status {
@@ -26,8 +26,9 @@ use protocol::{ProtocolError, ProtocolResult};
 use crate::fixed_types::{FixedEpochID, FixedPill, FixedSignedTxs};
 use crate::message::{
     END_GOSSIP_AGGREGATED_VOTE, END_GOSSIP_RICH_EPOCH_ID, END_GOSSIP_SIGNED_PROPOSAL,
-    END_GOSSIP_SIGNED_VOTE, RPC_SYNC_PULL,
+    END_GOSSIP_SIGNED_VOTE, RPC_SYNC_PULL, RPC_SYNC_PULL_STATE,
 };
+use crate::wal::ConsensusWal;
 use crate::ConsensusError;
 
 /// validator is for create new epoch, and authority is for build overlord
@@ -38,6 +39,7 @@ pub struct ConsensusEngine<Adapter> {
     exemption_hash:           RwLock<HashSet<Bytes>>,
 
     adapter:  Arc<Adapter>,
+    wal:      Arc<ConsensusWal>,
     pub lock: Mutex<()>,
 }
 
@@ -76,7 +78,7 @@ impl<Adapter: ConsensusAdapter + 'static> Engine<FixedPill, FixedSignedTxs>
             cycles_used:       current_consensus_status.cycles_used,
             proposer:          self.node_info.self_address.clone(),
             proof:             current_consensus_status.proof.clone(),
-            validator_version: 0u64,
+            validator_version: current_consensus_status.validator_version,
             validators:        current_consensus_status.validators.clone(),
         };
         let epoch = Epoch {
@@ -91,7 +93,10 @@ impl<Adapter: ConsensusAdapter + 'static> Engine<FixedPill, FixedSignedTxs>
         let fixed_pill = FixedPill {
             inner: pill.clone(),
         };
-        let hash = Hash::digest(pill.encode_fixed()?).as_bytes();
+        // Overlord votes for the hash of the epoch rather than the pill, so that
+        // the proof can be verified by the synchronizing nodes, which do not
+        // get the propose hashes.
+        let hash = Hash::digest(pill.epoch.encode_fixed()?).as_bytes();
         let mut set = self.exemption_hash.write();
         set.insert(hash.clone());
 
@@ -105,6 +110,28 @@ impl<Adapter: ConsensusAdapter + 'static> Engine<FixedPill, FixedSignedTxs>
         hash: Bytes,
         epoch: FixedPill,
     ) -> Result<FixedSignedTxs, Box<dyn Error + Send>> {
+        if Hash::digest(epoch.inner.epoch.encode_fixed()?).as_bytes() != hash {
+            return Err(ProtocolError::from(ConsensusError::Other(
+                "epoch hash mismatch".to_string(),
+            ))
+            .into());
+        }
+
+        // The header carries the validators and the state root after executing
+        // the previous epoch, they must be the same as the local status.
+        {
+            let header = &epoch.inner.epoch.header;
+            let status = self.current_consensus_status.read();
+            if header.validator_version != status.validator_version
+                || header.validators != status.validators
+                || header.state_root != status.state_root
+            {
+                return Err(
+                    ProtocolError::from(ConsensusError::EpochStateErr(header.epoch_id)).into(),
+                );
+            }
+        }
+
         let order_hashes = epoch.get_ordered_hashes();
         let exemption = {
             let set = self.exemption_hash.read();
@@ -217,11 +244,13 @@ impl<Adapter: ConsensusAdapter + 'static> Engine<FixedPill, FixedSignedTxs>
     ) -> Result<(), Box<dyn Error + Send>> {
         let (end, msg) = match msg {
             OverlordMsg::SignedProposal(sp) => {
+                self.wal.save_proposal(&sp)?;
                 let bytes = sp.rlp_bytes();
                 (END_GOSSIP_SIGNED_PROPOSAL, bytes)
             }
 
             OverlordMsg::AggregatedVote(av) => {
+                self.wal.see_qc(&av);
                 let bytes = av.rlp_bytes();
                 (END_GOSSIP_AGGREGATED_VOTE, bytes)
             }
@@ -242,7 +271,12 @@ impl<Adapter: ConsensusAdapter + 'static> Engine<FixedPill, FixedSignedTxs>
         msg: OverlordMsg<FixedPill>,
     ) -> Result<(), Box<dyn Error + Send>> {
         let msg = match msg {
-            OverlordMsg::SignedVote(sv) => sv.rlp_bytes(),
+            OverlordMsg::SignedVote(sv) => {
+                // Save the vote before it is sent, a restarted node must not
+                // sign another one in the same round.
+                self.wal.save_vote(&sv)?;
+                sv.rlp_bytes()
+            }
             _ => unreachable!(),
         };
 
@@ -285,12 +319,14 @@ impl<Adapter: ConsensusAdapter + 'static> ConsensusEngine<Adapter> {
         current_consensus_status: Arc<RwLock<CurrentConsensusStatus>>,
         node_info: NodeInfo,
         adapter: Arc<Adapter>,
+        wal: Arc<ConsensusWal>,
     ) -> Self {
         Self {
             current_consensus_status,
             node_info,
             exemption_hash: RwLock::new(HashSet::new()),
             adapter,
+            wal,
             lock: Mutex::new(()),
         }
     }
@@ -299,16 +335,44 @@ impl<Adapter: ConsensusAdapter + 'static> ConsensusEngine<Adapter> {
         self.adapter.get_current_epoch_id(ctx).await
     }
 
-    pub async fn pull_epoch(&self, ctx: Context, epoch_id: u64) -> ProtocolResult<Epoch> {
-        self.adapter.pull_epoch(ctx, epoch_id, RPC_SYNC_PULL).await
+    pub async fn pull_proof(&self, ctx: Context) -> ProtocolResult<Proof> {
+        self.adapter.pull_proof(ctx, RPC_SYNC_PULL).await
     }
 
-    pub async fn pull_txs(
+    pub async fn pull_epoch_range(
         &self,
         ctx: Context,
-        hashes: Vec<Hash>,
-    ) -> ProtocolResult<Vec<SignedTransaction>> {
-        self.adapter.pull_txs(ctx, hashes, RPC_SYNC_PULL).await
+        start: u64,
+        stop: u64,
+    ) -> ProtocolResult<Vec<(Epoch, Vec<SignedTransaction>)>> {
+        self.adapter
+            .pull_epoch_range(ctx, start, stop, RPC_SYNC_PULL)
+            .await
+    }
+
+    pub async fn pull_headers(
+        &self,
+        ctx: Context,
+        start: u64,
+        stop: u64,
+    ) -> ProtocolResult<Vec<Epoch>> {
+        self.adapter
+            .pull_headers(ctx, start, stop, RPC_SYNC_PULL)
+            .await
+    }
+
+    pub async fn pull_state(
+        &self,
+        peers: Vec<Context>,
+        state_root: MerkleRoot,
+    ) -> ProtocolResult<()> {
+        self.adapter
+            .pull_state(peers, state_root, RPC_SYNC_PULL_STATE)
+            .await
+    }
+
+    pub fn report_bad(&self, ctx: Context, reason: &str) {
+        self.adapter.report_bad(ctx, reason);
     }
 
     pub async fn get_epoch_by_id(&self, ctx: Context, epoch_id: u64) -> ProtocolResult<Epoch> {
@@ -341,8 +405,11 @@ impl<Adapter: ConsensusAdapter + 'static> ConsensusEngine<Adapter> {
     /// 1. Execute the signed transactions.
     /// 2. Save the signed transactions.
     /// 3. Save the latest proof.
-    /// 4. Save the new epoch.
-    /// 5. Save the receipt.
+    /// 4. Save the state root after executing the epoch.
+    /// 5. Save the new epoch.
+    /// 6. Save the receipt.
+    /// 7. Save the state diffs if the state is traced.
+    /// 8. Clear the consensus wal for the next epoch.
     pub async fn update_status(
         &self,
         epoch_id: u64,
@@ -355,24 +422,52 @@ impl<Adapter: ConsensusAdapter + 'static> ConsensusEngine<Adapter> {
         self.adapter
             .save_receipts(Context::new(), exec_resp.receipts.clone())
             .await?;
+        // Save state diffs
+        if !exec_resp.state_diffs.is_empty() {
+            self.adapter
+                .save_state_diffs(Context::new(), exec_resp.state_diffs.clone())
+                .await?;
+        }
         // Save signed transactions
         self.adapter.save_signed_txs(Context::new(), txs).await?;
 
+        // Save the state root after executing the epoch before the epoch, so
+        // that the latest epoch always has it after a restart.
+        let prev_hash = Hash::digest(epoch.encode_fixed()?);
+        self.adapter
+            .save_executed_state_root(
+                Context::new(),
+                prev_hash.clone(),
+                exec_resp.state_root.clone(),
+            )
+            .await?;
+
         // Save the epoch.
         self.adapter
             .save_epoch(Context::new(), epoch.clone())
             .await?;
 
-        let prev_hash = Hash::digest(epoch.encode_fixed()?);
         {
             let mut current_consensus_status = self.current_consensus_status.write();
             current_consensus_status.epoch_id = epoch_id + 1;
             current_consensus_status.prev_hash = prev_hash;
             current_consensus_status.proof = proof;
 
+            // Update the validators, they take effect from the next epoch.
+            current_consensus_status.validator_version = exec_resp.validator_set.version;
+            current_consensus_status.validators = exec_resp.validator_set.validators.clone();
+
+            // Update the chain params kept in the state.
+            current_consensus_status.cycles_limit = exec_resp.params.cycles_limit;
+            current_consensus_status.cycles_price = exec_resp.params.cycles_price;
+            current_consensus_status.consensus_interval = exec_resp.params.interval;
+
             // Update state root
             current_consensus_status.state_root = exec_resp.state_root.clone();
 
+            // Update logs bloom
+            current_consensus_status.logs_bloom = exec_resp.logs_bloom.clone();
+
             // Update order root
             let ordered_root = Merkle::from_hashes(epoch.ordered_tx_hashes.clone())
                 .get_root_hash()
@@ -396,6 +491,56 @@ impl<Adapter: ConsensusAdapter + 'static> ConsensusEngine<Adapter> {
                 vec![receipt_root]
             };
         }
+
+        // The mempool checks the new transactions against the latest state.
+        self.adapter.set_mempool_args(
+            epoch_id,
+            exec_resp.state_root.clone(),
+            exec_resp.params.timeout_gap,
+        );
+
+        self.wal.reset(epoch_id + 1)?;
+        Ok(())
+    }
+
+    /// Take the pulled state of an epoch header as if the previous epoch was
+    /// committed. The validators and the chain params kept in the state must
+    /// match the header.
+    pub async fn fast_forward(&self, epoch: &Epoch) -> ProtocolResult<()> {
+        let header = &epoch.header;
+        let (validator_set, params) = self
+            .adapter
+            .get_governance(self.node_info.clone(), header.state_root.clone())
+            .await?;
+        if validator_set.version != header.validator_version
+            || validator_set.validators != header.validators
+        {
+            return Err(ConsensusError::SyncStateErr(header.epoch_id).into());
+        }
+
+        {
+            let mut current_consensus_status = self.current_consensus_status.write();
+            current_consensus_status.epoch_id = header.epoch_id;
+            current_consensus_status.prev_hash = header.pre_hash.clone();
+            current_consensus_status.proof = header.proof.clone();
+
+            current_consensus_status.validator_version = validator_set.version;
+            current_consensus_status.validators = validator_set.validators;
+
+            current_consensus_status.cycles_limit = params.cycles_limit;
+            current_consensus_status.cycles_price = params.cycles_price;
+            current_consensus_status.consensus_interval = params.interval;
+
+            current_consensus_status.state_root = header.state_root.clone();
+        }
+
+        self.adapter.set_mempool_args(
+            header.epoch_id - 1,
+            header.state_root.clone(),
+            params.timeout_gap,
+        );
+
+        self.wal.reset(header.epoch_id)?;
         Ok(())
     }
 
@@ -408,6 +553,11 @@ impl<Adapter: ConsensusAdapter + 'static> ConsensusEngine<Adapter> {
         current_consensus_status.consensus_interval
     }
 
+    pub fn get_current_validators(&self) -> Vec<Validator> {
+        let current_consensus_status = self.current_consensus_status.read();
+        current_consensus_status.validators.clone()
+    }
+
     pub fn get_current_authority_list(&self) -> Vec<Node> {
         let current_consensus_status = self.current_consensus_status.read();
         covert_to_overlord_authority(&current_consensus_status.validators)
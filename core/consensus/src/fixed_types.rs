@@ -7,7 +7,7 @@ use overlord::Codec;
 
 use protocol::codec::{Deserialize, Serialize};
 use protocol::traits::MessageCodec;
-use protocol::types::{Epoch, Hash, Pill, SignedTransaction};
+use protocol::types::{Epoch, Hash, Pill, Proof, SignedTransaction};
 use protocol::{fixed_codec::ProtocolFixedCodec, ProtocolResult};
 
 use crate::{ConsensusError, MsgType};
@@ -16,12 +16,22 @@ use crate::{ConsensusError, MsgType};
 pub enum ConsensusRpcRequest {
     PullEpochs(u64),
     PullTxs(PullTxsRequest),
+    PullProof,
+    // Pull the epochs between the two epoch IDs inclusively with their
+    // transactions.
+    PullEpochRange(u64, u64),
+    // Pull the epochs between the two epoch IDs inclusively without their
+    // transactions.
+    PullHeaders(u64, u64),
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ConsensusRpcResponse {
     PullEpochs(Box<Epoch>),
     PullTxs(Box<FixedSignedTxs>),
+    PullProof(Box<Proof>),
+    PullEpochRange(Box<FixedEpochRange>),
+    PullHeaders(Box<FixedEpochs>),
 }
 
 #[async_trait]
@@ -41,6 +51,24 @@ impl MessageCodec for ConsensusRpcResponse {
                 tmp.extend_from_slice(b"b");
                 tmp
             }
+
+            ConsensusRpcResponse::PullProof(proof) => {
+                let mut tmp = proof.encode_fixed()?;
+                tmp.extend_from_slice(b"c");
+                tmp
+            }
+
+            ConsensusRpcResponse::PullEpochRange(range) => {
+                let mut tmp = range.encode()?;
+                tmp.extend_from_slice(b"d");
+                tmp
+            }
+
+            ConsensusRpcResponse::PullHeaders(epochs) => {
+                let mut tmp = epochs.encode()?;
+                tmp.extend_from_slice(b"e");
+                tmp
+            }
         };
         Ok(bytes)
     }
@@ -60,6 +88,21 @@ impl MessageCodec for ConsensusRpcResponse {
                     .map_err(|_| ConsensusError::DecodeErr(MsgType::RpcPullTxs))?;
                 Ok(ConsensusRpcResponse::PullTxs(Box::new(res)))
             }
+
+            b"c" => {
+                let res: Proof = ProtocolFixedCodec::decode_fixed(bytes)?;
+                Ok(ConsensusRpcResponse::PullProof(Box::new(res)))
+            }
+
+            b"d" => {
+                let res = FixedEpochRange::decode(bytes)?;
+                Ok(ConsensusRpcResponse::PullEpochRange(Box::new(res)))
+            }
+
+            b"e" => {
+                let res = FixedEpochs::decode(bytes)?;
+                Ok(ConsensusRpcResponse::PullHeaders(Box::new(res)))
+            }
             _ => unreachable!(),
         }
     }
@@ -140,6 +183,100 @@ impl FixedSignedTxs {
     }
 }
 
+/// Successive epochs with their ordered transactions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FixedEpochRange {
+    pub inner: Vec<(Epoch, FixedSignedTxs)>,
+}
+
+impl FixedEpochRange {
+    pub fn new(inner: Vec<(Epoch, FixedSignedTxs)>) -> Self {
+        FixedEpochRange { inner }
+    }
+
+    // The epochs are fixed encoded, the transactions are serialized along.
+    fn encode(&self) -> ProtocolResult<Bytes> {
+        let mut items = Vec::with_capacity(self.inner.len());
+        for (epoch, txs) in self.inner.iter() {
+            items.push((epoch.encode_fixed()?.to_vec(), txs));
+        }
+
+        let bytes =
+            serialize(&items).map_err(|_| ConsensusError::EncodeErr(MsgType::RpcPullEpochRange))?;
+        Ok(Bytes::from(bytes))
+    }
+
+    fn decode(bytes: Bytes) -> ProtocolResult<Self> {
+        let items: Vec<(Vec<u8>, FixedSignedTxs)> = deserialize(&bytes)
+            .map_err(|_| ConsensusError::DecodeErr(MsgType::RpcPullEpochRange))?;
+
+        let mut inner = Vec::with_capacity(items.len());
+        for (epoch, txs) in items.into_iter() {
+            let epoch: Epoch = ProtocolFixedCodec::decode_fixed(Bytes::from(epoch))?;
+            inner.push((epoch, txs));
+        }
+        Ok(FixedEpochRange { inner })
+    }
+}
+
+/// Successive epochs without their transactions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FixedEpochs {
+    pub inner: Vec<Epoch>,
+}
+
+impl FixedEpochs {
+    pub fn new(inner: Vec<Epoch>) -> Self {
+        FixedEpochs { inner }
+    }
+
+    fn encode(&self) -> ProtocolResult<Bytes> {
+        let mut items = Vec::with_capacity(self.inner.len());
+        for epoch in self.inner.iter() {
+            items.push(epoch.encode_fixed()?.to_vec());
+        }
+
+        let bytes =
+            serialize(&items).map_err(|_| ConsensusError::EncodeErr(MsgType::RpcPullHeaders))?;
+        Ok(Bytes::from(bytes))
+    }
+
+    fn decode(bytes: Bytes) -> ProtocolResult<Self> {
+        let items: Vec<Vec<u8>> =
+            deserialize(&bytes).map_err(|_| ConsensusError::DecodeErr(MsgType::RpcPullHeaders))?;
+
+        let mut inner = Vec::with_capacity(items.len());
+        for epoch in items.into_iter() {
+            inner.push(ProtocolFixedCodec::decode_fixed(Bytes::from(epoch))?);
+        }
+        Ok(FixedEpochs { inner })
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PullTrieNodesRequest {
+    #[serde(with = "core_network::serde_multi")]
+    pub inner: Vec<Hash>,
+}
+
+impl PullTrieNodesRequest {
+    pub fn new(inner: Vec<Hash>) -> Self {
+        PullTrieNodesRequest { inner }
+    }
+}
+
+/// The encoded trie nodes of a state, see `core_executor::state_sync`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FixedTrieNodes {
+    pub inner: Vec<Vec<u8>>,
+}
+
+impl FixedTrieNodes {
+    pub fn new(inner: Vec<Vec<u8>>) -> Self {
+        FixedTrieNodes { inner }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::convert::From;
@@ -155,7 +292,7 @@ mod test {
         TransactionAction, UserAddress,
     };
 
-    use super::{ConsensusRpcResponse, FixedSignedTxs};
+    use super::{ConsensusRpcResponse, FixedEpochRange, FixedEpochs, FixedSignedTxs};
 
     fn gen_epoch(epoch_id: u64, epoch_hash: Hash) -> Epoch {
         let nonce = Hash::digest(Bytes::from("XXXX"));
@@ -265,5 +402,33 @@ mod test {
         let bytes = origin.encode().await.unwrap();
         let res: ConsensusRpcResponse = MessageCodec::decode(bytes).await.unwrap();
         assert_eq!(origin, res);
+
+        let proof = mock_proof(Hash::digest(Bytes::from("epoch")));
+        let mut origin = ConsensusRpcResponse::PullProof(Box::new(proof));
+        let bytes = origin.encode().await.unwrap();
+        let res: ConsensusRpcResponse = MessageCodec::decode(bytes).await.unwrap();
+        assert_eq!(origin, res);
+
+        let range = (1..5)
+            .map(|id| {
+                let txs = FixedSignedTxs {
+                    inner: (0..10).map(|_| gen_signed_tx()).collect::<Vec<_>>(),
+                };
+                (gen_epoch(id, Hash::from_empty()), txs)
+            })
+            .collect::<Vec<_>>();
+        let mut origin =
+            ConsensusRpcResponse::PullEpochRange(Box::new(FixedEpochRange::new(range)));
+        let bytes = origin.encode().await.unwrap();
+        let res: ConsensusRpcResponse = MessageCodec::decode(bytes).await.unwrap();
+        assert_eq!(origin, res);
+
+        let epochs = (1..5)
+            .map(|id| gen_epoch(id, Hash::from_empty()))
+            .collect::<Vec<_>>();
+        let mut origin = ConsensusRpcResponse::PullHeaders(Box::new(FixedEpochs::new(epochs)));
+        let bytes = origin.encode().await.unwrap();
+        let res: ConsensusRpcResponse = MessageCodec::decode(bytes).await.unwrap();
+        assert_eq!(origin, res);
     }
 }
@@ -4,7 +4,9 @@ pub mod adapter;
 pub mod consensus;
 pub mod fixed_types;
 pub mod message;
+pub mod synchronization;
 pub mod util;
+pub mod wal;
 
 pub use overlord::DurationConfig;
 
@@ -14,7 +16,7 @@ use derive_more::{Display, From};
 
 use common_crypto::CryptoError;
 
-use protocol::types::Hash;
+use protocol::types::{Hash, UserAddress};
 use protocol::{ProtocolError, ProtocolErrorKind};
 
 #[derive(Clone, Debug, Display, PartialEq, Eq)]
@@ -36,6 +38,15 @@ pub enum MsgType {
 
     #[display(fmt = "Rpc Pull Transactions")]
     RpcPullTxs,
+
+    #[display(fmt = "Rpc Pull Proof")]
+    RpcPullProof,
+
+    #[display(fmt = "Rpc Pull Epoch Range")]
+    RpcPullEpochRange,
+
+    #[display(fmt = "Rpc Pull Headers")]
+    RpcPullHeaders,
 }
 
 /// Consensus errors defines here.
@@ -73,6 +84,32 @@ pub enum ConsensusError {
     #[display(fmt = "Crypto error {:?}", _0)]
     CryptoErr(Box<CryptoError>),
 
+    /// The signature is malformed or mismatches the hash or the voters.
+    #[display(fmt = "Invalid signature {}", _0)]
+    InvalidSignature(String),
+
+    /// The signer is not a validator.
+    #[display(fmt = "Voter {:?} is not a validator", _0)]
+    UnknownVoter(UserAddress),
+
+    /// The voters of an aggregated signature hold no more than 2/3 of the
+    /// vote weight.
+    #[display(fmt = "Voters hold {} of {} vote weight", _0, _1)]
+    NoQuorum(u64, u64),
+
+    /// The node refuses to sign another proposal or vote in a round.
+    #[display(fmt = "Refuse to sign twice in round {} of epoch {}", _1, _0)]
+    DoubleSign(u64, u64),
+
+    /// Read or write the consensus wal failed.
+    #[display(fmt = "Consensus wal error {}", _0)]
+    WalErr(String),
+
+    /// The validators or the state root carried by the epoch header mismatch
+    /// the local status.
+    #[display(fmt = "Epoch {} state mismatch", _0)]
+    EpochStateErr(u64),
+
     /// The synchronous epoch does not pass the checks.
     #[display(fmt = "Synchronization {} epoch error", _0)]
     SyncEpochHashErr(u64),
@@ -81,6 +118,14 @@ pub enum ConsensusError {
     #[display(fmt = "Synchronization {} proof error", _0)]
     SyncEpochProofErr(u64),
 
+    /// The synchronous transactions mismatch the epoch.
+    #[display(fmt = "Synchronization {} transactions error", _0)]
+    SyncTxsErr(u64),
+
+    /// The state pulled for the epoch mismatches the epoch header.
+    #[display(fmt = "Synchronization {} state error", _0)]
+    SyncStateErr(u64),
+
     /// The Rpc response mismatch the request.
     #[display(fmt = "Synchronization Rpc {:?} message mismatch", _0)]
     RpcErr(MsgType),
@@ -8,10 +8,15 @@ use overlord::Codec;
 use rlp::Encodable;
 use serde::{Deserialize, Serialize};
 
+use core_executor::state_sync::get_trie_nodes;
+use protocol::traits::executor::TrieDB;
 use protocol::traits::{Consensus, Context, MessageHandler, Priority, Rpc, Storage};
 use protocol::ProtocolResult;
 
-use crate::fixed_types::{ConsensusRpcRequest, ConsensusRpcResponse, FixedEpochID, FixedSignedTxs};
+use crate::fixed_types::{
+    ConsensusRpcRequest, ConsensusRpcResponse, FixedEpochID, FixedEpochRange, FixedEpochs,
+    FixedSignedTxs, FixedTrieNodes, PullTrieNodesRequest,
+};
 
 pub const END_GOSSIP_SIGNED_PROPOSAL: &str = "/gossip/consensus/signed_proposal";
 pub const END_GOSSIP_SIGNED_VOTE: &str = "/gossip/consensus/signed_vote";
@@ -19,6 +24,15 @@ pub const END_GOSSIP_AGGREGATED_VOTE: &str = "/gossip/consensus/qc";
 pub const END_GOSSIP_RICH_EPOCH_ID: &str = "/gossip/consensus/rich_epoch_id";
 pub const RPC_SYNC_PULL: &str = "/rpc_call/consensus/sync_pull";
 pub const RPC_RESP_SYNC_PULL: &str = "/rpc_resp/consensus/sync_pull";
+pub const RPC_SYNC_PULL_STATE: &str = "/rpc_call/consensus/sync_pull_state";
+pub const RPC_RESP_SYNC_PULL_STATE: &str = "/rpc_resp/consensus/sync_pull_state";
+
+// The most epochs returned by an epoch range request.
+const MAX_EPOCH_RANGE: u64 = 50;
+// The most epochs returned by a header request.
+const MAX_HEADERS_RANGE: u64 = 500;
+// The most trie nodes returned by a trie node request.
+const MAX_TRIE_NODES: usize = 1024;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct Proposal(pub Vec<u8>);
@@ -173,6 +187,68 @@ impl<R: Rpc + 'static, S: Storage + 'static> MessageHandler for RpcHandler<R, S>
                     )
                     .await
             }
+
+            ConsensusRpcRequest::PullProof => {
+                let res = self.storage.get_latest_proof().await?;
+
+                self.rpc
+                    .response(
+                        ctx,
+                        RPC_RESP_SYNC_PULL,
+                        ConsensusRpcResponse::PullProof(Box::new(res)),
+                        Priority::High,
+                    )
+                    .await
+            }
+
+            ConsensusRpcRequest::PullEpochRange(start, stop) => {
+                debug!("message: get rpc pull epoch range {} to {}", start, stop);
+                let latest = self.storage.get_latest_epoch().await?.header.epoch_id;
+                let stop = stop
+                    .min(latest)
+                    .min(start.saturating_add(MAX_EPOCH_RANGE - 1));
+
+                let mut res = Vec::new();
+                for epoch_id in start..=stop {
+                    let epoch = self.storage.get_epoch_by_epoch_id(epoch_id).await?;
+                    let txs = self
+                        .storage
+                        .get_transactions(epoch.ordered_tx_hashes.clone())
+                        .await?;
+                    res.push((epoch, FixedSignedTxs::new(txs)));
+                }
+
+                self.rpc
+                    .response(
+                        ctx,
+                        RPC_RESP_SYNC_PULL,
+                        ConsensusRpcResponse::PullEpochRange(Box::new(FixedEpochRange::new(res))),
+                        Priority::High,
+                    )
+                    .await
+            }
+
+            ConsensusRpcRequest::PullHeaders(start, stop) => {
+                debug!("message: get rpc pull headers {} to {}", start, stop);
+                let latest = self.storage.get_latest_epoch().await?.header.epoch_id;
+                let stop = stop
+                    .min(latest)
+                    .min(start.saturating_add(MAX_HEADERS_RANGE - 1));
+
+                let mut res = Vec::new();
+                for epoch_id in start..=stop {
+                    res.push(self.storage.get_epoch_by_epoch_id(epoch_id).await?);
+                }
+
+                self.rpc
+                    .response(
+                        ctx,
+                        RPC_RESP_SYNC_PULL,
+                        ConsensusRpcResponse::PullHeaders(Box::new(FixedEpochs::new(res))),
+                        Priority::High,
+                    )
+                    .await
+            }
         }
     }
 }
@@ -186,3 +262,42 @@ where
         RpcHandler { rpc, storage }
     }
 }
+
+/// Serve the trie nodes of the committed states to the nodes in fast sync.
+pub struct StateRpcHandler<R, DB> {
+    rpc:     Arc<R>,
+    trie_db: Arc<DB>,
+}
+
+#[async_trait]
+impl<R: Rpc + 'static, DB: TrieDB + 'static> MessageHandler for StateRpcHandler<R, DB> {
+    type Message = PullTrieNodesRequest;
+
+    async fn process(&self, ctx: Context, msg: PullTrieNodesRequest) -> ProtocolResult<()> {
+        debug!("message: get rpc pull {} trie nodes", msg.inner.len());
+        let hashes = &msg.inner[..msg.inner.len().min(MAX_TRIE_NODES)];
+        let nodes = get_trie_nodes(self.trie_db.as_ref(), hashes)?
+            .into_iter()
+            .map(|node| node.to_vec())
+            .collect::<Vec<_>>();
+
+        self.rpc
+            .response(
+                ctx,
+                RPC_RESP_SYNC_PULL_STATE,
+                FixedTrieNodes::new(nodes),
+                Priority::High,
+            )
+            .await
+    }
+}
+
+impl<R, DB> StateRpcHandler<R, DB>
+where
+    R: Rpc + 'static,
+    DB: TrieDB + 'static,
+{
+    pub fn new(rpc: Arc<R>, trie_db: Arc<DB>) -> Self {
+        StateRpcHandler { rpc, trie_db }
+    }
+}
@@ -0,0 +1,903 @@
+use std::sync::Arc;
+use std::time::Instant;
+
+use futures::stream::{self, StreamExt};
+use log::{debug, info, warn};
+use overlord::types::VoteType;
+use parking_lot::RwLock;
+
+use protocol::fixed_codec::ProtocolFixedCodec;
+use protocol::traits::{ConsensusAdapter, Context};
+use protocol::types::{Address, Epoch, Hash, MerkleRoot, Proof, SignedTransaction, Validator};
+use protocol::{ProtocolError, ProtocolResult};
+
+use crate::engine::ConsensusEngine;
+use crate::util::{verify_aggregated_signature, vote_hash};
+use crate::ConsensusError;
+
+// The epochs pulled by a range request.
+const SYNC_BATCH_SIZE: u64 = 20;
+// The range requests in flight at the same time.
+const MAX_CONCURRENT_PULLS: usize = 4;
+// The attempts to pull a range, each of which goes to the next peer.
+const MAX_PULL_ATTEMPTS: usize = 5;
+// The epochs pulled by a header request in a fast sync.
+const HEADERS_BATCH_SIZE: u64 = 200;
+// The least epochs to skip by a fast sync, fewer epochs are executed.
+const FAST_SYNC_MIN_EPOCHS: u64 = 100;
+
+/// The progress of a synchronization.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SyncProgress {
+    /// The latest epoch ID before the synchronization.
+    pub start: u64,
+    /// The latest epoch ID executed.
+    pub current: u64,
+    /// The epoch ID to synchronize to.
+    pub target: u64,
+}
+
+// A peer to pull from, the ID tells the recorded peers apart.
+#[derive(Clone)]
+struct SyncPeer {
+    id:  u64,
+    ctx: Context,
+}
+
+impl SyncPeer {
+    // The peer which is not recorded, dropping it changes nothing.
+    fn unrecorded(ctx: Context) -> Self {
+        SyncPeer { id: 0, ctx }
+    }
+}
+
+// The peer which serves an epoch or a proof failing the check.
+struct BadPeer {
+    peer: SyncPeer,
+    err:  ProtocolError,
+}
+
+impl BadPeer {
+    fn new(peer: &SyncPeer, err: ProtocolError) -> Self {
+        BadPeer {
+            peer: peer.clone(),
+            err,
+        }
+    }
+}
+
+// The peers which gossip the latest rich epoch IDs. Every validator gossips
+// the rich epoch ID once when it commits an epoch, so the contexts of the same
+// epoch ID come from different peers. The peers of the previous epoch ID are
+// kept until the latest one is reported by as many peers.
+#[derive(Default)]
+struct SyncPeers {
+    epoch_id: u64,
+    last_id:  u64,
+    current:  Vec<SyncPeer>,
+    previous: Vec<SyncPeer>,
+}
+
+impl SyncPeers {
+    fn report(&mut self, ctx: Context, epoch_id: u64) {
+        if epoch_id < self.epoch_id {
+            return;
+        }
+
+        self.last_id += 1;
+        let peer = SyncPeer {
+            id: self.last_id,
+            ctx,
+        };
+        if epoch_id > self.epoch_id {
+            self.epoch_id = epoch_id;
+            self.previous = std::mem::replace(&mut self.current, vec![peer]);
+        } else {
+            self.current.push(peer);
+        }
+    }
+
+    // Drop a peer which serves a bad epoch, it is pulled from again only if
+    // it reports another rich epoch ID.
+    fn drop_peer(&mut self, id: u64) {
+        self.current.retain(|peer| peer.id != id);
+        self.previous.retain(|peer| peer.id != id);
+    }
+
+    fn peers(&self) -> Vec<SyncPeer> {
+        if self.current.len() >= self.previous.len() {
+            self.current.clone()
+        } else {
+            self.previous.clone()
+        }
+    }
+}
+
+/// Synchronize the epochs committed by the other validators.
+///
+/// The epochs are pulled in ranges with their transactions, several ranges
+/// at a time from different peers, while the pulled epochs are checked and
+/// executed in order. Every epoch is saved once it is executed, so an
+/// interrupted synchronization goes on from the latest saved epoch.
+///
+/// In a fast sync, the headers are pulled and checked first, then the state
+/// of a recent header is pulled instead of executing the epochs before it.
+/// The validators changed by an epoch are only known by executing it, so the
+/// headers are trusted until the validators change and the epochs after are
+/// executed. Nothing is saved until the state is complete, and the trie
+/// nodes pulled are kept, so an interrupted fast sync goes on from where it
+/// stopped.
+pub struct Synchronization<Adapter: ConsensusAdapter + 'static> {
+    engine:    Arc<ConsensusEngine<Adapter>>,
+    peers:     RwLock<SyncPeers>,
+    progress:  RwLock<Option<SyncProgress>>,
+    fast_sync: bool,
+}
+
+impl<Adapter: ConsensusAdapter + 'static> Synchronization<Adapter> {
+    pub fn new(engine: Arc<ConsensusEngine<Adapter>>, fast_sync: bool) -> Self {
+        Synchronization {
+            engine,
+            peers: RwLock::new(SyncPeers::default()),
+            progress: RwLock::new(None),
+            fast_sync,
+        }
+    }
+
+    /// Record the peer which gossips a rich epoch ID.
+    pub fn report_peer(&self, ctx: Context, rich_epoch_id: u64) {
+        self.peers.write().report(ctx, rich_epoch_id);
+    }
+
+    /// The progress of the running synchronization.
+    pub fn progress(&self) -> Option<SyncProgress> {
+        self.progress.read().clone()
+    }
+
+    /// Synchronize from the current epoch to the rich epoch, return the epoch
+    /// ID synchronized to.
+    pub async fn sync(
+        &self,
+        ctx: Context,
+        current_epoch_id: u64,
+        rich_epoch_id: u64,
+    ) -> ProtocolResult<u64> {
+        // The proof of an epoch is carried by the header of the next epoch, so
+        // the proof of the rich epoch is pulled on its own. The peer may have
+        // gone further, then synchronize to the epoch of its latest proof.
+        let rich_proof = self.engine.pull_proof(ctx.clone()).await?;
+        if rich_proof.epoch_id < rich_epoch_id {
+            return Err(ConsensusError::SyncEpochProofErr(rich_epoch_id).into());
+        }
+        let target = rich_proof.epoch_id;
+
+        *self.progress.write() = Some(SyncProgress {
+            start: current_epoch_id,
+            current: current_epoch_id,
+            target,
+        });
+        let res = self.sync_to(ctx, current_epoch_id, rich_proof).await;
+        *self.progress.write() = None;
+
+        res.map(|_| target)
+    }
+
+    async fn sync_to(
+        &self,
+        ctx: Context,
+        current_epoch_id: u64,
+        rich_proof: Proof,
+    ) -> ProtocolResult<()> {
+        let current_epoch = self
+            .engine
+            .get_epoch_by_id(ctx.clone(), current_epoch_id)
+            .await?;
+        let mut last_epoch_id = current_epoch_id;
+        let mut state_root = current_epoch.header.state_root.clone();
+        let mut current_hash = Hash::digest(current_epoch.encode_fixed()?);
+
+        if self.fast_sync && rich_proof.epoch_id > current_epoch_id + FAST_SYNC_MIN_EPOCHS {
+            let pivot = self
+                .sync_headers(ctx.clone(), current_epoch, &rich_proof)
+                .await?;
+            let pivot_id = pivot.header.epoch_id;
+
+            // The pivot epoch is executed on the pulled state, as if the epoch
+            // before it was committed.
+            if pivot_id > current_epoch_id + FAST_SYNC_MIN_EPOCHS {
+                self.sync_state(ctx.clone(), &pivot).await?;
+                last_epoch_id = pivot_id - 1;
+                state_root = pivot.header.state_root.clone();
+                current_hash = pivot.header.pre_hash.clone();
+
+                if let Some(progress) = self.progress.write().as_mut() {
+                    progress.current = last_epoch_id;
+                }
+            }
+        }
+
+        self.sync_ranges(
+            ctx,
+            last_epoch_id,
+            rich_proof,
+            &mut state_root,
+            &mut current_hash,
+        )
+        .await
+    }
+
+    // Pull and check the headers after the current epoch, return the latest
+    // header checked before the validators change. The peer serving a bad
+    // header is dropped.
+    async fn sync_headers(
+        &self,
+        ctx: Context,
+        current_epoch: Epoch,
+        rich_proof: &Proof,
+    ) -> ProtocolResult<Epoch> {
+        let current_epoch_id = current_epoch.header.epoch_id;
+        let target = rich_proof.epoch_id;
+        let ranges = ((current_epoch_id + 1)..=target)
+            .step_by(HEADERS_BATCH_SIZE as usize)
+            .map(|start| (start, (start + HEADERS_BATCH_SIZE - 1).min(target)))
+            .enumerate()
+            .collect::<Vec<_>>();
+        let concurrency = self.peers.read().peers().len().max(1);
+        let concurrency = concurrency.min(MAX_CONCURRENT_PULLS);
+
+        let mut pulls = stream::iter(ranges)
+            .map(|(index, (start, stop))| self.pull_headers(ctx.clone(), index, start, stop))
+            .buffered(concurrency);
+
+        let validators = self.engine.get_current_validators();
+        let mut last = (current_epoch, SyncPeer::unrecorded(ctx.clone()));
+        while let Some(headers) = pulls.next().await {
+            let stopped =
+                check_fast_sync_headers(&mut last, headers?, current_epoch_id, &validators)
+                    .map_err(|bad| self.drop_peer(bad))?;
+            if stopped {
+                info!(
+                    "consensus: fast sync stops before the validators change in epoch {}",
+                    last.0.header.epoch_id + 1
+                );
+                break;
+            }
+            debug!(
+                "consensus: fast sync checked headers to {}",
+                last.0.header.epoch_id
+            );
+        }
+
+        let (last, last_peer) = last;
+        if last.header.epoch_id == target {
+            check_epoch_proof(
+                &last,
+                &last_peer,
+                rich_proof,
+                &SyncPeer::unrecorded(ctx.clone()),
+                &validators,
+            )
+            .map_err(|bad| self.drop_peer(bad))?;
+        }
+        Ok(last)
+    }
+
+    // Pull the state of the pivot epoch and take it as the current status.
+    async fn sync_state(&self, ctx: Context, pivot: &Epoch) -> ProtocolResult<()> {
+        let peers = self
+            .sync_peers(&ctx)
+            .into_iter()
+            .map(|peer| peer.ctx)
+            .collect();
+
+        info!(
+            "consensus: fast sync pull the state of epoch {}",
+            pivot.header.epoch_id
+        );
+        self.engine
+            .pull_state(peers, pivot.header.state_root.clone())
+            .await?;
+        self.engine.fast_forward(pivot).await
+    }
+
+    async fn sync_ranges(
+        &self,
+        ctx: Context,
+        current_epoch_id: u64,
+        rich_proof: Proof,
+        state_root: &mut MerkleRoot,
+        current_hash: &mut Hash,
+    ) -> ProtocolResult<()> {
+        let target = rich_proof.epoch_id;
+        let ranges = ((current_epoch_id + 1)..=target)
+            .step_by(SYNC_BATCH_SIZE as usize)
+            .map(|start| (start, (start + SYNC_BATCH_SIZE - 1).min(target)))
+            .enumerate()
+            .collect::<Vec<_>>();
+        let concurrency = self.peers.read().peers().len().max(1);
+        let concurrency = concurrency.min(MAX_CONCURRENT_PULLS);
+
+        // The ranges are pulled concurrently but yielded in order.
+        let mut pulls = stream::iter(ranges)
+            .map(|(index, (start, stop))| self.pull_range(ctx.clone(), index, start, stop))
+            .buffered(concurrency);
+
+        // The proof of an epoch is carried by the next epoch, which may be
+        // pulled from another peer.
+        let started = Instant::now();
+        let mut pending: Option<(Epoch, Vec<SignedTransaction>, SyncPeer)> = None;
+        while let Some(range) = pulls.next().await {
+            for (epoch, txs, peer) in range?.into_iter() {
+                if let Some((last_epoch, last_txs, last_peer)) = pending.take() {
+                    let proof = epoch.header.proof.clone();
+                    self.check_epoch(&last_epoch, &last_peer, &proof, &peer)?;
+                    self.commit_epoch(last_epoch, last_txs, proof, state_root, current_hash)
+                        .await?;
+                }
+                pending = Some((epoch, txs, peer));
+            }
+
+            let current = self.progress().map_or(current_epoch_id, |p| p.current);
+            let elapsed = started.elapsed().as_secs().max(1);
+            info!(
+                "consensus: synchronization progress {}/{}, {} epochs/s",
+                current,
+                target,
+                (current - current_epoch_id) / elapsed
+            );
+        }
+
+        if let Some((epoch, txs, peer)) = pending {
+            let rich_peer = SyncPeer::unrecorded(ctx.clone());
+            self.check_epoch(&epoch, &peer, &rich_proof, &rich_peer)?;
+            self.commit_epoch(epoch, txs, rich_proof, state_root, current_hash)
+                .await?;
+        }
+        Ok(())
+    }
+
+    // Pull a range of epochs, the peers are tried in turn when a pull fails.
+    // Each epoch is returned with the peer serving it.
+    async fn pull_range(
+        &self,
+        ctx: Context,
+        index: usize,
+        start: u64,
+        stop: u64,
+    ) -> ProtocolResult<Vec<(Epoch, Vec<SignedTransaction>, SyncPeer)>> {
+        let mut res = Vec::new();
+        let mut next = start;
+        let mut attempt = 0;
+
+        while next <= stop {
+            let peers = self.sync_peers(&ctx);
+            let peer = peers[(index + attempt) % peers.len()].clone();
+
+            debug!(
+                "consensus: synchronization pull epochs {} to {}",
+                next, stop
+            );
+            let range = self
+                .engine
+                .pull_epoch_range(peer.ctx.clone(), next, stop)
+                .await
+                .and_then(|range| check_range(next, stop, range));
+            match range {
+                Ok(range) => {
+                    next += range.len() as u64;
+                    res.extend(
+                        range
+                            .into_iter()
+                            .map(|(epoch, txs)| (epoch, txs, peer.clone())),
+                    );
+                }
+                Err(e) => {
+                    warn!(
+                        "consensus: synchronization pull epochs {} to {} failed, {}",
+                        next, stop, e
+                    );
+                    attempt += 1;
+                    if attempt >= MAX_PULL_ATTEMPTS {
+                        return Err(e);
+                    }
+                }
+            }
+        }
+        Ok(res)
+    }
+
+    // Pull a range of headers, the peers are tried in turn when a pull fails.
+    // Each header is returned with the peer serving it.
+    async fn pull_headers(
+        &self,
+        ctx: Context,
+        index: usize,
+        start: u64,
+        stop: u64,
+    ) -> ProtocolResult<Vec<(Epoch, SyncPeer)>> {
+        let mut res = Vec::new();
+        let mut next = start;
+        let mut attempt = 0;
+
+        while next <= stop {
+            let peers = self.sync_peers(&ctx);
+            let peer = peers[(index + attempt) % peers.len()].clone();
+
+            let headers = self
+                .engine
+                .pull_headers(peer.ctx.clone(), next, stop)
+                .await
+                .and_then(|headers| check_headers(next, stop, headers));
+            match headers {
+                Ok(headers) => {
+                    next += headers.len() as u64;
+                    res.extend(headers.into_iter().map(|epoch| (epoch, peer.clone())));
+                }
+                Err(e) => {
+                    warn!(
+                        "consensus: fast sync pull headers {} to {} failed, {}",
+                        next, stop, e
+                    );
+                    attempt += 1;
+                    if attempt >= MAX_PULL_ATTEMPTS {
+                        return Err(e);
+                    }
+                }
+            }
+        }
+        Ok(res)
+    }
+
+    // The recorded peers, or the peer which gossips the rich epoch ID if none
+    // is recorded.
+    fn sync_peers(&self, ctx: &Context) -> Vec<SyncPeer> {
+        let peers = self.peers.read().peers();
+        if peers.is_empty() {
+            vec![SyncPeer::unrecorded(ctx.clone())]
+        } else {
+            peers
+        }
+    }
+
+    // Check an epoch with the proof of it, the peer serving the bad one is
+    // dropped. The epoch is voted by the validators of the current status,
+    // which are updated by the execution of the last epoch.
+    fn check_epoch(
+        &self,
+        epoch: &Epoch,
+        epoch_peer: &SyncPeer,
+        proof: &Proof,
+        proof_peer: &SyncPeer,
+    ) -> ProtocolResult<()> {
+        let validators = self.engine.get_current_validators();
+        check_epoch_proof(epoch, epoch_peer, proof, proof_peer, &validators)
+            .map_err(|bad| self.drop_peer(bad))
+    }
+
+    // Drop the bad peer and report it to the network.
+    fn drop_peer(&self, bad: BadPeer) -> ProtocolError {
+        self.peers.write().drop_peer(bad.peer.id);
+        self.engine.report_bad(bad.peer.ctx, &bad.err.to_string());
+        bad.err
+    }
+
+    // Execute an epoch checked with the proof of it.
+    async fn commit_epoch(
+        &self,
+        epoch: Epoch,
+        txs: Vec<SignedTransaction>,
+        proof: Proof,
+        state_root: &mut MerkleRoot,
+        current_hash: &mut Hash,
+    ) -> ProtocolResult<()> {
+        let epoch_id = epoch.header.epoch_id;
+        debug!("consensus: synchronization check previous hash");
+        if *current_hash != epoch.header.pre_hash {
+            return Err(ConsensusError::SyncEpochHashErr(epoch_id).into());
+        }
+
+        debug!("consensus: synchronization executor the epoch {}", epoch_id);
+        let exec_resp = self
+            .engine
+            .exec(
+                state_root.clone(),
+                epoch_id,
+                Address::User(epoch.header.proposer.clone()),
+                txs.clone(),
+            )
+            .await?;
+        *state_root = exec_resp.state_root.clone();
+        *current_hash = Hash::digest(epoch.encode_fixed()?);
+
+        // The proof is saved after the epoch, so an interrupted
+        // synchronization never leaves a proof beyond the latest epoch.
+        self.engine
+            .update_status(epoch_id, epoch, proof.clone(), exec_resp, txs)
+            .await?;
+        self.engine.save_proof(Context::new(), proof).await?;
+
+        if let Some(progress) = self.progress.write().as_mut() {
+            progress.current = epoch_id;
+        }
+        debug!("consensus: finish synchronization {} epoch", epoch_id);
+        Ok(())
+    }
+}
+
+// Check that a pulled range starts from the requested epoch, the epochs are
+// successive and the transactions match the ordered transaction hashes.
+fn check_range(
+    start: u64,
+    stop: u64,
+    range: Vec<(Epoch, Vec<SignedTransaction>)>,
+) -> ProtocolResult<Vec<(Epoch, Vec<SignedTransaction>)>> {
+    if range.is_empty() || range.len() as u64 > stop - start + 1 {
+        return Err(ConsensusError::SyncEpochHashErr(start).into());
+    }
+
+    for (index, (epoch, txs)) in range.iter().enumerate() {
+        let epoch_id = start + index as u64;
+        if epoch.header.epoch_id != epoch_id {
+            return Err(ConsensusError::SyncEpochHashErr(epoch_id).into());
+        }
+
+        let matched = txs.len() == epoch.ordered_tx_hashes.len()
+            && txs
+                .iter()
+                .zip(epoch.ordered_tx_hashes.iter())
+                .all(|(tx, hash)| &tx.tx_hash == hash);
+        if !matched {
+            return Err(ConsensusError::SyncTxsErr(epoch_id).into());
+        }
+    }
+    Ok(range)
+}
+
+// Check that a pulled range of headers starts from the requested epoch and the
+// epochs are successive.
+fn check_headers(start: u64, stop: u64, headers: Vec<Epoch>) -> ProtocolResult<Vec<Epoch>> {
+    if headers.is_empty() || headers.len() as u64 > stop - start + 1 {
+        return Err(ConsensusError::SyncEpochHashErr(start).into());
+    }
+
+    for (index, epoch) in headers.iter().enumerate() {
+        let epoch_id = start + index as u64;
+        if epoch.header.epoch_id != epoch_id {
+            return Err(ConsensusError::SyncEpochHashErr(epoch_id).into());
+        }
+    }
+    Ok(headers)
+}
+
+// Check the headers pulled after the last checked header in a fast sync, the
+// proof of a header is carried by the next one. Return true once the
+// validators change, then the last checked header is the one before the
+// change.
+fn check_fast_sync_headers(
+    last: &mut (Epoch, SyncPeer),
+    headers: Vec<(Epoch, SyncPeer)>,
+    current_epoch_id: u64,
+    validators: &[Validator],
+) -> Result<bool, BadPeer> {
+    for (epoch, peer) in headers.into_iter() {
+        let (last_epoch, last_peer) = &*last;
+        let epoch_id = epoch.header.epoch_id;
+        if last_epoch.header.epoch_id > current_epoch_id {
+            check_epoch_proof(
+                last_epoch,
+                last_peer,
+                &epoch.header.proof,
+                &peer,
+                validators,
+            )?;
+        }
+
+        // The last header is checked, so the header not linking to it is bad.
+        let last_hash = Hash::digest(
+            last_epoch
+                .encode_fixed()
+                .map_err(|e| BadPeer::new(last_peer, e))?,
+        );
+        if last_hash != epoch.header.pre_hash {
+            let err = ConsensusError::SyncEpochHashErr(epoch_id).into();
+            return Err(BadPeer::new(&peer, err));
+        }
+
+        if epoch.header.validators != validators {
+            return Ok(true);
+        }
+        *last = (epoch, peer);
+    }
+    Ok(false)
+}
+
+// Check an epoch with the proof of it, which is carried by the next epoch and
+// may be served by another peer. The proof signed by the validators certifies
+// an epoch, so the peer of the proof is bad if the signature is invalid, and
+// the peer of the epoch is bad if the epoch is not the certified one.
+fn check_epoch_proof(
+    epoch: &Epoch,
+    epoch_peer: &SyncPeer,
+    proof: &Proof,
+    proof_peer: &SyncPeer,
+    validators: &[Validator],
+) -> Result<(), BadPeer> {
+    let epoch_id = epoch.header.epoch_id;
+    if let Err(e) = check_proof_signature(epoch_id, proof, validators) {
+        warn!(
+            "consensus: reject the proof of epoch {} of the peer, {}",
+            epoch_id, e
+        );
+        let err = ConsensusError::SyncEpochProofErr(epoch_id).into();
+        return Err(BadPeer::new(proof_peer, err));
+    }
+
+    let epoch_hash = Hash::digest(
+        epoch
+            .encode_fixed()
+            .map_err(|e| BadPeer::new(epoch_peer, e))?,
+    );
+    if proof.epoch_hash != epoch_hash {
+        warn!("consensus: reject the epoch {} of the peer", epoch_id);
+        let err = ConsensusError::SyncEpochProofErr(epoch_id).into();
+        return Err(BadPeer::new(epoch_peer, err));
+    }
+    Ok(())
+}
+
+// Check that the proof is the precommit quorum certificate of the epoch ID,
+// signed by more than 2/3 of the vote weight of the validators.
+fn check_proof_signature(
+    epoch_id: u64,
+    proof: &Proof,
+    validators: &[Validator],
+) -> ProtocolResult<()> {
+    if proof.epoch_id != epoch_id {
+        return Err(ConsensusError::SyncEpochProofErr(epoch_id).into());
+    }
+
+    let hash = vote_hash(
+        epoch_id,
+        proof.round,
+        VoteType::Precommit,
+        proof.epoch_hash.as_bytes(),
+    );
+    verify_aggregated_signature(validators, &proof.signature, &proof.bitmap, &hash)
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use bytes::Bytes;
+    use overlord::types::VoteType;
+    use overlord::Crypto;
+
+    use protocol::fixed_codec::ProtocolFixedCodec;
+    use protocol::traits::Context;
+    use protocol::types::{Epoch, EpochHeader, Hash, Proof, UserAddress, Validator};
+
+    use crate::util::test::{mock_status, mock_validators};
+    use crate::util::{vote_hash, OverlordCrypto};
+
+    use super::{check_epoch_proof, check_fast_sync_headers, BadPeer, SyncPeer, SyncPeers};
+
+    // Make the epoch after the last one, which carries the proof of the last
+    // one signed by the first three of its validators.
+    fn mock_next_epoch(
+        last: &Epoch,
+        validators: Vec<Validator>,
+        cryptos: &[OverlordCrypto],
+    ) -> Epoch {
+        let epoch_hash = Hash::digest(last.encode_fixed().unwrap());
+        let hash = vote_hash(
+            last.header.epoch_id,
+            0,
+            VoteType::Precommit,
+            epoch_hash.as_bytes(),
+        );
+        let (signatures, voters) = cryptos[..3]
+            .iter()
+            .zip(last.header.validators.iter())
+            .map(|(crypto, v)| (crypto.sign(hash.clone()).unwrap(), v.address.as_bytes()))
+            .unzip();
+        let proof = Proof {
+            epoch_id:   last.header.epoch_id,
+            round:      0,
+            epoch_hash: epoch_hash.clone(),
+            signature:  cryptos[0].aggregate_signatures(signatures, voters).unwrap(),
+            bitmap:     Bytes::from(vec![0b1110_0000]),
+        };
+
+        let mut epoch = mock_epoch(last.header.epoch_id + 1, validators);
+        epoch.header.pre_hash = epoch_hash;
+        epoch.header.proof = proof;
+        epoch
+    }
+
+    fn mock_epoch(epoch_id: u64, validators: Vec<Validator>) -> Epoch {
+        let header = EpochHeader {
+            chain_id: Hash::from_empty(),
+            epoch_id,
+            pre_hash: Hash::from_empty(),
+            timestamp: 1000,
+            logs_bloom: Default::default(),
+            order_root: Hash::from_empty(),
+            confirm_root: Vec::new(),
+            state_root: Hash::from_empty(),
+            receipt_root: Vec::new(),
+            cycles_used: 0,
+            proposer: validators[0].address.clone(),
+            proof: Proof {
+                epoch_id:   0,
+                round:      0,
+                epoch_hash: Hash::from_empty(),
+                signature:  Default::default(),
+                bitmap:     Default::default(),
+            },
+            validator_version: 0,
+            validators,
+        };
+
+        Epoch {
+            header,
+            ordered_tx_hashes: Vec::new(),
+        }
+    }
+
+    fn mock_cryptos(validators: &[Validator]) -> Vec<OverlordCrypto> {
+        let (_, private_keys) = mock_validators(validators.len() as u8);
+        let status = mock_status(validators.to_vec());
+        validators
+            .iter()
+            .zip(private_keys.into_iter())
+            .map(|(v, key)| OverlordCrypto::new(v.address.clone(), key, Arc::clone(&status)))
+            .collect()
+    }
+
+    fn mock_peer(id: u64) -> SyncPeer {
+        SyncPeer {
+            id,
+            ctx: Context::new(),
+        }
+    }
+
+    fn bad_peer_id<T>(res: Result<T, BadPeer>) -> u64 {
+        match res {
+            Err(bad) => bad.peer.id,
+            Ok(_) => panic!("the check passes"),
+        }
+    }
+
+    #[test]
+    fn test_sync_peers() {
+        let mut peers = SyncPeers::default();
+        assert!(peers.peers().is_empty());
+
+        peers.report(Context::new(), 2);
+        peers.report(Context::new(), 2);
+        peers.report(Context::new(), 1);
+        assert_eq!(peers.peers().len(), 2);
+
+        // The peers of the previous epoch ID are used until the latest one is
+        // reported by as many peers.
+        peers.report(Context::new(), 3);
+        assert_eq!(peers.peers().len(), 2);
+        peers.report(Context::new(), 3);
+        peers.report(Context::new(), 3);
+        assert_eq!(peers.peers().len(), 3);
+
+        // A dropped peer is not pulled from.
+        let dropped = peers.peers()[1].id;
+        peers.drop_peer(dropped);
+        let ids = peers.peers().iter().map(|peer| peer.id).collect::<Vec<_>>();
+        assert_eq!(ids.len(), 2);
+        assert!(!ids.contains(&dropped));
+    }
+
+    #[test]
+    fn test_fast_sync_headers() {
+        let (validators, _) = mock_validators(4);
+        let cryptos = mock_cryptos(&validators);
+
+        // The first two headers are served by the peer 1, the others by the
+        // peer 2.
+        let current = mock_epoch(0, validators.clone());
+        let mut headers = vec![mock_next_epoch(&current, validators.clone(), &cryptos)];
+        for _ in 0..3 {
+            let next = mock_next_epoch(headers.last().unwrap(), validators.clone(), &cryptos);
+            headers.push(next);
+        }
+        let with_peers = |headers: Vec<Epoch>| {
+            headers
+                .into_iter()
+                .enumerate()
+                .map(|(index, epoch)| (epoch, mock_peer(if index < 2 { 1 } else { 2 })))
+                .collect::<Vec<_>>()
+        };
+
+        let mut last = (current.clone(), mock_peer(0));
+        let res = check_fast_sync_headers(&mut last, with_peers(headers.clone()), 0, &validators);
+        assert_eq!(res.ok(), Some(false));
+        assert_eq!(last.0, headers[3]);
+
+        // The proof of the epoch 2 carried by the epoch 3 is signed by two of
+        // four validators.
+        let mut bad_headers = headers.clone();
+        bad_headers[2].header.proof.bitmap = Bytes::from(vec![0b1100_0000]);
+        let mut last = (current.clone(), mock_peer(0));
+        let res = check_fast_sync_headers(&mut last, with_peers(bad_headers), 0, &validators);
+        assert_eq!(bad_peer_id(res), 2);
+
+        // The epoch 2 is not the one certified by the proof.
+        let mut bad_headers = headers.clone();
+        bad_headers[1].header.timestamp += 1;
+        let mut last = (current.clone(), mock_peer(0));
+        let res = check_fast_sync_headers(&mut last, with_peers(bad_headers), 0, &validators);
+        assert_eq!(bad_peer_id(res), 1);
+
+        // The epoch 3 does not link to the epoch 2.
+        let mut bad_headers = headers;
+        bad_headers[2].header.pre_hash = Hash::from_empty();
+        let mut last = (current, mock_peer(0));
+        let res = check_fast_sync_headers(&mut last, with_peers(bad_headers), 0, &validators);
+        assert_eq!(bad_peer_id(res), 2);
+    }
+
+    #[test]
+    fn test_check_epoch_proof() {
+        let (validators, _) = mock_validators(4);
+        let cryptos = mock_cryptos(&validators);
+
+        let epoch = mock_epoch(1, validators.clone());
+        let next = mock_next_epoch(&epoch, validators.clone(), &cryptos);
+        let proof = next.header.proof;
+        let (epoch_peer, proof_peer) = (mock_peer(1), mock_peer(2));
+        assert!(check_epoch_proof(&epoch, &epoch_peer, &proof, &proof_peer, &validators).is_ok());
+
+        // A proof which is not signed by the validators.
+        let other_validators = mock_validators(5).0[1..].to_vec();
+        let res = check_epoch_proof(&epoch, &epoch_peer, &proof, &proof_peer, &other_validators);
+        assert_eq!(bad_peer_id(res), 2);
+
+        let mut bad_proof = proof.clone();
+        bad_proof.epoch_id = 2;
+        let res = check_epoch_proof(&epoch, &epoch_peer, &bad_proof, &proof_peer, &validators);
+        assert_eq!(bad_peer_id(res), 2);
+
+        // An epoch which is not certified by the proof.
+        let mut bad_epoch = epoch;
+        bad_epoch.header.state_root = Hash::digest(Bytes::from("other"));
+        let res = check_epoch_proof(&bad_epoch, &epoch_peer, &proof, &proof_peer, &validators);
+        assert_eq!(bad_peer_id(res), 1);
+    }
+
+    #[test]
+    fn test_fast_sync_stops_at_validators_change() {
+        let (validators, _) = mock_validators(4);
+        let cryptos = mock_cryptos(&validators);
+        let new_validators = vec![Validator {
+            address:        UserAddress::from_hex("100000000000000000000000000000000000000009")
+                .unwrap(),
+            propose_weight: 1,
+            vote_weight:    1,
+            bls_pub_key:    validators[0].bls_pub_key.clone(),
+        }];
+
+        let current = mock_epoch(0, validators.clone());
+        let first = mock_next_epoch(&current, validators.clone(), &cryptos);
+        let second = mock_next_epoch(&first, validators.clone(), &cryptos);
+        let changed = mock_next_epoch(&second, new_validators.clone(), &cryptos);
+        let after = mock_epoch(4, new_validators);
+
+        // The headers after the change are not checked.
+        let mut last = (current, mock_peer(0));
+        let headers = vec![first, second.clone(), changed, after]
+            .into_iter()
+            .map(|epoch| (epoch, mock_peer(1)))
+            .collect();
+        let res = check_fast_sync_headers(&mut last, headers, 0, &validators);
+        assert_eq!(res.ok(), Some(true));
+        assert_eq!(last.0, second);
+    }
+}
@@ -1,22 +1,40 @@
+use std::convert::TryFrom;
 use std::error::Error;
+use std::sync::Arc;
 
 use bytes::Bytes;
-use overlord::{types::AggregatedSignature, Crypto};
+use overlord::types::{AggregatedSignature, AggregatedVote, Vote, VoteType};
+use overlord::Crypto;
+use parking_lot::RwLock;
 
 use common_crypto::{
-    Crypto as Secp256k1Crypto, PrivateKey, PublicKey, Secp256k1, Secp256k1PrivateKey,
-    Secp256k1PublicKey, Signature,
+    BLS12381PrivateKey, BLS12381PublicKey, BLS12381Signature, Crypto as BLSCrypto, PrivateKey,
+    Signature, BLS12381,
 };
 
-use protocol::types::{Hash, UserAddress};
-use protocol::ProtocolError;
+use protocol::traits::CurrentConsensusStatus;
+use protocol::types::{Hash, UserAddress, Validator};
+use protocol::{ProtocolError, ProtocolResult};
 
 use crate::ConsensusError;
 
+const ADDRESS_LEN: usize = 21;
+const HASH_LEN: usize = 32;
+
+/// The votes are signed by the BLS12-381 keys of the validators, so that the
+/// signatures of a quorum certificate aggregate into one.
+///
+/// Overlord passes neither the signed hash to `aggregate_signatures` nor to
+/// `verify_aggregated_signature`, so the signatures carry it. A signature is
+/// `voter address ++ hash ++ bls signature`, an aggregated signature is
+/// `hash ++ aggregated bls signature`.
 #[derive(Clone, Debug)]
 pub struct OverlordCrypto {
-    public_key:  Secp256k1PublicKey,
-    private_key: Secp256k1PrivateKey,
+    address:     UserAddress,
+    private_key: BLS12381PrivateKey,
+    // The validators of the signatures are read from the current status,
+    // which the engine updates at the end of each epoch.
+    status: Arc<RwLock<CurrentConsensusStatus>>,
 }
 
 impl Crypto for OverlordCrypto {
@@ -25,51 +43,442 @@ impl Crypto for OverlordCrypto {
     }
 
     fn sign(&self, hash: Bytes) -> Result<Bytes, Box<dyn Error + Send>> {
-        let signature = Secp256k1::sign_message(&hash, &self.private_key.to_bytes())
+        let signature = BLS12381::sign_message(&hash, &self.private_key.to_bytes())
             .map_err(|e| ProtocolError::from(ConsensusError::CryptoErr(Box::new(e))))?
             .to_bytes();
 
-        let mut res = self.public_key.to_bytes();
+        let mut res = self.address.as_bytes();
+        res.extend_from_slice(&hash);
         res.extend_from_slice(&signature);
         Ok(res)
     }
 
     fn verify_signature(
         &self,
-        mut signature: Bytes,
+        signature: Bytes,
         hash: Bytes,
     ) -> Result<Bytes, Box<dyn Error + Send>> {
-        let tmp = signature.split_off(33);
-        let pub_key = signature;
-        let signature = tmp;
+        let (address, signed_hash, signature) = split_signature(signature)?;
+        if signed_hash != hash {
+            return Err(ProtocolError::from(ConsensusError::InvalidSignature(
+                "signed another hash".to_owned(),
+            ))
+            .into());
+        }
 
-        Secp256k1::verify_signature(&hash, &signature, &pub_key)
+        let pub_key = bls_pub_key_of(&self.status.read().validators, &address)?;
+        BLS12381::verify_signature(&hash, &signature, &pub_key.to_bytes())
             .map_err(|e| ProtocolError::from(ConsensusError::CryptoErr(Box::new(e))))?;
-        let address = UserAddress::from_pubkey_bytes(pub_key)?;
         Ok(address.as_bytes())
     }
 
     fn aggregate_signatures(
         &self,
-        _signatures: Vec<Bytes>,
-        _voters: Vec<Bytes>,
+        signatures: Vec<Bytes>,
+        voters: Vec<Bytes>,
     ) -> Result<Bytes, Box<dyn Error + Send>> {
-        Ok(Bytes::new())
+        if signatures.is_empty() || signatures.len() != voters.len() {
+            return Err(ProtocolError::from(ConsensusError::InvalidSignature(
+                "signatures mismatch the voters".to_owned(),
+            ))
+            .into());
+        }
+
+        let validators = self.status.read().validators.clone();
+        let (_, hash, _) = split_signature(signatures[0].clone())?;
+        let mut sigs_pub_keys = Vec::with_capacity(signatures.len());
+        for (signature, voter) in signatures.into_iter().zip(voters.iter()) {
+            let (address, signed_hash, signature) = split_signature(signature)?;
+            if &address.as_bytes() != voter {
+                return Err(ProtocolError::from(ConsensusError::InvalidSignature(
+                    "signature mismatch the voter".to_owned(),
+                ))
+                .into());
+            }
+            if signed_hash != hash {
+                return Err(ProtocolError::from(ConsensusError::InvalidSignature(
+                    "signatures sign different hashes".to_owned(),
+                ))
+                .into());
+            }
+
+            let signature = BLS12381Signature::try_from(signature.as_ref())
+                .map_err(|e| ProtocolError::from(ConsensusError::CryptoErr(Box::new(e))))?;
+            let pub_key = bls_pub_key_of(&validators, &address)?;
+            sigs_pub_keys.push((signature, pub_key));
+        }
+
+        let mut res = hash;
+        res.extend_from_slice(&BLS12381Signature::combine(sigs_pub_keys).to_bytes());
+        Ok(res)
     }
 
+    // Overlord passes no vote here, so only the carried hash is verified. The
+    // quorum certificates from the network are checked against their votes by
+    // `verify_qc` before they reach overlord.
     fn verify_aggregated_signature(
         &self,
-        _aggregated_signature: AggregatedSignature,
+        aggregated_signature: AggregatedSignature,
     ) -> Result<(), Box<dyn Error + Send>> {
+        let (hash, signature) = split_aggregated_signature(&aggregated_signature.signature)?;
+        verify_voters_signature(
+            &self.status.read().validators,
+            &hash,
+            &signature,
+            &aggregated_signature.address_bitmap,
+        )?;
         Ok(())
     }
 }
 
 impl OverlordCrypto {
-    pub fn new(public_key: Secp256k1PublicKey, private_key: Secp256k1PrivateKey) -> Self {
+    pub fn new(
+        address: UserAddress,
+        private_key: BLS12381PrivateKey,
+        status: Arc<RwLock<CurrentConsensusStatus>>,
+    ) -> Self {
         OverlordCrypto {
-            public_key,
+            address,
             private_key,
+            status,
+        }
+    }
+}
+
+/// Verify an aggregated signature of the voters in the bitmap on the hash. The
+/// hash carried by the signature is compared first, so that a signature of
+/// another vote is rejected before any pairing.
+pub fn verify_aggregated_signature(
+    validators: &[Validator],
+    aggregated_signature: &Bytes,
+    bitmap: &Bytes,
+    hash: &Bytes,
+) -> ProtocolResult<()> {
+    let (signed_hash, signature) = split_aggregated_signature(aggregated_signature)?;
+    if &signed_hash != hash {
+        return Err(ConsensusError::InvalidSignature("signed another hash".to_owned()).into());
+    }
+    verify_voters_signature(validators, hash, &signature, bitmap)
+}
+
+/// Verify a quorum certificate of the validators, which is an aggregated
+/// signature of the vote.
+pub fn verify_qc(validators: &[Validator], qc: &AggregatedVote) -> ProtocolResult<()> {
+    let hash = vote_hash(
+        qc.epoch_id,
+        qc.round,
+        qc.vote_type.clone(),
+        qc.epoch_hash.clone(),
+    );
+    verify_aggregated_signature(
+        validators,
+        &qc.signature.signature,
+        &qc.signature.address_bitmap,
+        &hash,
+    )
+}
+
+/// The hash of a vote which overlord signs.
+pub fn vote_hash(epoch_id: u64, round: u64, vote_type: VoteType, epoch_hash: Bytes) -> Bytes {
+    let vote = Vote {
+        epoch_id,
+        round,
+        vote_type,
+        epoch_hash,
+    };
+    Hash::digest(Bytes::from(rlp::encode(&vote))).as_bytes()
+}
+
+// Verify the bls signature of the voters in the bitmap, which marks the
+// validators sorted by address in the same way as the authority list of
+// overlord. The voters must hold more than 2/3 of the vote weight.
+fn verify_voters_signature(
+    validators: &[Validator],
+    hash: &Bytes,
+    signature: &Bytes,
+    bitmap: &Bytes,
+) -> ProtocolResult<()> {
+    let mut authority = validators.iter().collect::<Vec<_>>();
+    authority.sort_by(|a, b| a.address.cmp(&b.address));
+    if bitmap.len() != (authority.len() + 7) / 8 {
+        return Err(
+            ConsensusError::InvalidSignature("bitmap mismatch the validators".to_owned()).into(),
+        );
+    }
+
+    let mut total_weight: u64 = 0;
+    let mut voted_weight: u64 = 0;
+    let mut pub_keys = Vec::new();
+    for (i, validator) in authority.iter().enumerate() {
+        total_weight += u64::from(validator.vote_weight);
+        if bitmap[i / 8] & (0x80 >> (i % 8)) != 0 {
+            voted_weight += u64::from(validator.vote_weight);
+            pub_keys.push(parse_bls_pub_key(validator)?);
         }
     }
+    // The bits after the validators must be zero.
+    let voters = bitmap
+        .iter()
+        .map(|b| b.count_ones() as usize)
+        .sum::<usize>();
+    if voters != pub_keys.len() {
+        return Err(
+            ConsensusError::InvalidSignature("bitmap mismatch the validators".to_owned()).into(),
+        );
+    }
+    if voted_weight * 3 <= total_weight * 2 {
+        return Err(ConsensusError::NoQuorum(voted_weight, total_weight).into());
+    }
+
+    let pub_key = BLS12381PublicKey::aggregate(pub_keys);
+    BLS12381::verify_signature(hash, signature, &pub_key.to_bytes())
+        .map_err(|e| ConsensusError::CryptoErr(Box::new(e)))?;
+    Ok(())
+}
+
+// Split an aggregated signature into the signed hash and the aggregated bls
+// signature.
+fn split_aggregated_signature(aggregated_signature: &Bytes) -> ProtocolResult<(Bytes, Bytes)> {
+    if aggregated_signature.len() <= HASH_LEN {
+        return Err(
+            ConsensusError::InvalidSignature("aggregated signature too short".to_owned()).into(),
+        );
+    }
+    Ok((
+        aggregated_signature.slice_to(HASH_LEN),
+        aggregated_signature.slice_from(HASH_LEN),
+    ))
+}
+
+// Split a signature into the voter address, the signed hash and the bls
+// signature.
+fn split_signature(signature: Bytes) -> ProtocolResult<(UserAddress, Bytes, Bytes)> {
+    if signature.len() <= ADDRESS_LEN + HASH_LEN {
+        return Err(ConsensusError::InvalidSignature("signature too short".to_owned()).into());
+    }
+
+    let address = UserAddress::from_bytes(signature.slice_to(ADDRESS_LEN))?;
+    let hash = signature.slice(ADDRESS_LEN, ADDRESS_LEN + HASH_LEN);
+    let signature = signature.slice_from(ADDRESS_LEN + HASH_LEN);
+    Ok((address, hash, signature))
+}
+
+fn bls_pub_key_of(
+    validators: &[Validator],
+    address: &UserAddress,
+) -> ProtocolResult<BLS12381PublicKey> {
+    let validator = validators
+        .iter()
+        .find(|v| &v.address == address)
+        .ok_or_else(|| ConsensusError::UnknownVoter(address.clone()))?;
+    parse_bls_pub_key(validator)
+}
+
+fn parse_bls_pub_key(validator: &Validator) -> ProtocolResult<BLS12381PublicKey> {
+    let pub_key = BLS12381PublicKey::try_from(validator.bls_pub_key.as_ref())
+        .map_err(|e| ConsensusError::CryptoErr(Box::new(e)))?;
+    Ok(pub_key)
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use std::convert::TryFrom;
+    use std::sync::Arc;
+
+    use bytes::Bytes;
+    use overlord::types::{AggregatedSignature, AggregatedVote, VoteType};
+    use overlord::Crypto;
+    use parking_lot::RwLock;
+
+    use common_crypto::{BLS12381PrivateKey, PrivateKey, PublicKey};
+
+    use protocol::traits::CurrentConsensusStatus;
+    use protocol::types::{Hash, Proof, UserAddress, Validator};
+
+    use super::{verify_aggregated_signature, verify_qc, vote_hash, OverlordCrypto};
+
+    pub(crate) fn mock_status(validators: Vec<Validator>) -> Arc<RwLock<CurrentConsensusStatus>> {
+        let status = CurrentConsensusStatus {
+            cycles_price: 1,
+            cycles_limit: 99_999_999,
+            epoch_id: 1,
+            prev_hash: Hash::from_empty(),
+            logs_bloom: Default::default(),
+            order_root: Hash::from_empty(),
+            confirm_root: vec![],
+            state_root: Hash::from_empty(),
+            receipt_root: vec![],
+            cycles_used: 0,
+            proof: Proof {
+                epoch_id:   0,
+                round:      0,
+                epoch_hash: Hash::from_empty(),
+                signature:  Default::default(),
+                bitmap:     Default::default(),
+            },
+            validator_version: 0,
+            validators,
+            consensus_interval: 3000,
+        };
+        Arc::new(RwLock::new(status))
+    }
+
+    // Make the validators sorted by address, with their private keys.
+    pub(crate) fn mock_validators(count: u8) -> (Vec<Validator>, Vec<BLS12381PrivateKey>) {
+        (1..=count)
+            .map(|seed| {
+                let private_key = BLS12381PrivateKey::try_from([seed; 32].as_ref()).unwrap();
+                let validator = Validator {
+                    address:        UserAddress::from_hex(&format!(
+                        "1000000000000000000000000000000000000000{:02x}",
+                        seed
+                    ))
+                    .unwrap(),
+                    propose_weight: 1,
+                    vote_weight:    1,
+                    bls_pub_key:    private_key.pub_key().to_bytes(),
+                };
+                (validator, private_key)
+            })
+            .unzip()
+    }
+
+    #[test]
+    fn test_aggregate_signatures() {
+        let (validators, private_keys) = mock_validators(4);
+        let status = mock_status(validators.clone());
+        let cryptos = validators
+            .iter()
+            .zip(private_keys.into_iter())
+            .map(|(v, key)| OverlordCrypto::new(v.address.clone(), key, Arc::clone(&status)))
+            .collect::<Vec<_>>();
+
+        let hash = cryptos[0].hash(Bytes::from("vote"));
+        let signatures = cryptos
+            .iter()
+            .map(|crypto| crypto.sign(hash.clone()).unwrap())
+            .collect::<Vec<_>>();
+        for (signature, validator) in signatures.iter().zip(validators.iter()) {
+            let voter = cryptos[0]
+                .verify_signature(signature.clone(), hash.clone())
+                .unwrap();
+            assert_eq!(voter, validator.address.as_bytes());
+        }
+        let other_hash = cryptos[0].hash(Bytes::from("other"));
+        assert!(cryptos[0]
+            .verify_signature(signatures[0].clone(), other_hash)
+            .is_err());
+
+        let voters = validators
+            .iter()
+            .map(|v| v.address.as_bytes())
+            .collect::<Vec<_>>();
+        // The first three of four validators reach the quorum.
+        let signature = cryptos[0]
+            .aggregate_signatures(signatures[..3].to_vec(), voters[..3].to_vec())
+            .unwrap();
+        let aggregated = |bitmap: u8| AggregatedSignature {
+            signature:      signature.clone(),
+            address_bitmap: Bytes::from(vec![bitmap]),
+        };
+
+        assert!(cryptos[0]
+            .verify_aggregated_signature(aggregated(0b1110_0000))
+            .is_ok());
+        assert!(verify_aggregated_signature(
+            &validators,
+            &signature,
+            &Bytes::from(vec![0b1110_0000]),
+            &hash
+        )
+        .is_ok());
+        // The signature carries the hash it signs.
+        let other_hash = cryptos[0].hash(Bytes::from("other"));
+        assert!(verify_aggregated_signature(
+            &validators,
+            &signature,
+            &Bytes::from(vec![0b1110_0000]),
+            &other_hash
+        )
+        .is_err());
+        // The voters mismatch the signature.
+        assert!(cryptos[0]
+            .verify_aggregated_signature(aggregated(0b1101_0000))
+            .is_err());
+        assert!(cryptos[0]
+            .verify_aggregated_signature(aggregated(0b1111_0000))
+            .is_err());
+        // A bit out of the validators.
+        assert!(cryptos[0]
+            .verify_aggregated_signature(aggregated(0b1110_1000))
+            .is_err());
+
+        // Two of four do not reach the quorum.
+        let signature = cryptos[0]
+            .aggregate_signatures(signatures[..2].to_vec(), voters[..2].to_vec())
+            .unwrap();
+        assert!(verify_aggregated_signature(
+            &validators,
+            &signature,
+            &Bytes::from(vec![0b1100_0000]),
+            &hash
+        )
+        .is_err());
+
+        // The signatures must match the voters.
+        assert!(cryptos[0]
+            .aggregate_signatures(signatures[..2].to_vec(), vec![
+                voters[1].clone(),
+                voters[0].clone()
+            ])
+            .is_err());
+    }
+
+    #[test]
+    fn test_verify_relabeled_qc() {
+        let (validators, private_keys) = mock_validators(4);
+        let status = mock_status(validators.clone());
+        let cryptos = validators
+            .iter()
+            .zip(private_keys.into_iter())
+            .map(|(v, key)| OverlordCrypto::new(v.address.clone(), key, Arc::clone(&status)))
+            .collect::<Vec<_>>();
+
+        let epoch_hash = Hash::digest(Bytes::from("epoch")).as_bytes();
+        let hash = vote_hash(1, 0, VoteType::Prevote, epoch_hash.clone());
+        let signatures = cryptos
+            .iter()
+            .map(|crypto| crypto.sign(hash.clone()).unwrap())
+            .collect::<Vec<_>>();
+        let voters = validators
+            .iter()
+            .map(|v| v.address.as_bytes())
+            .collect::<Vec<_>>();
+        let qc = AggregatedVote {
+            signature:  AggregatedSignature {
+                signature:      cryptos[0]
+                    .aggregate_signatures(signatures[..3].to_vec(), voters[..3].to_vec())
+                    .unwrap(),
+                address_bitmap: Bytes::from(vec![0b1110_0000]),
+            },
+            vote_type:  VoteType::Prevote,
+            epoch_id:   1,
+            round:      0,
+            epoch_hash: epoch_hash.clone(),
+        };
+        assert!(verify_qc(&validators, &qc).is_ok());
+
+        // The signature is valid on its own, but not for another vote.
+        let mut relabeled = qc.clone();
+        relabeled.epoch_hash = Hash::digest(Bytes::from("other")).as_bytes();
+        assert!(verify_qc(&validators, &relabeled).is_err());
+
+        let mut relabeled = qc.clone();
+        relabeled.round = 1;
+        assert!(verify_qc(&validators, &relabeled).is_err());
+
+        let mut relabeled = qc;
+        relabeled.vote_type = VoteType::Precommit;
+        assert!(verify_qc(&validators, &relabeled).is_err());
+    }
 }
@@ -0,0 +1,309 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+
+use bincode::{deserialize, serialize};
+use bytes::Bytes;
+use overlord::types::{AggregatedVote, SignedProposal, SignedVote, VoteType};
+use parking_lot::Mutex;
+use rlp::Encodable;
+use serde::{Deserialize, Serialize};
+
+use protocol::ProtocolResult;
+
+use crate::fixed_types::FixedPill;
+use crate::ConsensusError;
+
+const WAL_FILE: &str = "wal";
+const WAL_TMP_FILE: &str = "wal.tmp";
+
+/// The consensus state of an epoch which must survive a crash. The messages
+/// are rlp encoded.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct WalInfo {
+    pub epoch_id: u64,
+    pub round: u64,
+    /// The prevote quorum certificate which locks the node on a proposal.
+    pub lock: Option<Vec<u8>>,
+    /// The signed proposal of the lock.
+    pub lock_proposal: Option<Vec<u8>>,
+    /// The proposals signed by the node.
+    pub proposals: Vec<Vec<u8>>,
+    /// The votes signed by the node.
+    pub votes: Vec<Vec<u8>>,
+}
+
+// The messages of the epoch seen by the node, one of which becomes the lock
+// once the node precommits.
+#[derive(Default)]
+struct WalCache {
+    info:        WalInfo,
+    prevote_qcs: HashMap<(u64, Bytes), Vec<u8>>,
+    proposals:   HashMap<Bytes, Vec<u8>>,
+}
+
+/// The write-ahead log of the consensus. The proposals and votes signed by
+/// the node are saved before they are sent, and refused if they conflict
+/// with the saved ones, so a restarted validator never signs twice in a
+/// round. The log is replayed into overlord on restart and cleared when an
+/// epoch is committed.
+pub struct ConsensusWal {
+    path:  PathBuf,
+    cache: Mutex<WalCache>,
+}
+
+impl ConsensusWal {
+    pub fn new(path: PathBuf) -> ProtocolResult<Self> {
+        fs::create_dir_all(&path).map_err(|e| ConsensusError::WalErr(e.to_string()))?;
+
+        let file = path.join(WAL_FILE);
+        let info = if file.exists() {
+            let bytes = fs::read(&file).map_err(|e| ConsensusError::WalErr(e.to_string()))?;
+            deserialize(&bytes).map_err(|e| ConsensusError::WalErr(e.to_string()))?
+        } else {
+            WalInfo::default()
+        };
+
+        let cache = WalCache {
+            info,
+            ..Default::default()
+        };
+        Ok(ConsensusWal {
+            path,
+            cache: Mutex::new(cache),
+        })
+    }
+
+    pub fn info(&self) -> WalInfo {
+        self.cache.lock().info.clone()
+    }
+
+    /// Clear the log for a new epoch.
+    pub fn reset(&self, epoch_id: u64) -> ProtocolResult<()> {
+        let mut cache = self.cache.lock();
+        *cache = WalCache::default();
+        cache.info.epoch_id = epoch_id;
+        self.persist(&cache.info)
+    }
+
+    /// Save a proposal signed by the node, a different proposal of the same
+    /// round is refused.
+    pub fn save_proposal(&self, signed_proposal: &SignedProposal<FixedPill>) -> ProtocolResult<()> {
+        let proposal = &signed_proposal.proposal;
+        let mut cache = self.cache.lock();
+        if !enter_epoch(&mut cache, proposal.epoch_id) {
+            return Ok(());
+        }
+
+        for bytes in cache.info.proposals.iter() {
+            let saved: SignedProposal<FixedPill> = rlp::decode(bytes)
+                .map_err(|_| ConsensusError::WalErr("invalid proposal".to_owned()))?;
+            if saved.proposal.round == proposal.round {
+                if saved.proposal.epoch_hash == proposal.epoch_hash {
+                    return Ok(());
+                }
+                return Err(ConsensusError::DoubleSign(proposal.epoch_id, proposal.round).into());
+            }
+        }
+
+        let bytes = signed_proposal.rlp_bytes();
+        cache
+            .proposals
+            .insert(proposal.epoch_hash.clone(), bytes.clone());
+        cache.info.proposals.push(bytes);
+        cache.info.round = cache.info.round.max(proposal.round);
+        self.persist(&cache.info)
+    }
+
+    /// Save a vote signed by the node, a different vote of the same round and
+    /// type is refused. A precommit locks the node on the proposal.
+    pub fn save_vote(&self, signed_vote: &SignedVote) -> ProtocolResult<()> {
+        let vote = &signed_vote.vote;
+        let mut cache = self.cache.lock();
+        if !enter_epoch(&mut cache, vote.epoch_id) {
+            return Ok(());
+        }
+
+        for bytes in cache.info.votes.iter() {
+            let saved: SignedVote = rlp::decode(bytes)
+                .map_err(|_| ConsensusError::WalErr("invalid vote".to_owned()))?;
+            if saved.vote.round == vote.round && saved.vote.vote_type == vote.vote_type {
+                if saved.vote.epoch_hash == vote.epoch_hash {
+                    return Ok(());
+                }
+                return Err(ConsensusError::DoubleSign(vote.epoch_id, vote.round).into());
+            }
+        }
+
+        if vote.vote_type == VoteType::Precommit && !vote.epoch_hash.is_empty() {
+            let key = (vote.round, vote.epoch_hash.clone());
+            if let Some(qc) = cache.prevote_qcs.get(&key).cloned() {
+                cache.info.lock = Some(qc);
+                cache.info.lock_proposal = cache.proposals.get(&vote.epoch_hash).cloned();
+            }
+        }
+
+        cache.info.votes.push(signed_vote.rlp_bytes());
+        cache.info.round = cache.info.round.max(vote.round);
+        self.persist(&cache.info)
+    }
+
+    /// Keep a proposal of the epoch in memory, it is saved if the node gets
+    /// locked on it.
+    pub fn see_proposal(&self, signed_proposal: &SignedProposal<FixedPill>) {
+        let proposal = &signed_proposal.proposal;
+        let mut cache = self.cache.lock();
+        if proposal.epoch_id == cache.info.epoch_id {
+            cache
+                .proposals
+                .insert(proposal.epoch_hash.clone(), signed_proposal.rlp_bytes());
+        }
+    }
+
+    /// Keep a verified prevote quorum certificate of the epoch in memory, it
+    /// is saved as the lock if the node precommits for it.
+    pub fn see_qc(&self, qc: &AggregatedVote) {
+        let mut cache = self.cache.lock();
+        if qc.vote_type == VoteType::Prevote
+            && qc.epoch_id == cache.info.epoch_id
+            && !qc.epoch_hash.is_empty()
+        {
+            cache
+                .prevote_qcs
+                .insert((qc.round, qc.epoch_hash.clone()), qc.rlp_bytes());
+        }
+    }
+
+    // Write to a temporary file and rename it, so that a crash never leaves a
+    // partial log.
+    fn persist(&self, info: &WalInfo) -> ProtocolResult<()> {
+        let bytes = serialize(info).map_err(|e| ConsensusError::WalErr(e.to_string()))?;
+        let tmp = self.path.join(WAL_TMP_FILE);
+
+        let mut file = fs::File::create(&tmp).map_err(|e| ConsensusError::WalErr(e.to_string()))?;
+        file.write_all(&bytes)
+            .and_then(|_| file.sync_all())
+            .map_err(|e| ConsensusError::WalErr(e.to_string()))?;
+        fs::rename(&tmp, self.path.join(WAL_FILE))
+            .map_err(|e| ConsensusError::WalErr(e.to_string()))?;
+        Ok(())
+    }
+}
+
+// Move the log to a later epoch, return false if the epoch has passed.
+fn enter_epoch(cache: &mut WalCache, epoch_id: u64) -> bool {
+    if epoch_id < cache.info.epoch_id {
+        return false;
+    }
+    if epoch_id > cache.info.epoch_id {
+        *cache = WalCache::default();
+        cache.info.epoch_id = epoch_id;
+    }
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use bytes::Bytes;
+    use overlord::types::{AggregatedSignature, AggregatedVote, SignedVote, Vote, VoteType};
+    use rand::random;
+
+    use super::ConsensusWal;
+
+    fn mock_vote(epoch_id: u64, round: u64, vote_type: VoteType, epoch_hash: &str) -> SignedVote {
+        SignedVote {
+            signature: Bytes::from("signature"),
+            vote:      Vote {
+                epoch_id,
+                round,
+                vote_type,
+                epoch_hash: Bytes::from(epoch_hash),
+            },
+        }
+    }
+
+    fn mock_prevote_qc(epoch_id: u64, round: u64, epoch_hash: &str) -> AggregatedVote {
+        AggregatedVote {
+            signature: AggregatedSignature {
+                signature:      Bytes::from("signature"),
+                address_bitmap: Bytes::from("bitmap"),
+            },
+            vote_type: VoteType::Prevote,
+            epoch_id,
+            round,
+            epoch_hash: Bytes::from(epoch_hash),
+        }
+    }
+
+    fn temp_path() -> PathBuf {
+        std::env::temp_dir().join(format!("consensus_wal_{}", random::<u64>()))
+    }
+
+    #[test]
+    fn test_wal_refuse_double_sign() {
+        let path = temp_path();
+        let wal = ConsensusWal::new(path.clone()).unwrap();
+        wal.reset(1).unwrap();
+
+        wal.save_vote(&mock_vote(1, 0, VoteType::Prevote, "a"))
+            .unwrap();
+        // The same vote can be sent again.
+        wal.save_vote(&mock_vote(1, 0, VoteType::Prevote, "a"))
+            .unwrap();
+        assert!(wal
+            .save_vote(&mock_vote(1, 0, VoteType::Prevote, "b"))
+            .is_err());
+        assert!(wal
+            .save_vote(&mock_vote(1, 0, VoteType::Prevote, ""))
+            .is_err());
+        wal.save_vote(&mock_vote(1, 0, VoteType::Precommit, ""))
+            .unwrap();
+        wal.save_vote(&mock_vote(1, 1, VoteType::Prevote, "b"))
+            .unwrap();
+
+        // The votes survive a restart.
+        let wal = ConsensusWal::new(path.clone()).unwrap();
+        assert_eq!(wal.info().epoch_id, 1);
+        assert_eq!(wal.info().round, 1);
+        assert_eq!(wal.info().votes.len(), 3);
+        assert!(wal
+            .save_vote(&mock_vote(1, 1, VoteType::Prevote, "a"))
+            .is_err());
+
+        // A new epoch clears the log.
+        wal.reset(2).unwrap();
+        wal.save_vote(&mock_vote(2, 0, VoteType::Prevote, "b"))
+            .unwrap();
+        // The votes of a passed epoch are not saved.
+        wal.save_vote(&mock_vote(1, 0, VoteType::Prevote, "c"))
+            .unwrap();
+        assert_eq!(wal.info().votes.len(), 1);
+
+        fs::remove_dir_all(path).unwrap();
+    }
+
+    #[test]
+    fn test_wal_lock() {
+        let path = temp_path();
+        let wal = ConsensusWal::new(path.clone()).unwrap();
+        wal.reset(1).unwrap();
+
+        wal.see_qc(&mock_prevote_qc(1, 0, "a"));
+        wal.save_vote(&mock_vote(1, 0, VoteType::Prevote, "a"))
+            .unwrap();
+        assert_eq!(wal.info().lock, None);
+
+        wal.save_vote(&mock_vote(1, 0, VoteType::Precommit, "a"))
+            .unwrap();
+        let wal = ConsensusWal::new(path.clone()).unwrap();
+        let lock: AggregatedVote = rlp::decode(&wal.info().lock.unwrap()).unwrap();
+        assert_eq!(lock.epoch_hash, Bytes::from("a"));
+        assert_eq!(lock.round, 0);
+
+        fs::remove_dir_all(path).unwrap();
+    }
+}
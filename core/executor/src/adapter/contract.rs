@@ -16,6 +16,14 @@ use crate::trie::MPTTrie;
 
 pub type RcGeneralContractStateAdapter<DB> = Rc<RefCell<GeneralContractStateAdapter<DB>>>;
 
+// A key stashed while the state is traced, along with its fixed encoded
+// values, `before` is `None` if the key is new.
+pub struct Traced {
+    pub key:    Bytes,
+    pub before: Option<Bytes>,
+    pub after:  Bytes,
+}
+
 pub struct GeneralContractStateAdapter<DB: TrieDB> {
     trie: MPTTrie<DB>,
 
@@ -23,6 +31,10 @@ pub struct GeneralContractStateAdapter<DB: TrieDB> {
     // serializations.
     cache_map: HashMap<Bytes, Bytes>,
     stash_map: HashMap<Bytes, Bytes>,
+
+    // The values of the keys before they are stashed for the first time since
+    // the last `take_trace`, it is kept only when the state is traced.
+    trace_map: Option<HashMap<Bytes, Option<Bytes>>>,
 }
 
 impl<DB: TrieDB> GeneralContractStateAdapter<DB> {
@@ -32,8 +44,45 @@ impl<DB: TrieDB> GeneralContractStateAdapter<DB> {
 
             cache_map: HashMap::new(),
             stash_map: HashMap::new(),
+            trace_map: None,
         }
     }
+
+    pub fn enable_trace(&mut self) {
+        self.trace_map = Some(HashMap::new());
+    }
+
+    // Take the keys stashed since the last call, along with their values
+    // before and after. The keys written back with the same value are
+    // skipped.
+    pub fn take_trace(&mut self) -> Vec<Traced> {
+        let trace_map = match self.trace_map.as_mut() {
+            Some(trace_map) => trace_map,
+            None => return vec![],
+        };
+
+        let mut trace = Vec::with_capacity(trace_map.len());
+        for (key, before) in trace_map.drain() {
+            let after = match self.stash_map.get(&key) {
+                Some(after) => after.clone(),
+                None => continue,
+            };
+            if before.as_ref() == Some(&after) {
+                continue;
+            }
+
+            trace.push(Traced { key, before, after });
+        }
+
+        trace
+    }
+
+    pub fn get_proof<Schema: ContractSchema>(
+        &self,
+        key: &<Schema as ContractSchema>::Key,
+    ) -> ProtocolResult<Vec<Bytes>> {
+        self.trie.get_proof(&key.encode_fixed()?)
+    }
 }
 
 impl<DB: TrieDB> ContractStateAdapter for GeneralContractStateAdapter<DB> {
@@ -93,6 +142,20 @@ impl<DB: TrieDB> ContractStateAdapter for GeneralContractStateAdapter<DB> {
     }
 
     fn stash(&mut self) -> ProtocolResult<()> {
+        if let Some(trace_map) = self.trace_map.as_mut() {
+            for key in self.cache_map.keys() {
+                if trace_map.contains_key(key) {
+                    continue;
+                }
+
+                let before = match self.stash_map.get(key) {
+                    Some(before) => Some(before.clone()),
+                    None => self.trie.get(key)?,
+                };
+                trace_map.insert(key.clone(), before);
+            }
+        }
+
         for (k, v) in self.cache_map.drain() {
             self.stash_map.insert(k, v);
         }
@@ -101,6 +164,10 @@ impl<DB: TrieDB> ContractStateAdapter for GeneralContractStateAdapter<DB> {
     }
 
     fn commit(&mut self) -> ProtocolResult<MerkleRoot> {
+        if let Some(trace_map) = self.trace_map.as_mut() {
+            trace_map.clear();
+        }
+
         for (key, value) in self.stash_map.drain() {
             self.trie.insert(key, value)?;
         }
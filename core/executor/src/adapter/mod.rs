@@ -2,4 +2,5 @@ mod contract;
 
 pub use contract::{
     GeneralContractStateAdapter, GeneralContractStateAdapterError, RcGeneralContractStateAdapter,
+    Traced,
 };
@@ -12,7 +12,25 @@ lazy_static! {
     static ref CYCLES_TABLE: HashMap<CyclesAction, u64> = {
         let mut table = HashMap::new();
         table.insert(CyclesAction::AccountTransfer, NATIVE_BASE_CYCLES * 21);
+        table.insert(CyclesAction::AccountBatchTransfer, NATIVE_BASE_CYCLES * 15);
+        table.insert(CyclesAction::AccountApprove, NATIVE_BASE_CYCLES * 21);
+        table.insert(CyclesAction::AccountTransferFrom, NATIVE_BASE_CYCLES * 21);
         table.insert(CyclesAction::BankRegister, NATIVE_BASE_CYCLES * 210);
+        table.insert(CyclesAction::BankMint, NATIVE_BASE_CYCLES * 21);
+        table.insert(CyclesAction::BankBurn, NATIVE_BASE_CYCLES * 21);
+        table.insert(CyclesAction::DispatcherInvoke, NATIVE_BASE_CYCLES * 10);
+        table.insert(CyclesAction::GovernancePropose, NATIVE_BASE_CYCLES * 50);
+        table.insert(CyclesAction::GovernanceApprove, NATIVE_BASE_CYCLES * 21);
+        table.insert(CyclesAction::MultisigCreate, NATIVE_BASE_CYCLES * 210);
+        table.insert(CyclesAction::MultisigVerify, NATIVE_BASE_CYCLES * 30);
+        table.insert(CyclesAction::MultisigPropose, NATIVE_BASE_CYCLES * 50);
+        table.insert(CyclesAction::MultisigApprove, NATIVE_BASE_CYCLES * 21);
+        table.insert(CyclesAction::VMDeploy, NATIVE_BASE_CYCLES * 210);
+        table.insert(CyclesAction::VMInstruction, 1);
+        table.insert(CyclesAction::VMStorageGet, NATIVE_BASE_CYCLES * 20);
+        table.insert(CyclesAction::VMStorageSet, NATIVE_BASE_CYCLES * 50);
+        table.insert(CyclesAction::VMLog, NATIVE_BASE_CYCLES * 30);
+        table.insert(CyclesAction::VMContractCall, NATIVE_BASE_CYCLES * 30);
         table
     };
 }
@@ -20,7 +38,31 @@ lazy_static! {
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum CyclesAction {
     AccountTransfer,
+    AccountBatchTransfer,
+    AccountApprove,
+    AccountTransferFrom,
     BankRegister,
+    BankMint,
+    BankBurn,
+    DispatcherInvoke,
+    GovernancePropose,
+    GovernanceApprove,
+    MultisigCreate,
+    MultisigVerify,
+    MultisigPropose,
+    MultisigApprove,
+    VMDeploy,
+    VMInstruction,
+    VMStorageGet,
+    VMStorageSet,
+    VMLog,
+    VMContractCall,
+}
+
+pub fn cycles_of(action: CyclesAction) -> u64 {
+    *CYCLES_TABLE
+        .get(&action)
+        .unwrap_or_else(|| panic!("cycles action {:?} uninitialized", action))
 }
 
 pub fn consume_cycles(
@@ -28,10 +70,21 @@ pub fn consume_cycles(
     cycles_used: u64,
     cycles_limit: u64,
 ) -> ProtocolResult<u64> {
-    let cycles_used = cycles_used
-        + CYCLES_TABLE
-            .get(&action)
-            .unwrap_or_else(|| panic!("cycles action {:?} uninitialized", action));
+    consume_cycles_by(action, 1, cycles_used, cycles_limit)
+}
+
+// Consume the cycles of an action repeated `times`, e.g. the instructions
+// executed by the VM.
+pub fn consume_cycles_by(
+    action: CyclesAction,
+    times: u64,
+    cycles_used: u64,
+    cycles_limit: u64,
+) -> ProtocolResult<u64> {
+    let cycles_used = cycles_of(action)
+        .checked_mul(times)
+        .and_then(|cycles| cycles.checked_add(cycles_used))
+        .ok_or(CyclesError::OutOfCycles)?;
 
     if cycles_used > cycles_limit {
         return Err(CyclesError::OutOfCycles.into());
@@ -0,0 +1,97 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::rc::Rc;
+
+use bytes::Bytes;
+use derive_more::{Display, From};
+
+use protocol::traits::executor::contract::NativeContract;
+use protocol::traits::executor::{Dispatcher, RcInvokeContext};
+use protocol::types::ContractAddress;
+use protocol::{ProtocolError, ProtocolErrorKind, ProtocolResult};
+
+use crate::cycles::{consume_cycles, CyclesAction};
+
+pub type RcNativeContract = Rc<RefCell<dyn NativeContract>>;
+
+/// NativeDispatcher routes the contract call to the native contract registered
+/// under the contract address.
+#[derive(Default)]
+pub struct NativeDispatcher {
+    contracts: BTreeMap<ContractAddress, RcNativeContract>,
+}
+
+impl NativeDispatcher {
+    pub fn new() -> Self {
+        Self {
+            contracts: BTreeMap::new(),
+        }
+    }
+
+    pub fn register(
+        &mut self,
+        address: ContractAddress,
+        contract: RcNativeContract,
+    ) -> ProtocolResult<()> {
+        if self.contracts.contains_key(&address) {
+            return Err(NativeDispatcherError::ContractExists { address }.into());
+        }
+
+        self.contracts.insert(address, contract);
+        Ok(())
+    }
+
+    pub fn contains(&self, address: &ContractAddress) -> bool {
+        self.contracts.contains_key(address)
+    }
+}
+
+impl Dispatcher for NativeDispatcher {
+    fn invoke(
+        &self,
+        ictx: RcInvokeContext,
+        address: ContractAddress,
+        method: &str,
+        args: Vec<Bytes>,
+    ) -> ProtocolResult<Bytes> {
+        let contract =
+            self.contracts
+                .get(&address)
+                .ok_or(NativeDispatcherError::ContractNotFound {
+                    address: address.clone(),
+                })?;
+
+        let cycles_used = consume_cycles(
+            CyclesAction::DispatcherInvoke,
+            ictx.borrow().cycles_used,
+            ictx.borrow().cycles_limit,
+        )?;
+        ictx.borrow_mut().cycles_used = cycles_used;
+
+        contract.borrow_mut().call(ictx, method, args)
+    }
+}
+
+#[derive(Debug, Display, From)]
+pub enum NativeDispatcherError {
+    #[display(fmt = "contract {:?} already registered", address)]
+    ContractExists { address: ContractAddress },
+
+    #[display(fmt = "contract {:?} not found", address)]
+    ContractNotFound { address: ContractAddress },
+
+    #[display(fmt = "method {} not found", method)]
+    MethodNotFound { method: String },
+
+    #[display(fmt = "invalid args of method {}", method)]
+    InvalidArgs { method: String },
+}
+
+impl Error for NativeDispatcherError {}
+
+impl From<NativeDispatcherError> for ProtocolError {
+    fn from(err: NativeDispatcherError) -> ProtocolError {
+        ProtocolError::new(ProtocolErrorKind::Executor, Box::new(err))
+    }
+}
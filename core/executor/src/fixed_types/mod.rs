@@ -1,9 +1,11 @@
 use bytes::Bytes;
 
 use protocol::traits::executor::ContractSchema;
-use protocol::types::{Account, Address, Asset, AssetID};
+use protocol::types::{
+    Account, Address, Asset, AssetID, ChainParams, ContractAddress, GovernanceProposal, Hash,
+    MultisigProposal, MultisigWallet, UserAddress, ValidatorSet,
+};
 
-#[allow(dead_code)]
 pub struct FixedBytesSchema;
 impl ContractSchema for FixedBytesSchema {
     type Key = Bytes;
@@ -21,3 +23,39 @@ impl ContractSchema for FixedAccountSchema {
     type Key = Address;
     type Value = Account;
 }
+
+pub struct FixedContractCodeSchema;
+impl ContractSchema for FixedContractCodeSchema {
+    type Key = ContractAddress;
+    type Value = Bytes;
+}
+
+pub struct FixedMultisigWalletSchema;
+impl ContractSchema for FixedMultisigWalletSchema {
+    type Key = UserAddress;
+    type Value = MultisigWallet;
+}
+
+pub struct FixedMultisigProposalSchema;
+impl ContractSchema for FixedMultisigProposalSchema {
+    type Key = Hash;
+    type Value = MultisigProposal;
+}
+
+pub struct FixedValidatorSetSchema;
+impl ContractSchema for FixedValidatorSetSchema {
+    type Key = Bytes;
+    type Value = ValidatorSet;
+}
+
+pub struct FixedChainParamsSchema;
+impl ContractSchema for FixedChainParamsSchema {
+    type Key = Bytes;
+    type Value = ChainParams;
+}
+
+pub struct FixedGovernanceProposalSchema;
+impl ContractSchema for FixedGovernanceProposalSchema {
+    type Key = Hash;
+    type Value = GovernanceProposal;
+}
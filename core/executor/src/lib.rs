@@ -1,13 +1,17 @@
 mod adapter;
 mod cycles;
+mod dispatcher;
 mod fixed_types;
 mod native_contract;
+pub mod snapshot;
+pub mod state_sync;
 #[cfg(test)]
 mod tests;
 pub mod trie;
+mod vm;
 
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::error::Error;
 use std::num::ParseIntError;
 use std::rc::Rc;
@@ -17,64 +21,129 @@ use std::u64;
 use bytes::Bytes;
 use derive_more::{Display, From};
 
+use protocol::fixed_codec::ProtocolFixedCodec;
 use protocol::traits::executor::contract::{AccountContract, BankContract, ContractStateAdapter};
 use protocol::traits::executor::{
-    Executor, ExecutorExecResp, ExecutorFactory, InvokeContext, RcInvokeContext, TrieDB,
+    ContractSchema, Dispatcher, Executor, ExecutorExecResp, ExecutorFactory, ExecutorReadResp,
+    InvokeContext, RcInvokeContext, TrieDB,
 };
 use protocol::types::{
-    Address, AssetID, Balance, Bloom, ContractAddress, ContractType, Fee, Genesis, Hash,
-    MerkleRoot, Receipt, ReceiptResult, SignedTransaction, TransactionAction, UserAddress,
+    Account, Address, Asset, AssetID, AssetParams, Balance, BalanceDiff, Bloom, BloomInput,
+    CarryingAsset, ChainParams, ContractAddress, ContractType, Fee, Genesis, Hash, Log, MerkleRoot,
+    MultisigProposal, RawTransaction, Receipt, ReceiptResult, SignedTransaction, StateDiff,
+    StateProof, StorageDiff, TransactionAction, TransferOutput, UserAddress, Validator,
+    ValidatorSet,
 };
 use protocol::{ProtocolError, ProtocolErrorKind, ProtocolResult};
 
-use crate::adapter::{GeneralContractStateAdapter, RcGeneralContractStateAdapter};
+use crate::adapter::{GeneralContractStateAdapter, RcGeneralContractStateAdapter, Traced};
+use crate::dispatcher::NativeDispatcher;
+use crate::fixed_types::{FixedAccountSchema, FixedAssetSchema};
 use crate::native_contract::{
-    NativeAccountContract, NativeBankContract, ACCOUNT_CONTRACT_ADDRESS, BANK_CONTRACT_ADDRESS,
+    NativeAccountContract, NativeBankContract, NativeGovernanceContract, NativeMultisigContract,
+    ACCOUNT_CONTRACT_ADDRESS, BANK_CONTRACT_ADDRESS, BURN_LOG_TOPIC, GOVERNANCE_CONTRACT_ADDRESS,
+    MINT_LOG_TOPIC, MULTISIG_CONTRACT_ADDRESS, MULTISIG_EXECUTE_LOG_TOPIC, VM_CONTRACT_ADDRESS,
 };
-use crate::trie::MPTTrie;
+use crate::trie::{CacheDB, MPTTrie};
+use crate::vm::RiscvVM;
+
+pub use crate::native_contract::bls_pop_hash;
+
+// The cycles limit of `read_call`, which has no fee to limit the cycles.
+const READ_CALL_CYCLES_LIMIT: u64 = 99_999_999;
 
 pub struct TransactionExecutor<DB: TrieDB> {
     chain_id:     Hash,
     epoch_id:     u64,
     cycles_price: u64,
     coinbase:     Address,
+    trace_state:  bool,
 
-    trie:              MPTTrie<DB>,
-    account_contract:  NativeAccountContract<GeneralContractStateAdapter<DB>>,
-    bank_account:      NativeBankContract<GeneralContractStateAdapter<DB>>,
-    state_adapter_map: HashMap<Address, RcGeneralContractStateAdapter<DB>>,
+    trie:                MPTTrie<DB>,
+    account_contract:    NativeAccountContract<GeneralContractStateAdapter<DB>>,
+    bank_account:        NativeBankContract<GeneralContractStateAdapter<DB>>,
+    multisig_contract:   NativeMultisigContract<GeneralContractStateAdapter<DB>>,
+    governance_contract: NativeGovernanceContract<GeneralContractStateAdapter<DB>>,
+    state_adapter_map:   HashMap<Address, RcGeneralContractStateAdapter<DB>>,
+    dispatcher:          Rc<NativeDispatcher>,
+    vm:                  RiscvVM<DB>,
+    db:                  Arc<DB>,
 }
 
-impl<DB: TrieDB> Executor for TransactionExecutor<DB> {
+impl<DB: 'static + TrieDB> Executor for TransactionExecutor<DB> {
     fn create_genesis(&mut self, genesis: &Genesis) -> ProtocolResult<MerkleRoot> {
+        // The genesis is made by the governance contract rather than the
+        // coinbase of the node, so that every node builds the same state. The
+        // governance contract is the issuer of the genesis assets, which can
+        // never mint or burn them.
         let ictx = InvokeContext {
             chain_id:       self.chain_id.clone(),
             cycles_price:   self.cycles_price,
             epoch_id:       0,
-            coinbase:       self.coinbase.clone(),
-            caller:         self.coinbase.clone(),
+            coinbase:       GOVERNANCE_CONTRACT_ADDRESS.clone(),
+            caller:         GOVERNANCE_CONTRACT_ADDRESS.clone(),
             cycles_used:    0,
             cycles_limit:   999_999_999_999,
             fee_asset_id:   Hash::from_empty(),
             carrying_asset: None,
+            logs:           vec![],
         };
         let ictx = Rc::new(RefCell::new(ictx));
 
         // create system token
+        let mut supplies = BTreeMap::new();
         let system_token = &genesis.system_token;
-        let code = Bytes::from(
-            hex::decode(system_token.code.clone()).map_err(TransactionExecutorError::from)?,
-        );
-        let token_contract_address = ContractAddress::from_code(code, 0, ContractType::Asset)?;
-
-        self.bank_account.register(
+        let asset = self.register_genesis_asset(
             Rc::clone(&ictx),
-            &token_contract_address,
+            &system_token.code,
             system_token.name.clone(),
             system_token.symbol.clone(),
-            Balance::from(system_token.supply),
+            system_token.supply,
+            system_token.decimals,
         )?;
+        supplies.insert(asset.id, asset.supply);
+
+        // create the other assets
+        for asset in &genesis.assets {
+            let asset = self.register_genesis_asset(
+                Rc::clone(&ictx),
+                &asset.code,
+                asset.name.clone(),
+                asset.symbol.clone(),
+                asset.supply,
+                asset.decimals,
+            )?;
+            supplies.insert(asset.id, asset.supply);
+        }
+
+        // deploy the contracts, the address is derived from the code with nonce
+        // 0, so the code of each contract must be unique.
+        let mut deployed = BTreeSet::new();
+        for contract in &genesis.contracts {
+            let code = Bytes::from(
+                hex::decode(contract.code.clone()).map_err(TransactionExecutorError::from)?,
+            );
+            let contract_type = if contract.library {
+                ContractType::Library
+            } else {
+                ContractType::App
+            };
+            let address = ContractAddress::from_code(code.clone(), 0, contract_type)?;
+            if !deployed.insert(address.clone()) {
+                return Err(TransactionExecutorError::DuplicateGenesisContract { address }.into());
+            }
+
+            self.vm.deploy(Rc::clone(&ictx), &address, code)?;
+            self.account_contract
+                .update_storage_root(&Address::Contract(address), Hash::from_empty())?;
+        }
 
+        // allocate the assets, the balances of an asset must add up to the
+        // supply of it.
+        let mut allocated = supplies
+            .keys()
+            .map(|asset_id| (asset_id.clone(), Balance::from(0u64)))
+            .collect::<BTreeMap<_, _>>();
         for alloc in &genesis.state_alloc {
             let address = Address::from_hex(&alloc.address)?;
             self.account_contract.create_account(&address)?;
@@ -83,21 +152,83 @@ impl<DB: TrieDB> Executor for TransactionExecutor<DB> {
                 let asset_id = Hash::from_hex(&asset.asset_id)?;
                 let balance_byets =
                     hex::decode(asset.balance.clone()).map_err(TransactionExecutorError::from)?;
+                let balance = Balance::from_bytes_be(balance_byets.as_ref());
 
-                self.account_contract.add_balance(
-                    &asset_id,
-                    &address,
-                    Balance::from_bytes_be(balance_byets.as_ref()),
-                )?;
+                match allocated.get_mut(&asset_id) {
+                    Some(sum) => *sum += &balance,
+                    None => {
+                        return Err(
+                            TransactionExecutorError::UnknownGenesisAsset { asset_id }.into()
+                        )
+                    }
+                }
+                self.account_contract
+                    .add_balance(&asset_id, &address, balance)?;
+            }
+        }
+        for (asset_id, supply) in supplies.into_iter() {
+            let allocated = allocated.remove(&asset_id).unwrap_or_default();
+            if allocated != supply {
+                return Err(TransactionExecutorError::GenesisSupplyMismatch {
+                    asset_id,
+                    supply,
+                    allocated,
+                }
+                .into());
             }
         }
 
+        // set the validators of the consensus
+        let validators = genesis
+            .validators
+            .iter()
+            .map(|v| {
+                Ok(Validator {
+                    address:        UserAddress::from_hex(&v.address)?,
+                    propose_weight: v.propose_weight,
+                    vote_weight:    v.vote_weight,
+                    bls_pub_key:    Bytes::from(
+                        hex::decode(v.bls_pub_key.clone())
+                            .map_err(TransactionExecutorError::from)?,
+                    ),
+                })
+            })
+            .collect::<ProtocolResult<Vec<_>>>()?;
+        let pops = genesis
+            .validators
+            .iter()
+            .map(|v| {
+                Ok(Bytes::from(
+                    hex::decode(v.bls_pop.clone()).map_err(TransactionExecutorError::from)?,
+                ))
+            })
+            .collect::<ProtocolResult<Vec<_>>>()?;
+        self.governance_contract.init_validators(validators, pops)?;
+
+        // set the chain params
+        let params = &genesis.params;
+        self.governance_contract.init_params(ChainParams {
+            cycles_limit: params.cycles_limit,
+            cycles_price: params.cycles_price,
+            interval:     params.interval,
+            timeout_gap:  params.timeout_gap,
+        })?;
+
         self.stash()?;
-        self.commit()
+        let state_root = self.commit()?;
+        self.db.commit_epoch(0)?;
+        Ok(state_root)
     }
 
     fn exec(&mut self, signed_txs: Vec<SignedTransaction>) -> ProtocolResult<ExecutorExecResp> {
         let mut receipts = Vec::with_capacity(signed_txs.len());
+        let mut state_diffs = vec![];
+        if self.trace_state {
+            for (_, state) in self.state_adapter_map.iter() {
+                state.borrow_mut().enable_trace();
+            }
+            self.vm.enable_trace();
+        }
 
         for signed_tx in signed_txs.into_iter() {
             let tx_hash = signed_tx.tx_hash.clone();
@@ -110,8 +241,39 @@ impl<DB: TrieDB> Executor for TransactionExecutor<DB> {
                 &signed_tx,
             )?;
 
-            let res = match self.dispatch(Rc::clone(&ictx), signed_tx) {
-                Ok(res) => {
+            let nonce = signed_tx.raw.nonce.clone();
+            let timeout = signed_tx.raw.timeout;
+
+            // The prepaid fee must survive the revert of a failed transaction, so
+            // stash it before dispatching. A replayed transaction or one which can
+            // not afford the fee is rejected without any state change.
+            let prepaid = self
+                .check_replay(Rc::clone(&ictx), &nonce, timeout)
+                .and_then(|_| self.prepay_fee(Rc::clone(&ictx)));
+
+            let res = match prepaid {
+                Ok(prepaid) => {
+                    self.stash()?;
+
+                    let res = match self.dispatch(Rc::clone(&ictx), &signed_tx.raw.action) {
+                        Ok(res) => {
+                            self.stash()?;
+                            res
+                        }
+                        Err(e) => {
+                            self.revert()?;
+                            ictx.borrow_mut().logs.clear();
+                            ReceiptResult::Fail {
+                                system: e.to_string(),
+                                user:   "".to_owned(),
+                            }
+                        }
+                    };
+
+                    self.refund_fee(Rc::clone(&ictx), prepaid)?;
+                    self.account_contract
+                        .use_nonce(Rc::clone(&ictx), nonce, timeout)?;
+                    self.account_contract.inc_nonce(Rc::clone(&ictx))?;
                     self.stash()?;
                     res
                 }
@@ -124,10 +286,18 @@ impl<DB: TrieDB> Executor for TransactionExecutor<DB> {
                 }
             };
 
-            self.account_contract.inc_nonce(Rc::clone(&ictx))?;
+            if self.trace_state {
+                state_diffs.push(self.take_state_diff(tx_hash.clone())?);
+            }
+
+            // Commit the state of every transaction, so that a receipt can be
+            // proven and compared on its own. The reward of `coinbase` is not
+            // included in the state root of the last receipt. The nodes stay in
+            // the cache db until the epoch is committed.
+            let state_root = self.commit()?;
 
             let receipt = Receipt {
-                state_root: Hash::from_empty(),
+                state_root,
                 epoch_id: ictx.borrow().epoch_id,
                 cycles_used: Fee {
                     asset_id: ictx.borrow().fee_asset_id.clone(),
@@ -135,10 +305,16 @@ impl<DB: TrieDB> Executor for TransactionExecutor<DB> {
                 },
                 result: res,
                 tx_hash,
+                logs: ictx.borrow().logs.clone(),
             };
             receipts.push(receipt);
         }
 
+        let mut logs_bloom = Bloom::default();
+        for receipt in receipts.iter() {
+            accrue_logs(&mut logs_bloom, &receipt.logs);
+        }
+
         //  Calculate the total fee and reward `coinbsae`
         let mut all_cycles_used: Vec<Fee> = vec![];
         for receipt in receipts.iter() {
@@ -151,44 +327,218 @@ impl<DB: TrieDB> Executor for TransactionExecutor<DB> {
                 Balance::from(cycles_used.cycle),
             )?;
         }
+        self.stash()?;
 
         // commit state
         let state_root = self.commit()?;
-        for receipt in receipts.iter_mut() {
-            receipt.state_root = state_root.clone();
-        }
+        self.db.commit_epoch(self.epoch_id)?;
+
+        // The validators and the params changed by this epoch are used from
+        // the next epoch.
+        let validator_set = self.governance_contract.get_validator_set()?;
+        let params = self.governance_contract.get_params()?;
 
         Ok(ExecutorExecResp {
             receipts,
             all_cycles_used,
             state_root,
-            logs_bloom: Bloom::default(),
+            logs_bloom,
+            validator_set,
+            params,
+            state_diffs,
         })
     }
 
+    fn enable_state_trace(&mut self) {
+        self.trace_state = true;
+    }
+
+    fn simulate(&mut self, signed_tx: SignedTransaction) -> ProtocolResult<ExecutorReadResp> {
+        let ictx = gen_invoke_ctx(
+            self.epoch_id,
+            self.cycles_price,
+            &self.chain_id,
+            &self.coinbase,
+            &signed_tx,
+        )?;
+
+        let res = self.dispatch(Rc::clone(&ictx), &signed_tx.raw.action);
+        self.read_resp(ictx, res)
+    }
+
+    fn read_call(
+        &mut self,
+        caller: &Address,
+        contract: &ContractAddress,
+        method: &str,
+        args: Vec<Bytes>,
+    ) -> ProtocolResult<ExecutorReadResp> {
+        let ictx = InvokeContext {
+            chain_id:       self.chain_id.clone(),
+            cycles_price:   self.cycles_price,
+            epoch_id:       self.epoch_id,
+            coinbase:       self.coinbase.clone(),
+            caller:         caller.clone(),
+            cycles_used:    0,
+            cycles_limit:   READ_CALL_CYCLES_LIMIT,
+            fee_asset_id:   Hash::from_empty(),
+            carrying_asset: None,
+            logs:           vec![],
+        };
+        let ictx = Rc::new(RefCell::new(ictx));
+
+        let res = self.handle_call(Rc::clone(&ictx), contract, method, &args);
+        self.read_resp(ictx, res)
+    }
+
     fn get_balance(&self, address: &Address, id: &AssetID) -> ProtocolResult<Balance> {
         self.account_contract.get_balance(id, address)
     }
+
+    fn is_nonce_used(&self, address: &Address, nonce: &Hash) -> ProtocolResult<bool> {
+        self.account_contract.is_nonce_used(address, nonce)
+    }
+
+    fn get_account_proof(&self, address: &Address) -> ProtocolResult<StateProof> {
+        self.get_state_proof::<FixedAccountSchema>(&ACCOUNT_CONTRACT_ADDRESS, address)
+    }
+
+    fn get_asset_proof(&self, id: &AssetID) -> ProtocolResult<StateProof> {
+        self.get_state_proof::<FixedAssetSchema>(&BANK_CONTRACT_ADDRESS, id)
+    }
+
+    fn get_chain_params(&self) -> ProtocolResult<ChainParams> {
+        self.governance_contract.get_params()
+    }
+
+    fn get_validator_set(&self) -> ProtocolResult<ValidatorSet> {
+        self.governance_contract.get_validator_set()
+    }
 }
 
-impl<DB: TrieDB> TransactionExecutor<DB> {
+impl<DB: 'static + TrieDB> TransactionExecutor<DB> {
+    fn state_adapter(
+        &self,
+        address: &Address,
+    ) -> ProtocolResult<&RcGeneralContractStateAdapter<DB>> {
+        let adapter = self.state_adapter_map.get(address).ok_or_else(|| {
+            TransactionExecutorError::InvalidNativeContract {
+                address: address.clone(),
+            }
+        })?;
+
+        Ok(adapter)
+    }
+
+    // Prove the key in the storage of a native contract under the committed
+    // state root.
+    fn get_state_proof<Schema: ContractSchema>(
+        &self,
+        contract: &Address,
+        key: &<Schema as ContractSchema>::Key,
+    ) -> ProtocolResult<StateProof> {
+        let contract_proof = self.trie.get_proof(&contract.as_bytes())?;
+        let storage_proof = self
+            .state_adapter(contract)?
+            .borrow()
+            .get_proof::<Schema>(key)?;
+
+        Ok(StateProof {
+            state_root: self.trie.root(),
+            contract_proof,
+            storage_proof,
+        })
+    }
+
+    // Collect the keys stashed since the last transaction in the storages of
+    // all the contracts, and compare the balances of the accounts among them.
+    fn take_state_diff(&mut self, tx_hash: Hash) -> ProtocolResult<StateDiff> {
+        let mut traces = vec![];
+        for (address, state) in self.state_adapter_map.iter() {
+            traces.push((address.clone(), state.borrow_mut().take_trace()));
+        }
+        for (address, trace) in self.vm.take_trace().into_iter() {
+            traces.push((Address::Contract(address), trace));
+        }
+
+        let mut balances = vec![];
+        let mut storages = vec![];
+        for (address, trace) in traces.into_iter() {
+            for traced in trace.into_iter() {
+                if address == *ACCOUNT_CONTRACT_ADDRESS {
+                    balances.extend(balance_diffs(&traced)?);
+                }
+
+                storages.push(StorageDiff {
+                    address: address.clone(),
+                    key:     traced.key,
+                    before:  traced.before.unwrap_or_else(Bytes::new),
+                    after:   traced.after,
+                });
+            }
+        }
+        balances.sort_by(|a, b| (&a.address, &a.asset_id).cmp(&(&b.address, &b.asset_id)));
+        storages.sort_by(|a, b| (&a.address, &a.key).cmp(&(&b.address, &b.key)));
+
+        Ok(StateDiff {
+            tx_hash,
+            balances,
+            storages,
+        })
+    }
+
+    // Drop all the changes made by a read-only execution.
+    fn read_resp(
+        &mut self,
+        ictx: RcInvokeContext,
+        res: ProtocolResult<ReceiptResult>,
+    ) -> ProtocolResult<ExecutorReadResp> {
+        self.revert()?;
+
+        let result = match res {
+            Ok(result) => result,
+            Err(e) => ReceiptResult::Fail {
+                system: e.to_string(),
+                user:   "".to_owned(),
+            },
+        };
+        let cycles_used = ictx.borrow().cycles_used;
+
+        Ok(ExecutorReadResp {
+            result,
+            cycles_used,
+        })
+    }
+
     fn dispatch(
         &mut self,
         ictx: RcInvokeContext,
-        signed_tx: SignedTransaction,
+        action: &TransactionAction,
     ) -> ProtocolResult<ReceiptResult> {
-        let action = &signed_tx.raw.action;
-
         let res = match action {
             TransactionAction::Transfer { receiver, .. } => {
                 let to = &Address::User(receiver.clone());
                 self.handle_transfer(Rc::clone(&ictx), &to)?
             }
+            TransactionAction::Approve {
+                spender,
+                asset_id,
+                max,
+            } => self.handle_approve(Rc::clone(&ictx), spender, asset_id, max)?,
             TransactionAction::Deploy {
                 code,
                 contract_type,
-            } => self.handle_deploy(Rc::clone(&ictx), code, contract_type)?,
-            _ => panic!("Unsupported transaction"),
+                asset_params,
+            } => self.handle_deploy(Rc::clone(&ictx), code, contract_type, asset_params)?,
+            TransactionAction::Call {
+                contract,
+                method,
+                args,
+                ..
+            } => self.handle_call(Rc::clone(&ictx), contract, method, args)?,
+            TransactionAction::BatchTransfer { outputs } => {
+                self.handle_batch_transfer(Rc::clone(&ictx), outputs)?
+            }
         };
 
         Ok(res)
@@ -229,50 +579,455 @@ impl<DB: TrieDB> TransactionExecutor<DB> {
         })
     }
 
+    fn handle_batch_transfer(
+        &mut self,
+        ictx: RcInvokeContext,
+        outputs: &[TransferOutput],
+    ) -> ProtocolResult<ReceiptResult> {
+        if outputs.is_empty() {
+            return Err(TransactionExecutorError::EmptyBatchTransfer.into());
+        }
+
+        // check assets exist
+        for output in outputs.iter() {
+            self.bank_account
+                .get_asset(Rc::clone(&ictx), &output.carrying_asset.asset_id)?;
+        }
+
+        let outputs = self
+            .account_contract
+            .batch_transfer(Rc::clone(&ictx), outputs)?;
+
+        Ok(ReceiptResult::BatchTransfer { outputs })
+    }
+
+    fn handle_call(
+        &mut self,
+        ictx: RcInvokeContext,
+        contract: &ContractAddress,
+        method: &str,
+        args: &[Bytes],
+    ) -> ProtocolResult<ReceiptResult> {
+        let carrying_asset = { ictx.borrow().carrying_asset.clone() };
+
+        // Move the carrying asset into the contract account before invoking.
+        if let Some(carrying_asset) = carrying_asset {
+            self.bank_account
+                .get_asset(Rc::clone(&ictx), &carrying_asset.asset_id)?;
+
+            self.account_contract
+                .transfer(Rc::clone(&ictx), &Address::Contract(contract.clone()))?;
+        }
+
+        let return_value = match contract.contract_type() {
+            ContractType::Native if is_multisig_execution(contract, method) => {
+                self.handle_multisig(Rc::clone(&ictx), method, args)?
+            }
+            ContractType::Native if is_supply_management(contract, method) => {
+                self.handle_supply(Rc::clone(&ictx), method, args)?
+            }
+            ContractType::Native => {
+                self.dispatcher
+                    .invoke(Rc::clone(&ictx), contract.clone(), method, args.to_vec())?
+            }
+            ContractType::App | ContractType::Library => {
+                let address = Address::Contract(contract.clone());
+                let storage_root = match self.account_contract.get_account(&address)? {
+                    Account::Contract(account) => account.storage_root,
+                    Account::User(_) => {
+                        return Err(TransactionExecutorError::InvalidContract { address }.into())
+                    }
+                };
+
+                self.vm
+                    .call(Rc::clone(&ictx), contract, storage_root, method, args)?
+            }
+            ContractType::Asset => {
+                return Err(TransactionExecutorError::InvalidContract {
+                    address: Address::Contract(contract.clone()),
+                }
+                .into())
+            }
+        };
+
+        let mut logs_bloom = Bloom::default();
+        accrue_logs(&mut logs_bloom, &ictx.borrow().logs);
+
+        Ok(ReceiptResult::Call {
+            contract: contract.clone(),
+            return_value,
+            logs_bloom: Box::new(logs_bloom),
+        })
+    }
+
+    // Supported methods:
+    // execute(wallet, fixed encoded raw transaction, signature...) -> fixed encoded
+    // receipt result propose(wallet, fixed encoded raw transaction) -> proposal
+    // id approve(proposal id) -> proposal id
+    //
+    // The proposal is executed once the approvals reach the threshold.
+    fn handle_multisig(
+        &mut self,
+        ictx: RcInvokeContext,
+        method: &str,
+        args: &[Bytes],
+    ) -> ProtocolResult<Bytes> {
+        let invalid_args = || TransactionExecutorError::InvalidArgs {
+            method: method.to_owned(),
+        };
+
+        match method {
+            "execute" => {
+                if args.len() < 3 {
+                    return Err(invalid_args().into());
+                }
+
+                let wallet = UserAddress::from_bytes(args[0].clone())?;
+                let raw = RawTransaction::decode_fixed(args[1].clone())?;
+                let tx_hash = Hash::digest(raw.encode_fixed()?);
+
+                self.multisig_contract.verify_signatures(
+                    Rc::clone(&ictx),
+                    &wallet,
+                    &tx_hash,
+                    &args[2..],
+                )?;
+                self.execute_multisig_tx(ictx, &wallet, &raw)?
+                    .encode_fixed()
+            }
+            "propose" => {
+                if args.len() != 2 {
+                    return Err(invalid_args().into());
+                }
+
+                let wallet = UserAddress::from_bytes(args[0].clone())?;
+                let raw = RawTransaction::decode_fixed(args[1].clone())?;
+
+                let proposal = self
+                    .multisig_contract
+                    .propose(Rc::clone(&ictx), &wallet, raw)?;
+                self.try_execute_proposal(ictx, &proposal)?;
+                Ok(proposal.id.as_bytes())
+            }
+            "approve" => {
+                if args.len() != 1 {
+                    return Err(invalid_args().into());
+                }
+
+                let id = Hash::from_bytes(args[0].clone())?;
+                let proposal = self.multisig_contract.approve(Rc::clone(&ictx), &id)?;
+                self.try_execute_proposal(ictx, &proposal)?;
+                Ok(id.as_bytes())
+            }
+            _ => Err(invalid_args().into()),
+        }
+    }
+
+    // If the execution fails, the approval fails too, so the proposal stays
+    // pending and can be executed by a later approval.
+    fn try_execute_proposal(
+        &mut self,
+        ictx: RcInvokeContext,
+        proposal: &MultisigProposal,
+    ) -> ProtocolResult<()> {
+        if self.multisig_contract.is_approved(proposal)? {
+            self.execute_multisig_tx(ictx, &proposal.wallet, &proposal.raw)?;
+            self.multisig_contract.mark_executed(&proposal.id)?;
+        }
+        Ok(())
+    }
+
+    // Run the transaction as the wallet. The wallet transaction is replay
+    // protected by its own nonce, while the fee is paid by the outer
+    // transaction, so the `fee` of it is ignored.
+    fn execute_multisig_tx(
+        &mut self,
+        ictx: RcInvokeContext,
+        wallet: &UserAddress,
+        raw: &RawTransaction,
+    ) -> ProtocolResult<ReceiptResult> {
+        if raw.chain_id != self.chain_id {
+            return Err(TransactionExecutorError::InvalidChainID {
+                chain_id: raw.chain_id.clone(),
+            }
+            .into());
+        }
+
+        let wallet_ictx = {
+            let mut wallet_ictx = ictx.borrow().clone();
+            wallet_ictx.caller = Address::User(wallet.clone());
+            wallet_ictx.carrying_asset = carrying_asset_of(&raw.action);
+            Rc::new(RefCell::new(wallet_ictx))
+        };
+
+        self.check_replay(Rc::clone(&wallet_ictx), &raw.nonce, raw.timeout)?;
+        let res = self.dispatch(Rc::clone(&wallet_ictx), &raw.action)?;
+        self.account_contract
+            .use_nonce(Rc::clone(&wallet_ictx), raw.nonce.clone(), raw.timeout)?;
+        self.account_contract.inc_nonce(Rc::clone(&wallet_ictx))?;
+
+        let mut wallet_ictx = wallet_ictx.borrow_mut();
+        wallet_ictx.logs.push(Log {
+            address: MULTISIG_CONTRACT_ADDRESS.clone(),
+            topics:  vec![
+                MULTISIG_EXECUTE_LOG_TOPIC.clone(),
+                Hash::digest(raw.encode_fixed()?),
+            ],
+            data:    wallet.as_bytes(),
+        });
+
+        let mut ictx = ictx.borrow_mut();
+        ictx.cycles_used = wallet_ictx.cycles_used;
+        ictx.logs = wallet_ictx.logs.split_off(0);
+        Ok(res)
+    }
+
+    // Supported methods:
+    // mint(asset_id, receiver, amount) -> fixed encoded asset
+    // burn(asset_id, amount) -> fixed encoded asset
+    //
+    // The amount is in big-endian. The minted asset goes to the receiver and the
+    // burned asset is taken from the caller, so the supply always equals to the
+    // total balances.
+    fn handle_supply(
+        &mut self,
+        ictx: RcInvokeContext,
+        method: &str,
+        args: &[Bytes],
+    ) -> ProtocolResult<Bytes> {
+        let invalid_args = || TransactionExecutorError::InvalidArgs {
+            method: method.to_owned(),
+        };
+
+        match method {
+            "mint" => {
+                if args.len() != 3 {
+                    return Err(invalid_args().into());
+                }
+
+                let id = AssetID::from_bytes(args[0].clone())?;
+                let receiver = Address::from_bytes(args[1].clone())?;
+                let amount = Balance::from_bytes_be(&args[2]);
+
+                let asset = self
+                    .bank_account
+                    .mint(Rc::clone(&ictx), &id, amount.clone())?;
+                self.account_contract
+                    .add_balance(&id, &receiver, amount.clone())?;
+
+                emit_supply_log(&ictx, &MINT_LOG_TOPIC, &id, &receiver, &amount);
+                asset.encode_fixed()
+            }
+            "burn" => {
+                if args.len() != 2 {
+                    return Err(invalid_args().into());
+                }
+
+                let id = AssetID::from_bytes(args[0].clone())?;
+                let amount = Balance::from_bytes_be(&args[1]);
+                let caller = ictx.borrow().caller.clone();
+
+                let asset = self
+                    .bank_account
+                    .burn(Rc::clone(&ictx), &id, amount.clone())?;
+                self.account_contract
+                    .sub_balance(&id, &caller, amount.clone())?;
+
+                emit_supply_log(&ictx, &BURN_LOG_TOPIC, &id, &caller, &amount);
+                asset.encode_fixed()
+            }
+            _ => Err(invalid_args().into()),
+        }
+    }
+
+    fn handle_approve(
+        &mut self,
+        ictx: RcInvokeContext,
+        spender: &ContractAddress,
+        asset_id: &AssetID,
+        max: &Balance,
+    ) -> ProtocolResult<ReceiptResult> {
+        // check asset exists
+        self.bank_account.get_asset(Rc::clone(&ictx), asset_id)?;
+
+        self.account_contract
+            .approve(Rc::clone(&ictx), spender, asset_id, max.clone())?;
+
+        Ok(ReceiptResult::Approve {
+            spender:  spender.clone(),
+            asset_id: asset_id.clone(),
+            max:      max.clone(),
+        })
+    }
+
     fn handle_deploy(
         &mut self,
         ictx: RcInvokeContext,
         code: &Bytes,
         contract_type: &ContractType,
+        asset_params: &Option<AssetParams>,
     ) -> ProtocolResult<ReceiptResult> {
         match contract_type {
             ContractType::Asset => {
-                // TODO(@yejiayu): Check account balance?
-                let nonce = self.account_contract.get_nonce(&ictx.borrow().caller)?;
+                let params = asset_params
+                    .clone()
+                    .ok_or(TransactionExecutorError::MissingAssetParams)?;
+
+                let caller = ictx.borrow().caller.clone();
+                let nonce = self.account_contract.get_nonce(&caller)?;
                 let address = ContractAddress::from_code(code.clone(), nonce, ContractType::Asset)?;
 
-                self.bank_account.register(
+                let asset = self.bank_account.register(
                     Rc::clone(&ictx),
                     &address,
-                    "System token".to_owned(),
-                    "ST".to_owned(),
-                    Balance::from(21_000_000_000_000_000u64),
+                    params.name,
+                    params.symbol,
+                    params.supply.clone(),
+                    params.decimals,
                 )?;
 
+                // The initial supply belongs to the deployer.
+                self.account_contract
+                    .add_balance(&asset.id, &caller, params.supply)?;
+
                 Ok(ReceiptResult::Deploy {
                     contract:      address,
                     contract_type: ContractType::Asset,
                 })
             }
-            _ => panic!("Unsupported transaction"),
+            ContractType::App | ContractType::Library => {
+                let nonce = self.account_contract.get_nonce(&ictx.borrow().caller)?;
+                let address =
+                    ContractAddress::from_code(code.clone(), nonce, contract_type.clone())?;
+
+                self.vm.deploy(Rc::clone(&ictx), &address, code.clone())?;
+                self.account_contract
+                    .update_storage_root(&Address::Contract(address.clone()), Hash::from_empty())?;
+
+                Ok(ReceiptResult::Deploy {
+                    contract:      address,
+                    contract_type: contract_type.clone(),
+                })
+            }
+            ContractType::Native => Err(TransactionExecutorError::UnsupportedContractType {
+                contract_type: contract_type.clone(),
+            }
+            .into()),
         }
     }
 
+    fn register_genesis_asset(
+        &mut self,
+        ictx: RcInvokeContext,
+        code: &str,
+        name: String,
+        symbol: String,
+        supply: u64,
+        decimals: u64,
+    ) -> ProtocolResult<Asset> {
+        let code = Bytes::from(hex::decode(code).map_err(TransactionExecutorError::from)?);
+        let address = ContractAddress::from_code(code, 0, ContractType::Asset)?;
+
+        self.bank_account.register(
+            ictx,
+            &address,
+            name,
+            symbol,
+            Balance::from(supply),
+            decimals,
+        )
+    }
+
+    // A transaction can run only once before it times out, the used nonces
+    // are dropped after that. The timeout is bounded by `timeout_gap` as in
+    // the mempool, so the used nonces do not pile up.
+    fn check_replay(
+        &self,
+        ictx: RcInvokeContext,
+        nonce: &Hash,
+        timeout: u64,
+    ) -> ProtocolResult<()> {
+        let cloned_ictx = { ictx.borrow().clone() };
+
+        if timeout < cloned_ictx.epoch_id {
+            return Err(TransactionExecutorError::Timeout { timeout }.into());
+        }
+
+        let timeout_gap = self.governance_contract.get_params()?.timeout_gap;
+        if timeout > cloned_ictx.epoch_id.saturating_add(timeout_gap) {
+            return Err(TransactionExecutorError::InvalidTimeout { timeout }.into());
+        }
+
+        if self
+            .account_contract
+            .is_nonce_used(&cloned_ictx.caller, nonce)?
+        {
+            return Err(TransactionExecutorError::NonceUsed {
+                nonce: nonce.clone(),
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    // Charge `fee.cycle * cycles_price` from the caller before dispatching,
+    // return the prepaid amount.
+    fn prepay_fee(&mut self, ictx: RcInvokeContext) -> ProtocolResult<Balance> {
+        let cloned_ictx = { ictx.borrow().clone() };
+
+        let prepaid = cloned_ictx
+            .cycles_limit
+            .checked_mul(self.cycles_price)
+            .ok_or(TransactionExecutorError::FeeOverflow)?;
+        let prepaid = Balance::from(prepaid);
+
+        self.account_contract.sub_balance(
+            &cloned_ictx.fee_asset_id,
+            &cloned_ictx.caller,
+            prepaid.clone(),
+        )?;
+        Ok(prepaid)
+    }
+
+    // Return the fee of unused cycles to the caller. The `cycles_used` never
+    // exceeds `cycles_limit`, so the fee is always covered by the prepaid.
+    fn refund_fee(&mut self, ictx: RcInvokeContext, prepaid: Balance) -> ProtocolResult<()> {
+        let cloned_ictx = { ictx.borrow().clone() };
+
+        let fee = Balance::from(cloned_ictx.cycles_used * self.cycles_price);
+        if prepaid > fee {
+            self.account_contract.add_balance(
+                &cloned_ictx.fee_asset_id,
+                &cloned_ictx.caller,
+                prepaid - fee,
+            )?;
+        }
+        Ok(())
+    }
+
     fn stash(&mut self) -> ProtocolResult<()> {
         for (_, state) in self.state_adapter_map.iter() {
             state.borrow_mut().stash()?;
         }
-        Ok(())
+        self.vm.stash()
     }
 
     fn revert(&mut self) -> ProtocolResult<()> {
         for (_, state) in self.state_adapter_map.iter() {
             state.borrow_mut().revert_cache()?;
         }
-        Ok(())
+        self.vm.revert()
     }
 
     fn commit(&mut self) -> ProtocolResult<MerkleRoot> {
+        // The storage roots of the contracts are only known after the storages
+        // are committed, so commit them before the accounts.
+        for (contract, storage_root) in self.vm.commit()? {
+            self.account_contract
+                .update_storage_root(&Address::Contract(contract), storage_root)?;
+        }
+        self.stash()?;
+
         for (address, state) in self.state_adapter_map.iter() {
             let root = state.borrow_mut().commit()?;
 
@@ -294,6 +1049,9 @@ impl<DB: 'static + TrieDB> ExecutorFactory<DB> for TransactionExecutorFactory {
         cycles_price: u64,
         coinbase: Address,
     ) -> ProtocolResult<Box<dyn Executor>> {
+        // The nodes written by the executor are cached until the epoch is
+        // committed.
+        let db = Arc::new(CacheDB::new(db));
         let trie = {
             if state_root == Hash::from_empty() {
                 MPTTrie::new(Arc::clone(&db))
@@ -323,18 +1081,98 @@ impl<DB: 'static + TrieDB> ExecutorFactory<DB> for TransactionExecutorFactory {
             Rc::clone(&bank_state_adapter),
         );
 
+        // gen multisig contract
+        let multisig_state_adapter =
+            gen_contract_state(&trie, &MULTISIG_CONTRACT_ADDRESS, Arc::clone(&db))?;
+        let multisig_contract = NativeMultisigContract::new(Rc::clone(&multisig_state_adapter));
+        state_adapter_map.insert(
+            MULTISIG_CONTRACT_ADDRESS.clone(),
+            Rc::clone(&multisig_state_adapter),
+        );
+
+        // gen governance contract
+        let governance_state_adapter =
+            gen_contract_state(&trie, &GOVERNANCE_CONTRACT_ADDRESS, Arc::clone(&db))?;
+        let governance_contract =
+            NativeGovernanceContract::new(Rc::clone(&governance_state_adapter));
+        state_adapter_map.insert(
+            GOVERNANCE_CONTRACT_ADDRESS.clone(),
+            Rc::clone(&governance_state_adapter),
+        );
+
+        // register native contracts, they share the state adapters above.
+        let mut dispatcher = NativeDispatcher::new();
+        dispatcher.register(
+            native_contract_address(&ACCOUNT_CONTRACT_ADDRESS)?,
+            Rc::new(RefCell::new(NativeAccountContract::new(Rc::clone(
+                &account_state_adapter,
+            )))),
+        )?;
+        dispatcher.register(
+            native_contract_address(&BANK_CONTRACT_ADDRESS)?,
+            Rc::new(RefCell::new(NativeBankContract::new(
+                chain_id.clone(),
+                Rc::clone(&bank_state_adapter),
+            ))),
+        )?;
+        dispatcher.register(
+            native_contract_address(&MULTISIG_CONTRACT_ADDRESS)?,
+            Rc::new(RefCell::new(NativeMultisigContract::new(Rc::clone(
+                &multisig_state_adapter,
+            )))),
+        )?;
+        dispatcher.register(
+            native_contract_address(&GOVERNANCE_CONTRACT_ADDRESS)?,
+            Rc::new(RefCell::new(NativeGovernanceContract::new(Rc::clone(
+                &governance_state_adapter,
+            )))),
+        )?;
+
+        let dispatcher = Rc::new(dispatcher);
+
+        // gen riscv vm, the code of contracts is stored under the address of vm.
+        // The contracts call the native contracts through the dispatcher.
+        let vm_state_adapter = gen_contract_state(&trie, &VM_CONTRACT_ADDRESS, Arc::clone(&db))?;
+        let vm = RiscvVM::new(
+            Arc::clone(&db),
+            Rc::clone(&vm_state_adapter),
+            Rc::clone(&dispatcher),
+        );
+        state_adapter_map.insert(VM_CONTRACT_ADDRESS.clone(), vm_state_adapter);
+
         Ok(Box::new(TransactionExecutor {
             chain_id,
             epoch_id,
             cycles_price,
             coinbase,
+            trace_state: false,
 
             trie,
             account_contract,
             bank_account,
+            multisig_contract,
+            governance_contract,
             state_adapter_map,
+            dispatcher,
+            vm,
+            db,
         }))
     }
+
+    fn is_nonce_used(
+        state_root: MerkleRoot,
+        db: Arc<DB>,
+        address: &Address,
+        nonce: &Hash,
+    ) -> ProtocolResult<bool> {
+        if state_root == Hash::from_empty() {
+            return Ok(false);
+        }
+
+        let trie = MPTTrie::from(state_root, Arc::clone(&db))?;
+        let account_state_adapter = gen_contract_state(&trie, &ACCOUNT_CONTRACT_ADDRESS, db)?;
+        NativeAccountContract::new(account_state_adapter).is_nonce_used(address, nonce)
+    }
 }
 
 fn gen_contract_state<DB: TrieDB>(
@@ -355,6 +1193,58 @@ fn gen_contract_state<DB: TrieDB>(
     Ok(Rc::new(RefCell::new(state_adapter)))
 }
 
+// The methods of the multisig contract which execute the transactions of the
+// wallets, they need the executor to dispatch the transactions.
+fn is_multisig_execution(contract: &ContractAddress, method: &str) -> bool {
+    Address::Contract(contract.clone()) == *MULTISIG_CONTRACT_ADDRESS
+        && ["execute", "propose", "approve"].contains(&method)
+}
+
+// The methods of the bank contract which change the balances with the supply.
+fn is_supply_management(contract: &ContractAddress, method: &str) -> bool {
+    Address::Contract(contract.clone()) == *BANK_CONTRACT_ADDRESS
+        && ["mint", "burn"].contains(&method)
+}
+
+fn native_contract_address(address: &Address) -> ProtocolResult<ContractAddress> {
+    match address {
+        Address::Contract(contract) => Ok(contract.clone()),
+        Address::User(_) => Err(TransactionExecutorError::InvalidNativeContract {
+            address: address.clone(),
+        }
+        .into()),
+    }
+}
+
+// Both the address of the emitter and the topics of a log are put into the
+// bloom.
+fn accrue_logs(bloom: &mut Bloom, logs: &[Log]) {
+    for log in logs.iter() {
+        bloom.accrue(BloomInput::Raw(&log.address.as_bytes()));
+        for topic in log.topics.iter() {
+            bloom.accrue(BloomInput::Raw(&topic.as_bytes()));
+        }
+    }
+}
+
+// The log data is `account ++ amount`, the amount is in big-endian.
+fn emit_supply_log(
+    ictx: &RcInvokeContext,
+    topic: &Hash,
+    id: &AssetID,
+    account: &Address,
+    amount: &Balance,
+) {
+    let mut data = account.as_bytes().to_vec();
+    data.extend_from_slice(&amount.to_bytes_be());
+
+    ictx.borrow_mut().logs.push(Log {
+        address: BANK_CONTRACT_ADDRESS.clone(),
+        topics:  vec![topic.clone(), id.clone()],
+        data:    Bytes::from(data),
+    });
+}
+
 fn modify_all_cycles_used(all_cycles_used: &mut Vec<Fee>, cycles_used: &Fee) {
     for fee in all_cycles_used.iter_mut() {
         if fee.asset_id == cycles_used.asset_id {
@@ -378,7 +1268,7 @@ fn gen_invoke_ctx(
     coinbase: &Address,
     signed_tx: &SignedTransaction,
 ) -> ProtocolResult<RcInvokeContext> {
-    let mut ctx = InvokeContext {
+    let ctx = InvokeContext {
         chain_id: chain_id.clone(),
         cycles_used: 0,
         cycles_limit: signed_tx.raw.fee.cycle,
@@ -387,24 +1277,135 @@ fn gen_invoke_ctx(
         coinbase: coinbase.clone(),
         epoch_id,
         cycles_price,
-        carrying_asset: None,
-    };
-    match &signed_tx.raw.action {
-        TransactionAction::Transfer { carrying_asset, .. } => {
-            ctx.carrying_asset = Some(carrying_asset.clone());
-        }
-        TransactionAction::Call { carrying_asset, .. } => {
-            ctx.carrying_asset = carrying_asset.clone();
-        }
-        _ => {}
+        carrying_asset: carrying_asset_of(&signed_tx.raw.action),
+        logs: vec![],
     };
     Ok(Rc::new(RefCell::new(ctx)))
 }
 
+// Compare the balances of an account traced in the account contract.
+fn balance_diffs(traced: &Traced) -> ProtocolResult<Vec<BalanceDiff>> {
+    let address = Address::decode_fixed(traced.key.clone())?;
+    let before = match &traced.before {
+        Some(before) => balances_of(&Account::decode_fixed(before.clone())?),
+        None => BTreeMap::new(),
+    };
+    let after = balances_of(&Account::decode_fixed(traced.after.clone())?);
+
+    let zero = Balance::from(0u64);
+    let mut balances = vec![];
+    let asset_ids = before.keys().chain(after.keys()).collect::<BTreeSet<_>>();
+    for asset_id in asset_ids.into_iter() {
+        let before_amount = before.get(asset_id).unwrap_or(&zero);
+        let after_amount = after.get(asset_id).unwrap_or(&zero);
+
+        if before_amount != after_amount {
+            balances.push(BalanceDiff {
+                address:  address.clone(),
+                asset_id: asset_id.clone(),
+                before:   before_amount.clone(),
+                after:    after_amount.clone(),
+            });
+        }
+    }
+
+    Ok(balances)
+}
+
+fn balances_of(account: &Account) -> BTreeMap<AssetID, Balance> {
+    match account {
+        Account::User(user) => user
+            .assets
+            .iter()
+            .map(|(id, info)| (id.clone(), info.balance.clone()))
+            .collect(),
+        Account::Contract(contract) => contract.assets.clone(),
+    }
+}
+
+fn carrying_asset_of(action: &TransactionAction) -> Option<CarryingAsset> {
+    match action {
+        TransactionAction::Transfer { carrying_asset, .. } => Some(carrying_asset.clone()),
+        TransactionAction::Call { carrying_asset, .. } => carrying_asset.clone(),
+        _ => None,
+    }
+}
+
 #[derive(Debug, Display, From)]
 pub enum TransactionExecutorError {
     FromHex(hex::FromHexError),
     ParseInt(ParseIntError),
+
+    #[display(fmt = "{:?} is not a native contract address", address)]
+    InvalidNativeContract {
+        address: Address,
+    },
+
+    #[display(fmt = "{:?} is not a callable contract", address)]
+    InvalidContract {
+        address: Address,
+    },
+
+    #[display(fmt = "invalid args of method {}", method)]
+    InvalidArgs {
+        method: String,
+    },
+
+    #[display(fmt = "invalid chain id {:?}", chain_id)]
+    InvalidChainID {
+        chain_id: Hash,
+    },
+
+    #[display(fmt = "fee overflow")]
+    FeeOverflow,
+
+    #[display(fmt = "transaction timeout {}", timeout)]
+    Timeout {
+        timeout: u64,
+    },
+
+    #[display(fmt = "transaction timeout {} exceeds the timeout gap", timeout)]
+    InvalidTimeout {
+        timeout: u64,
+    },
+
+    #[display(fmt = "nonce {:?} has been used", nonce)]
+    NonceUsed {
+        nonce: Hash,
+    },
+
+    #[display(fmt = "batch transfer without outputs")]
+    EmptyBatchTransfer,
+
+    #[display(fmt = "asset params are required to deploy an asset contract")]
+    MissingAssetParams,
+
+    #[display(fmt = "unsupported contract type {:?}", contract_type)]
+    UnsupportedContractType {
+        contract_type: ContractType,
+    },
+
+    #[display(fmt = "genesis contract {:?} is duplicate", address)]
+    DuplicateGenesisContract {
+        address: ContractAddress,
+    },
+
+    #[display(fmt = "genesis allocates unknown asset {:?}", asset_id)]
+    UnknownGenesisAsset {
+        asset_id: Hash,
+    },
+
+    #[display(
+        fmt = "genesis allocates {} of asset {:?} with supply {}",
+        allocated,
+        asset_id,
+        supply
+    )]
+    GenesisSupplyMismatch {
+        asset_id:  Hash,
+        supply:    Balance,
+        allocated: Balance,
+    },
 }
 
 impl Error for TransactionExecutorError {}
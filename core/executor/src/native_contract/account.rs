@@ -3,17 +3,21 @@ use std::collections::BTreeMap;
 use std::error::Error;
 use std::rc::Rc;
 
+use bytes::Bytes;
 use derive_more::{Display, From};
 
-use protocol::traits::executor::contract::{AccountContract, ContractStateAdapter};
+use protocol::traits::executor::contract::{AccountContract, ContractStateAdapter, NativeContract};
 use protocol::traits::executor::RcInvokeContext;
 use protocol::types::{
-    Account, Address, AssetID, AssetInfo, Balance, ContractAccount, Hash, UserAccount,
+    Account, Address, ApprovedInfo, AssetID, AssetInfo, Balance, ContractAccount, ContractAddress,
+    Hash, Log, MerkleRoot, TransferOutput, TransferOutputResult, UserAccount,
 };
 use protocol::{ProtocolError, ProtocolErrorKind, ProtocolResult};
 
-use crate::cycles::{consume_cycles, CyclesAction};
+use crate::cycles::{consume_cycles, consume_cycles_by, CyclesAction};
+use crate::dispatcher::NativeDispatcherError;
 use crate::fixed_types::FixedAccountSchema;
+use crate::native_contract::{ACCOUNT_CONTRACT_ADDRESS, TRANSFER_LOG_TOPIC};
 
 pub struct NativeAccountContract<StateAdapter: ContractStateAdapter> {
     state_adapter: Rc<RefCell<StateAdapter>>,
@@ -23,6 +27,47 @@ impl<StateAdapter: ContractStateAdapter> NativeAccountContract<StateAdapter> {
     pub fn new(state_adapter: Rc<RefCell<StateAdapter>>) -> Self {
         Self { state_adapter }
     }
+
+    // Create the contract account if it does not exist.
+    pub fn update_storage_root(
+        &mut self,
+        address: &Address,
+        storage_root: MerkleRoot,
+    ) -> ProtocolResult<()> {
+        let modified_account = match self.find_or_create(address)? {
+            Account::Contract(contract) => Account::Contract(ContractAccount {
+                nonce: contract.nonce,
+                assets: contract.assets,
+                storage_root,
+            }),
+            Account::User(_) => return Err(NativeAccountContractError::InvalidAddress.into()),
+        };
+
+        self.state_adapter
+            .borrow_mut()
+            .insert_cache::<FixedAccountSchema>(address.clone(), modified_account)?;
+        Ok(())
+    }
+
+    // The log data is `from ++ to ++ amount`, the amount is in big-endian.
+    fn emit_transfer_log(
+        &self,
+        ictx: RcInvokeContext,
+        id: &AssetID,
+        from: &Address,
+        to: &Address,
+        amount: &Balance,
+    ) {
+        let mut data = from.as_bytes().to_vec();
+        data.extend_from_slice(&to.as_bytes());
+        data.extend_from_slice(&amount.to_bytes_be());
+
+        ictx.borrow_mut().logs.push(Log {
+            address: ACCOUNT_CONTRACT_ADDRESS.clone(),
+            topics:  vec![TRANSFER_LOG_TOPIC.clone(), id.clone()],
+            data:    Bytes::from(data),
+        });
+    }
 }
 
 impl<StateAdapter: ContractStateAdapter> AccountContract<StateAdapter>
@@ -43,6 +88,13 @@ impl<StateAdapter: ContractStateAdapter> AccountContract<StateAdapter>
             carrying_asset.amount.clone(),
         )?;
         self.add_balance(&carrying_asset.asset_id, to, carrying_asset.amount.clone())?;
+        self.emit_transfer_log(
+            Rc::clone(&ictx),
+            &carrying_asset.asset_id,
+            &cloned_ictx.caller,
+            to,
+            &carrying_asset.amount,
+        );
 
         let cycles_used = consume_cycles(
             CyclesAction::AccountTransfer,
@@ -54,6 +106,145 @@ impl<StateAdapter: ContractStateAdapter> AccountContract<StateAdapter>
         Ok(())
     }
 
+    fn batch_transfer(
+        &mut self,
+        ictx: RcInvokeContext,
+        outputs: &[TransferOutput],
+    ) -> ProtocolResult<Vec<TransferOutputResult>> {
+        let cloned_ictx = { ictx.borrow().clone() };
+        let from = &cloned_ictx.caller;
+
+        let cycles_used = consume_cycles_by(
+            CyclesAction::AccountBatchTransfer,
+            outputs.len() as u64,
+            cloned_ictx.cycles_used,
+            cloned_ictx.cycles_limit,
+        )?;
+
+        let mut results = Vec::with_capacity(outputs.len());
+        for output in outputs.iter() {
+            let asset_id = &output.carrying_asset.asset_id;
+            let amount = &output.carrying_asset.amount;
+            let to = &Address::User(output.receiver.clone());
+
+            let before_amount = self.get_balance(asset_id, from)?;
+            self.sub_balance(asset_id, from, amount.clone())?;
+            self.add_balance(asset_id, to, amount.clone())?;
+            self.emit_transfer_log(Rc::clone(&ictx), asset_id, from, to, amount);
+            let after_amount = self.get_balance(asset_id, from)?;
+
+            results.push(TransferOutputResult {
+                receiver: output.receiver.clone(),
+                asset_id: asset_id.clone(),
+                before_amount,
+                after_amount,
+            });
+        }
+
+        ictx.borrow_mut().cycles_used = cycles_used;
+        Ok(results)
+    }
+
+    fn approve(
+        &mut self,
+        ictx: RcInvokeContext,
+        spender: &ContractAddress,
+        id: &AssetID,
+        max: Balance,
+    ) -> ProtocolResult<()> {
+        let cloned_ictx = { ictx.borrow().clone() };
+
+        let caller = &cloned_ictx.caller;
+        let mut user = match self.find_or_create(caller)? {
+            Account::User(user) => user,
+            Account::Contract(_) => return Err(NativeAccountContractError::InvalidAddress.into()),
+        };
+
+        let info = user.assets.entry(id.clone()).or_insert_with(|| AssetInfo {
+            balance:  Balance::from(0u64),
+            approved: BTreeMap::new(),
+        });
+        info.approved.insert(spender.clone(), ApprovedInfo {
+            max,
+            used: Balance::from(0u64),
+        });
+
+        self.state_adapter
+            .borrow_mut()
+            .insert_cache::<FixedAccountSchema>(caller.clone(), Account::User(user))?;
+
+        let cycles_used = consume_cycles(
+            CyclesAction::AccountApprove,
+            cloned_ictx.cycles_used,
+            cloned_ictx.cycles_limit,
+        )?;
+
+        ictx.borrow_mut().cycles_used = cycles_used;
+        Ok(())
+    }
+
+    fn transfer_from(
+        &mut self,
+        ictx: RcInvokeContext,
+        from: &Address,
+        to: &Address,
+        id: &AssetID,
+        amount: Balance,
+    ) -> ProtocolResult<()> {
+        let cloned_ictx = { ictx.borrow().clone() };
+
+        let spender = match &cloned_ictx.caller {
+            Address::Contract(contract) => contract.clone(),
+            Address::User(_) => return Err(NativeAccountContractError::InvalidAddress.into()),
+        };
+
+        let mut user = match self.get_account(from)? {
+            Account::User(user) => user,
+            Account::Contract(_) => return Err(NativeAccountContractError::InvalidAddress.into()),
+        };
+
+        {
+            let info = user
+                .assets
+                .get_mut(id)
+                .ok_or(NativeAccountContractError::NotApproved {
+                    spender: spender.clone(),
+                })?;
+            let approved =
+                info.approved
+                    .get_mut(&spender)
+                    .ok_or(NativeAccountContractError::NotApproved {
+                        spender: spender.clone(),
+                    })?;
+
+            let used = approved.used.clone() + amount.clone();
+            if used > approved.max {
+                return Err(NativeAccountContractError::ExceedApproved { spender }.into());
+            }
+            if info.balance < amount {
+                return Err(NativeAccountContractError::InsufficientBalance.into());
+            }
+
+            approved.used = used;
+            info.balance -= amount.clone();
+        }
+
+        self.state_adapter
+            .borrow_mut()
+            .insert_cache::<FixedAccountSchema>(from.clone(), Account::User(user))?;
+        self.add_balance(id, to, amount.clone())?;
+        self.emit_transfer_log(Rc::clone(&ictx), id, from, to, &amount);
+
+        let cycles_used = consume_cycles(
+            CyclesAction::AccountTransferFrom,
+            cloned_ictx.cycles_used,
+            cloned_ictx.cycles_limit,
+        )?;
+
+        ictx.borrow_mut().cycles_used = cycles_used;
+        Ok(())
+    }
+
     fn create_account(&mut self, address: &Address) -> ProtocolResult<Account> {
         self.find_or_create(address)
     }
@@ -114,8 +305,9 @@ impl<StateAdapter: ContractStateAdapter> AccountContract<StateAdapter>
 
         let modified_account = match account {
             Account::User(user) => Account::User(UserAccount {
-                nonce:  user.nonce + 1,
-                assets: user.assets,
+                nonce:       user.nonce + 1,
+                assets:      user.assets,
+                used_nonces: user.used_nonces,
             }),
             Account::Contract(contract) => Account::Contract(ContractAccount {
                 nonce:        contract.nonce + 1,
@@ -130,6 +322,48 @@ impl<StateAdapter: ContractStateAdapter> AccountContract<StateAdapter>
         Ok(())
     }
 
+    fn use_nonce(
+        &mut self,
+        ictx: RcInvokeContext,
+        nonce: Hash,
+        timeout: u64,
+    ) -> ProtocolResult<()> {
+        let cloned_ictx = { ictx.borrow().clone() };
+        let caller = &cloned_ictx.caller;
+
+        let mut user = match self.find_or_create(caller)? {
+            Account::User(user) => user,
+            Account::Contract(_) => return Err(NativeAccountContractError::InvalidAddress.into()),
+        };
+
+        if user.used_nonces.contains_key(&nonce) {
+            return Err(NativeAccountContractError::NonceUsed { nonce }.into());
+        }
+
+        // The transactions with these nonces have timed out, they can not be
+        // replayed anymore.
+        let epoch_id = cloned_ictx.epoch_id;
+        user.used_nonces.retain(|_, timeout| *timeout >= epoch_id);
+        user.used_nonces.insert(nonce, timeout);
+
+        self.state_adapter
+            .borrow_mut()
+            .insert_cache::<FixedAccountSchema>(caller.clone(), Account::User(user))?;
+        Ok(())
+    }
+
+    fn is_nonce_used(&self, address: &Address, nonce: &Hash) -> ProtocolResult<bool> {
+        let account = self
+            .state_adapter
+            .borrow()
+            .get::<FixedAccountSchema>(&address.clone())?;
+
+        match account {
+            Some(Account::User(user)) => Ok(user.used_nonces.contains_key(nonce)),
+            _ => Ok(false),
+        }
+    }
+
     fn get_balance(&self, id: &AssetID, address: &Address) -> ProtocolResult<Balance> {
         let fixed_account = self
             .state_adapter
@@ -178,8 +412,9 @@ impl<StateAdapter: ContractStateAdapter> NativeAccountContract<StateAdapter> {
 
         let account = match address {
             Address::User(_) => Account::User(UserAccount {
-                nonce:  0,
-                assets: BTreeMap::new(),
+                nonce:       0,
+                assets:      BTreeMap::new(),
+                used_nonces: BTreeMap::new(),
             }),
             Address::Contract(_) => Account::Contract(ContractAccount {
                 nonce:        0,
@@ -278,6 +513,66 @@ impl<StateAdapter: ContractStateAdapter> NativeAccountContract<StateAdapter> {
     }
 }
 
+impl<StateAdapter: ContractStateAdapter> NativeContract for NativeAccountContract<StateAdapter> {
+    // Supported methods:
+    // get_balance(asset_id, address) -> big-endian balance bytes
+    // approve(spender, asset_id, max) -> empty
+    // transfer_from(from, to, asset_id, amount) -> empty
+    //
+    // The balances are big-endian. `transfer_from` spends the allowance of the
+    // caller, which must be a contract.
+    fn call(
+        &mut self,
+        ictx: RcInvokeContext,
+        method: &str,
+        args: Vec<Bytes>,
+    ) -> ProtocolResult<Bytes> {
+        let invalid_args = || NativeDispatcherError::InvalidArgs {
+            method: method.to_owned(),
+        };
+
+        match method {
+            "get_balance" => {
+                if args.len() != 2 {
+                    return Err(invalid_args().into());
+                }
+
+                let id = AssetID::from_bytes(args[0].clone())?;
+                let address = Address::from_bytes(args[1].clone())?;
+                let balance = self.get_balance(&id, &address)?;
+                Ok(Bytes::from(balance.to_bytes_be()))
+            }
+            "approve" => {
+                if args.len() != 3 {
+                    return Err(invalid_args().into());
+                }
+
+                let spender = ContractAddress::from_bytes(args[0].clone())?;
+                let id = AssetID::from_bytes(args[1].clone())?;
+                let max = Balance::from_bytes_be(&args[2]);
+                self.approve(ictx, &spender, &id, max)?;
+                Ok(Bytes::new())
+            }
+            "transfer_from" => {
+                if args.len() != 4 {
+                    return Err(invalid_args().into());
+                }
+
+                let from = Address::from_bytes(args[0].clone())?;
+                let to = Address::from_bytes(args[1].clone())?;
+                let id = AssetID::from_bytes(args[2].clone())?;
+                let amount = Balance::from_bytes_be(&args[3]);
+                self.transfer_from(ictx, &from, &to, &id, amount)?;
+                Ok(Bytes::new())
+            }
+            _ => Err(NativeDispatcherError::MethodNotFound {
+                method: method.to_owned(),
+            }
+            .into()),
+        }
+    }
+}
+
 #[derive(Debug, Display, From)]
 pub enum NativeAccountContractError {
     #[display(fmt = "Insufficient balance")]
@@ -288,6 +583,15 @@ pub enum NativeAccountContractError {
 
     #[display(fmt = "invalid address")]
     InvalidAddress,
+
+    #[display(fmt = "nonce {:?} has been used", nonce)]
+    NonceUsed { nonce: Hash },
+
+    #[display(fmt = "contract {:?} is not approved", spender)]
+    NotApproved { spender: ContractAddress },
+
+    #[display(fmt = "contract {:?} exceeds the approved amount", spender)]
+    ExceedApproved { spender: ContractAddress },
 }
 
 impl Error for NativeAccountContractError {}
@@ -5,20 +5,23 @@ use std::rc::Rc;
 use bytes::Bytes;
 use derive_more::{Display, From};
 
-use protocol::traits::executor::contract::{BankContract, ContractStateAdapter};
+use protocol::fixed_codec::ProtocolFixedCodec;
+use protocol::traits::executor::contract::{BankContract, ContractStateAdapter, NativeContract};
 use protocol::traits::executor::RcInvokeContext;
-use protocol::types::{Asset, AssetID, Balance, ContractAddress, ContractType, Hash};
+use protocol::types::{Address, Asset, AssetID, Balance, ContractAddress, ContractType, Hash};
 use protocol::{ProtocolError, ProtocolErrorKind, ProtocolResult};
 
 use crate::cycles::{consume_cycles, CyclesAction};
+use crate::dispatcher::NativeDispatcherError;
 use crate::fixed_types::FixedAssetSchema;
 
 /// Bank is the registration and query center for asset.
 ///
-/// It only does two things
+/// It does three things
 /// 1. Responsible for generating a unique ID for the asset and writing the
 /// asset's information to the chain.
 /// 2. Query the basic information of the asset by asset id.
+/// 3. Track the supply of the asset when it is minted or burned.
 pub struct NativeBankContract<StateAdapter: ContractStateAdapter> {
     chain_id: Hash,
 
@@ -32,12 +35,45 @@ impl<StateAdapter: ContractStateAdapter> NativeBankContract<StateAdapter> {
             state_adapter,
         }
     }
+
+    // Only the issuer and the manage contract can change the supply.
+    fn check_issuer(&self, ictx: &RcInvokeContext, asset: &Asset) -> ProtocolResult<()> {
+        let caller = ictx.borrow().caller.clone();
+
+        if caller != asset.issuer && caller != Address::Contract(asset.manage_contract.clone()) {
+            return Err(NativeBankContractError::NotIssuer {
+                id: asset.id.clone(),
+                caller,
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    fn update_supply(
+        &mut self,
+        ictx: RcInvokeContext,
+        action: CyclesAction,
+        asset: Asset,
+    ) -> ProtocolResult<Asset> {
+        self.state_adapter
+            .borrow_mut()
+            .insert_cache::<FixedAssetSchema>(asset.id.clone(), asset.clone())?;
+
+        let cycles_used = consume_cycles(
+            action,
+            ictx.borrow().cycles_used,
+            ictx.borrow().cycles_limit,
+        )?;
+        ictx.borrow_mut().cycles_used = cycles_used;
+        Ok(asset)
+    }
 }
 
 impl<StateAdapter: ContractStateAdapter> BankContract<StateAdapter>
     for NativeBankContract<StateAdapter>
 {
-    // Register an asset.
+    // Register an asset, the caller becomes the issuer of it.
     // The asset id is generated by: AssetID = Hash(ChainID + AssetContractAddress).
     //
     // NOTE: After the asset is successfully registered, the `world state` will not
@@ -49,6 +85,7 @@ impl<StateAdapter: ContractStateAdapter> BankContract<StateAdapter>
         name: String,
         symbol: String,
         supply: Balance,
+        decimals: u64,
     ) -> ProtocolResult<Asset> {
         if address.contract_type() != ContractType::Asset {
             return Err(NativeBankContractError::InvalidAddress.into());
@@ -71,10 +108,12 @@ impl<StateAdapter: ContractStateAdapter> BankContract<StateAdapter>
             name,
             symbol,
             supply,
+            decimals,
 
             id: asset_id.clone(),
             manage_contract: address.clone(),
             storage_root: Hash::from_empty(),
+            issuer: ictx.borrow().caller.clone(),
         };
 
         self.state_adapter
@@ -98,6 +137,68 @@ impl<StateAdapter: ContractStateAdapter> BankContract<StateAdapter>
             .ok_or(NativeBankContractError::NotFound { id: id.clone() })?;
         Ok(fixed_asset)
     }
+
+    fn mint(
+        &mut self,
+        ictx: RcInvokeContext,
+        id: &AssetID,
+        amount: Balance,
+    ) -> ProtocolResult<Asset> {
+        let mut asset = self.get_asset(Rc::clone(&ictx), id)?;
+        self.check_issuer(&ictx, &asset)?;
+
+        asset.supply += amount;
+        self.update_supply(ictx, CyclesAction::BankMint, asset)
+    }
+
+    fn burn(
+        &mut self,
+        ictx: RcInvokeContext,
+        id: &AssetID,
+        amount: Balance,
+    ) -> ProtocolResult<Asset> {
+        let mut asset = self.get_asset(Rc::clone(&ictx), id)?;
+        self.check_issuer(&ictx, &asset)?;
+
+        if asset.supply < amount {
+            return Err(NativeBankContractError::InsufficientSupply { id: id.clone() }.into());
+        }
+
+        asset.supply -= amount;
+        self.update_supply(ictx, CyclesAction::BankBurn, asset)
+    }
+}
+
+impl<StateAdapter: ContractStateAdapter> NativeContract for NativeBankContract<StateAdapter> {
+    // Supported methods:
+    // get_asset(asset_id) -> fixed encoded asset
+    //
+    // `mint` and `burn` change the balances too, so they are handled by the
+    // executor.
+    fn call(
+        &mut self,
+        ictx: RcInvokeContext,
+        method: &str,
+        args: Vec<Bytes>,
+    ) -> ProtocolResult<Bytes> {
+        match method {
+            "get_asset" => {
+                if args.len() != 1 {
+                    return Err(NativeDispatcherError::InvalidArgs {
+                        method: method.to_owned(),
+                    }
+                    .into());
+                }
+
+                let id = AssetID::from_bytes(args[0].clone())?;
+                self.get_asset(ictx, &id)?.encode_fixed()
+            }
+            _ => Err(NativeDispatcherError::MethodNotFound {
+                method: method.to_owned(),
+            }
+            .into()),
+        }
+    }
 }
 
 #[derive(Debug, Display, From)]
@@ -110,6 +211,12 @@ pub enum NativeBankContractError {
 
     #[display(fmt = "invalid address")]
     InvalidAddress,
+
+    #[display(fmt = "{:?} is not the issuer of asset {:?}", caller, id)]
+    NotIssuer { id: AssetID, caller: Address },
+
+    #[display(fmt = "burn more than the supply of asset {:?}", id)]
+    InsufficientSupply { id: AssetID },
 }
 
 impl Error for NativeBankContractError {}
@@ -0,0 +1,518 @@
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::rc::Rc;
+
+use bytes::Bytes;
+use derive_more::{Display, From};
+
+use common_crypto::{BLS12381PublicKey, Crypto, BLS12381};
+
+use protocol::fixed_codec::ProtocolFixedCodec;
+use protocol::traits::executor::contract::{ContractStateAdapter, NativeContract};
+use protocol::traits::executor::RcInvokeContext;
+use protocol::types::{
+    Address, ChainParams, GovernanceAction, GovernanceProposal, Hash, UserAddress, Validator,
+    ValidatorSet,
+};
+use protocol::{ProtocolError, ProtocolErrorKind, ProtocolResult};
+
+use crate::cycles::{consume_cycles, CyclesAction};
+use crate::dispatcher::NativeDispatcherError;
+use crate::fixed_types::{
+    FixedBytesSchema, FixedChainParamsSchema, FixedGovernanceProposalSchema,
+    FixedValidatorSetSchema,
+};
+
+const VALIDATOR_SET_KEY: &str = "validator_set";
+const CHAIN_PARAMS_KEY: &str = "chain_params";
+// The number of the proposals made, which makes the proposal ids unique.
+const PROPOSAL_COUNT_KEY: &str = "proposal_count";
+const BLS_POP_DOMAIN: &str = "bls_proof_of_possession";
+
+/// Governance holds the validators of the consensus and the chain params.
+///
+/// A change is proposed by a validator and applied once the validators
+/// approving it hold more than 2/3 of the vote weight. Every change of the
+/// validators increases the version of the validator set, which outdates the
+/// proposals made under the older versions. The consensus and the mempool
+/// pick the changes up at the end of the epoch in which they are applied.
+pub struct NativeGovernanceContract<StateAdapter: ContractStateAdapter> {
+    state_adapter: Rc<RefCell<StateAdapter>>,
+}
+
+impl<StateAdapter: ContractStateAdapter> NativeGovernanceContract<StateAdapter> {
+    pub fn new(state_adapter: Rc<RefCell<StateAdapter>>) -> Self {
+        Self { state_adapter }
+    }
+
+    // Set the validators of the genesis, the version starts from 0. Each of
+    // the validators proves the possession of the bls private key by `pops`.
+    pub fn init_validators(
+        &mut self,
+        validators: Vec<Validator>,
+        pops: Vec<Bytes>,
+    ) -> ProtocolResult<()> {
+        check_validators(&validators, &pops)?;
+
+        let validator_set = ValidatorSet {
+            version: 0,
+            validators,
+        };
+        self.state_adapter
+            .borrow_mut()
+            .insert_cache::<FixedValidatorSetSchema>(
+                Bytes::from(VALIDATOR_SET_KEY),
+                validator_set,
+            )?;
+        Ok(())
+    }
+
+    // Set the chain params of the genesis.
+    pub fn init_params(&mut self, params: ChainParams) -> ProtocolResult<()> {
+        check_params(&params)?;
+
+        self.state_adapter
+            .borrow_mut()
+            .insert_cache::<FixedChainParamsSchema>(Bytes::from(CHAIN_PARAMS_KEY), params)?;
+        Ok(())
+    }
+
+    pub fn get_params(&self) -> ProtocolResult<ChainParams> {
+        let params = self
+            .state_adapter
+            .borrow()
+            .get::<FixedChainParamsSchema>(&Bytes::from(CHAIN_PARAMS_KEY))?
+            .ok_or(NativeGovernanceContractError::ChainParamsNotFound)?;
+        Ok(params)
+    }
+
+    pub fn get_validator_set(&self) -> ProtocolResult<ValidatorSet> {
+        let validator_set = self
+            .state_adapter
+            .borrow()
+            .get::<FixedValidatorSetSchema>(&Bytes::from(VALIDATOR_SET_KEY))?
+            .ok_or(NativeGovernanceContractError::ValidatorSetNotFound)?;
+        Ok(validator_set)
+    }
+
+    pub fn get_proposal(&self, id: &Hash) -> ProtocolResult<GovernanceProposal> {
+        let proposal = self
+            .state_adapter
+            .borrow()
+            .get::<FixedGovernanceProposalSchema>(id)?
+            .ok_or(NativeGovernanceContractError::ProposalNotFound { id: id.clone() })?;
+        Ok(proposal)
+    }
+
+    // Propose to replace the validators, each of them proves the possession of
+    // the bls private key by `pops`.
+    pub fn propose_validators(
+        &mut self,
+        ictx: RcInvokeContext,
+        validators: Vec<Validator>,
+        pops: Vec<Bytes>,
+    ) -> ProtocolResult<GovernanceProposal> {
+        check_validators(&validators, &pops)?;
+        self.propose(ictx, GovernanceAction::UpdateValidators(validators))
+    }
+
+    pub fn propose_params(
+        &mut self,
+        ictx: RcInvokeContext,
+        params: ChainParams,
+    ) -> ProtocolResult<GovernanceProposal> {
+        check_params(&params)?;
+        self.propose(ictx, GovernanceAction::UpdateParams(params))
+    }
+
+    // Propose an action by a validator, the proposal is approved by the caller
+    // at the same time and applied at once if the caller holds enough weight.
+    fn propose(
+        &mut self,
+        ictx: RcInvokeContext,
+        action: GovernanceAction,
+    ) -> ProtocolResult<GovernanceProposal> {
+        let validator_set = self.get_validator_set()?;
+        let caller = caller_of(&ictx)?;
+        if !is_validator(&validator_set, &caller) {
+            return Err(NativeGovernanceContractError::NotValidator { address: caller }.into());
+        }
+
+        let count_key = Bytes::from(PROPOSAL_COUNT_KEY);
+        let count = match self
+            .state_adapter
+            .borrow()
+            .get::<FixedBytesSchema>(&count_key)?
+        {
+            Some(count) => decode_u64(&count).ok_or(NativeGovernanceContractError::InvalidCount)?,
+            None => 0,
+        };
+
+        let mut seed = action.encode_fixed()?;
+        seed.extend_from_slice(&count.to_be_bytes());
+        let id = Hash::digest(seed);
+
+        let proposal = GovernanceProposal {
+            id: id.clone(),
+            proposer: caller.clone(),
+            action,
+            validator_version: validator_set.version,
+            approvals: vec![caller],
+            executed: false,
+        };
+
+        self.state_adapter
+            .borrow_mut()
+            .insert_cache::<FixedBytesSchema>(
+                count_key,
+                Bytes::from(&(count + 1).to_be_bytes()[..]),
+            )?;
+
+        let cycles_used = consume_cycles(
+            CyclesAction::GovernancePropose,
+            ictx.borrow().cycles_used,
+            ictx.borrow().cycles_limit,
+        )?;
+        ictx.borrow_mut().cycles_used = cycles_used;
+
+        self.try_apply(proposal)
+    }
+
+    // Approve a proposal by a validator, the proposal is applied once it is
+    // approved by more than 2/3 of the vote weight. A proposal made under an
+    // older validator set is outdated, so that it can not roll back the
+    // changes applied after it.
+    pub fn approve(
+        &mut self,
+        ictx: RcInvokeContext,
+        id: &Hash,
+    ) -> ProtocolResult<GovernanceProposal> {
+        let mut proposal = self.get_proposal(id)?;
+        if proposal.executed {
+            return Err(NativeGovernanceContractError::ProposalExecuted { id: id.clone() }.into());
+        }
+
+        let validator_set = self.get_validator_set()?;
+        if proposal.validator_version != validator_set.version {
+            return Err(NativeGovernanceContractError::ProposalOutdated {
+                id:      id.clone(),
+                version: proposal.validator_version,
+            }
+            .into());
+        }
+        let caller = caller_of(&ictx)?;
+        if !is_validator(&validator_set, &caller) {
+            return Err(NativeGovernanceContractError::NotValidator { address: caller }.into());
+        }
+        if proposal.approvals.contains(&caller) {
+            return Err(NativeGovernanceContractError::AlreadyApproved { address: caller }.into());
+        }
+        proposal.approvals.push(caller);
+
+        let cycles_used = consume_cycles(
+            CyclesAction::GovernanceApprove,
+            ictx.borrow().cycles_used,
+            ictx.borrow().cycles_limit,
+        )?;
+        ictx.borrow_mut().cycles_used = cycles_used;
+
+        self.try_apply(proposal)
+    }
+
+    // Apply the action of the proposal if it has enough approvals, the
+    // proposal is saved either way.
+    fn try_apply(
+        &mut self,
+        mut proposal: GovernanceProposal,
+    ) -> ProtocolResult<GovernanceProposal> {
+        let validator_set = self.get_validator_set()?;
+
+        if is_approved(&validator_set, &proposal.approvals) {
+            match &proposal.action {
+                GovernanceAction::UpdateValidators(validators) => {
+                    let validator_set = ValidatorSet {
+                        version:    validator_set.version + 1,
+                        validators: validators.clone(),
+                    };
+
+                    self.state_adapter
+                        .borrow_mut()
+                        .insert_cache::<FixedValidatorSetSchema>(
+                            Bytes::from(VALIDATOR_SET_KEY),
+                            validator_set,
+                        )?;
+                }
+                GovernanceAction::UpdateParams(params) => {
+                    self.state_adapter
+                        .borrow_mut()
+                        .insert_cache::<FixedChainParamsSchema>(
+                            Bytes::from(CHAIN_PARAMS_KEY),
+                            params.clone(),
+                        )?;
+                }
+            }
+
+            proposal.executed = true;
+        }
+
+        self.state_adapter
+            .borrow_mut()
+            .insert_cache::<FixedGovernanceProposalSchema>(proposal.id.clone(), proposal.clone())?;
+        Ok(proposal)
+    }
+}
+
+impl<StateAdapter: ContractStateAdapter> NativeContract for NativeGovernanceContract<StateAdapter> {
+    // Supported methods:
+    // propose_validators((fixed encoded validator, bls pop)...) -> proposal id
+    // propose_params(fixed encoded params) -> proposal id
+    // approve(id) -> fixed encoded proposal
+    // get_validators() -> fixed encoded validator set
+    // get_params() -> fixed encoded params
+    // get_proposal(id) -> fixed encoded proposal
+    fn call(
+        &mut self,
+        ictx: RcInvokeContext,
+        method: &str,
+        args: Vec<Bytes>,
+    ) -> ProtocolResult<Bytes> {
+        let invalid_args = || NativeDispatcherError::InvalidArgs {
+            method: method.to_owned(),
+        };
+
+        match method {
+            "propose_validators" => {
+                if args.is_empty() || args.len() % 2 != 0 {
+                    return Err(invalid_args().into());
+                }
+
+                let mut validators = Vec::with_capacity(args.len() / 2);
+                let mut pops = Vec::with_capacity(args.len() / 2);
+                for pair in args.chunks(2) {
+                    validators.push(Validator::decode_fixed(pair[0].clone())?);
+                    pops.push(pair[1].clone());
+                }
+
+                Ok(self
+                    .propose_validators(ictx, validators, pops)?
+                    .id
+                    .as_bytes())
+            }
+            "propose_params" => {
+                if args.len() != 1 {
+                    return Err(invalid_args().into());
+                }
+
+                let params = ChainParams::decode_fixed(args[0].clone())?;
+                Ok(self.propose_params(ictx, params)?.id.as_bytes())
+            }
+            "approve" => {
+                if args.len() != 1 {
+                    return Err(invalid_args().into());
+                }
+
+                let id = Hash::from_bytes(args[0].clone())?;
+                self.approve(ictx, &id)?.encode_fixed()
+            }
+            "get_validators" => {
+                if !args.is_empty() {
+                    return Err(invalid_args().into());
+                }
+
+                self.get_validator_set()?.encode_fixed()
+            }
+            "get_params" => {
+                if !args.is_empty() {
+                    return Err(invalid_args().into());
+                }
+
+                self.get_params()?.encode_fixed()
+            }
+            "get_proposal" => {
+                if args.len() != 1 {
+                    return Err(invalid_args().into());
+                }
+
+                let id = Hash::from_bytes(args[0].clone())?;
+                self.get_proposal(&id)?.encode_fixed()
+            }
+            _ => Err(NativeDispatcherError::MethodNotFound {
+                method: method.to_owned(),
+            }
+            .into()),
+        }
+    }
+}
+
+fn caller_of(ictx: &RcInvokeContext) -> ProtocolResult<UserAddress> {
+    match &ictx.borrow().caller {
+        Address::User(user) => Ok(user.clone()),
+        Address::Contract(_) => Err(NativeGovernanceContractError::InvalidCaller {
+            address: ictx.borrow().caller.clone(),
+        }
+        .into()),
+    }
+}
+
+/// The hash a validator signs by the bls private key to prove the possession
+/// of it, so that no one registers a key derived from the keys of others to
+/// forge their aggregated signatures.
+pub fn bls_pop_hash(address: &UserAddress, bls_pub_key: &Bytes) -> Hash {
+    let mut msg = Bytes::from(BLS_POP_DOMAIN);
+    msg.extend_from_slice(&address.as_bytes());
+    msg.extend_from_slice(bls_pub_key);
+    Hash::digest(msg)
+}
+
+fn check_validators(validators: &[Validator], pops: &[Bytes]) -> ProtocolResult<()> {
+    if validators.is_empty() {
+        return Err(NativeGovernanceContractError::NoValidator.into());
+    }
+    if validators.len() != pops.len() {
+        return Err(NativeGovernanceContractError::MissingBlsPop.into());
+    }
+
+    let mut addresses = BTreeSet::new();
+    let mut bls_pub_keys = BTreeSet::new();
+    for (validator, pop) in validators.iter().zip(pops.iter()) {
+        if validator.vote_weight == 0 {
+            return Err(NativeGovernanceContractError::ZeroVoteWeight {
+                address: validator.address.clone(),
+            }
+            .into());
+        }
+        if BLS12381PublicKey::try_from(validator.bls_pub_key.as_ref()).is_err() {
+            return Err(NativeGovernanceContractError::InvalidBlsPubKey {
+                address: validator.address.clone(),
+            }
+            .into());
+        }
+        let pop_hash = bls_pop_hash(&validator.address, &validator.bls_pub_key);
+        if BLS12381::verify_signature(&pop_hash.as_bytes(), pop, &validator.bls_pub_key).is_err() {
+            return Err(NativeGovernanceContractError::InvalidBlsPop {
+                address: validator.address.clone(),
+            }
+            .into());
+        }
+        if !addresses.insert(validator.address.clone()) {
+            return Err(NativeGovernanceContractError::DuplicateValidator {
+                address: validator.address.clone(),
+            }
+            .into());
+        }
+        if !bls_pub_keys.insert(validator.bls_pub_key.clone()) {
+            return Err(NativeGovernanceContractError::DuplicateBlsPubKey {
+                address: validator.address.clone(),
+            }
+            .into());
+        }
+    }
+    Ok(())
+}
+
+// A zero cycles limit, interval or timeout gap stops the chain.
+fn check_params(params: &ChainParams) -> ProtocolResult<()> {
+    if params.cycles_limit == 0 || params.interval == 0 || params.timeout_gap == 0 {
+        return Err(NativeGovernanceContractError::InvalidParams {
+            params: params.clone(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+fn is_validator(validator_set: &ValidatorSet, address: &UserAddress) -> bool {
+    validator_set
+        .validators
+        .iter()
+        .any(|validator| &validator.address == address)
+}
+
+// The approvals of the addresses which are not validators anymore are ignored.
+fn is_approved(validator_set: &ValidatorSet, approvals: &[UserAddress]) -> bool {
+    let mut total_weight: u64 = 0;
+    let mut approved_weight: u64 = 0;
+    for validator in validator_set.validators.iter() {
+        total_weight += u64::from(validator.vote_weight);
+        if approvals.contains(&validator.address) {
+            approved_weight += u64::from(validator.vote_weight);
+        }
+    }
+
+    approved_weight * 3 > total_weight * 2
+}
+
+// Decode a big-endian u64 of at most 8 bytes.
+fn decode_u64(bytes: &Bytes) -> Option<u64> {
+    if bytes.is_empty() || bytes.len() > 8 {
+        return None;
+    }
+
+    let mut buf = [0u8; 8];
+    buf[8 - bytes.len()..].copy_from_slice(bytes);
+    Some(u64::from_be_bytes(buf))
+}
+
+#[derive(Debug, Display, From)]
+pub enum NativeGovernanceContractError {
+    #[display(fmt = "validator set not found")]
+    ValidatorSetNotFound,
+
+    #[display(fmt = "chain params not found")]
+    ChainParamsNotFound,
+
+    #[display(fmt = "invalid chain params {:?}", params)]
+    InvalidParams { params: ChainParams },
+
+    #[display(fmt = "proposal {:?} not found", id)]
+    ProposalNotFound { id: Hash },
+
+    #[display(fmt = "proposal {:?} has been executed", id)]
+    ProposalExecuted { id: Hash },
+
+    #[display(fmt = "proposal {:?} of validator version {} is outdated", id, version)]
+    ProposalOutdated { id: Hash, version: u64 },
+
+    #[display(fmt = "validator set is empty")]
+    NoValidator,
+
+    #[display(fmt = "validator {:?} has zero vote weight", address)]
+    ZeroVoteWeight { address: UserAddress },
+
+    #[display(fmt = "validator {:?} has an invalid bls public key", address)]
+    InvalidBlsPubKey { address: UserAddress },
+
+    #[display(fmt = "validator {:?} has an invalid bls proof of possession", address)]
+    InvalidBlsPop { address: UserAddress },
+
+    #[display(fmt = "validators mismatch the bls proofs of possession")]
+    MissingBlsPop,
+
+    #[display(fmt = "duplicate validator {:?}", address)]
+    DuplicateValidator { address: UserAddress },
+
+    #[display(fmt = "validator {:?} reuses the bls public key of another", address)]
+    DuplicateBlsPubKey { address: UserAddress },
+
+    #[display(fmt = "invalid proposal count")]
+    InvalidCount,
+
+    #[display(fmt = "{:?} is not a validator", address)]
+    NotValidator { address: UserAddress },
+
+    #[display(fmt = "contract {:?} can not govern the chain", address)]
+    InvalidCaller { address: Address },
+
+    #[display(fmt = "{:?} has approved the proposal", address)]
+    AlreadyApproved { address: UserAddress },
+}
+
+impl Error for NativeGovernanceContractError {}
+
+impl From<NativeGovernanceContractError> for ProtocolError {
+    fn from(err: NativeGovernanceContractError) -> ProtocolError {
+        ProtocolError::new(ProtocolErrorKind::Executor, Box::new(err))
+    }
+}
@@ -1,9 +1,13 @@
 mod account;
 mod bank;
+mod governance;
+mod multisig;
 
 use lazy_static::lazy_static;
 
-use protocol::types::Address;
+use bytes::Bytes;
+
+use protocol::types::{Address, Hash};
 
 lazy_static! {
     pub static ref ACCOUNT_CONTRACT_ADDRESS: Address = Address::from_hex(
@@ -14,7 +18,28 @@ lazy_static! {
         "0x230000000000000000000000000000000000000002"
     )
     .expect("0x230000000000000000000000000000000000000001 is not a legal native contract address.");
+    pub static ref VM_CONTRACT_ADDRESS: Address = Address::from_hex(
+        "0x230000000000000000000000000000000000000003"
+    )
+    .expect("0x230000000000000000000000000000000000000003 is not a legal native contract address.");
+    pub static ref MULTISIG_CONTRACT_ADDRESS: Address = Address::from_hex(
+        "0x230000000000000000000000000000000000000004"
+    )
+    .expect("0x230000000000000000000000000000000000000004 is not a legal native contract address.");
+    pub static ref GOVERNANCE_CONTRACT_ADDRESS: Address = Address::from_hex(
+        "0x230000000000000000000000000000000000000005"
+    )
+    .expect("0x230000000000000000000000000000000000000005 is not a legal native contract address.");
+    // The topic of the log emitted by every asset movement of the account contract.
+    pub static ref TRANSFER_LOG_TOPIC: Hash = Hash::digest(Bytes::from("transfer"));
+    // The topics of the logs emitted by minting and burning an asset.
+    pub static ref MINT_LOG_TOPIC: Hash = Hash::digest(Bytes::from("mint"));
+    pub static ref BURN_LOG_TOPIC: Hash = Hash::digest(Bytes::from("burn"));
+    // The topic of the log emitted by every transaction executed by a multisig wallet.
+    pub static ref MULTISIG_EXECUTE_LOG_TOPIC: Hash = Hash::digest(Bytes::from("multisig_execute"));
 }
 
 pub use account::{NativeAccountContract, NativeAccountContractError};
 pub use bank::{NativeBankContract, NativeBankContractError};
+pub use governance::{bls_pop_hash, NativeGovernanceContract, NativeGovernanceContractError};
+pub use multisig::{NativeMultisigContract, NativeMultisigContractError};
@@ -0,0 +1,456 @@
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::rc::Rc;
+
+use bytes::Bytes;
+use derive_more::{Display, From};
+
+use common_crypto::{Crypto, Secp256k1};
+
+use protocol::fixed_codec::ProtocolFixedCodec;
+use protocol::traits::executor::contract::{ContractStateAdapter, NativeContract};
+use protocol::traits::executor::RcInvokeContext;
+use protocol::types::{
+    Address, Hash, MultisigOwner, MultisigProposal, MultisigWallet, RawTransaction, UserAddress,
+};
+use protocol::{ProtocolError, ProtocolErrorKind, ProtocolResult};
+
+use crate::cycles::{consume_cycles, consume_cycles_by, CyclesAction};
+use crate::dispatcher::NativeDispatcherError;
+use crate::fixed_types::{
+    FixedBytesSchema, FixedMultisigProposalSchema, FixedMultisigWalletSchema,
+};
+
+// The number of the wallets created, which makes the wallet addresses unique.
+const WALLET_COUNT_KEY: &str = "wallet_count";
+const PUBKEY_LEN: usize = 33;
+
+/// Multisig manages the M-of-N wallets.
+///
+/// A wallet is a normal user account whose address has no private key, so it
+/// can receive assets like any other account. A transaction of the wallet is
+/// executed once the total weight of the owners signing it reaches the
+/// threshold, the signatures are either collected off-chain and verified by
+/// `verify_signatures`, or collected on-chain by `propose` and `approve`.
+///
+/// NOTE: The execution of the transaction is done by the executor.
+pub struct NativeMultisigContract<StateAdapter: ContractStateAdapter> {
+    state_adapter: Rc<RefCell<StateAdapter>>,
+}
+
+impl<StateAdapter: ContractStateAdapter> NativeMultisigContract<StateAdapter> {
+    pub fn new(state_adapter: Rc<RefCell<StateAdapter>>) -> Self {
+        Self { state_adapter }
+    }
+
+    // Create a wallet, the address is generated by the caller and the number of
+    // the wallets created before.
+    pub fn create(
+        &mut self,
+        ictx: RcInvokeContext,
+        owners: Vec<MultisigOwner>,
+        threshold: u64,
+    ) -> ProtocolResult<MultisigWallet> {
+        if owners.is_empty() {
+            return Err(NativeMultisigContractError::NoOwner.into());
+        }
+
+        let mut total_weight: u64 = 0;
+        let mut addresses = BTreeSet::new();
+        for owner in owners.iter() {
+            if owner.weight == 0 {
+                return Err(NativeMultisigContractError::ZeroWeight {
+                    owner: owner.address.clone(),
+                }
+                .into());
+            }
+            if !addresses.insert(owner.address.clone()) {
+                return Err(NativeMultisigContractError::DuplicateOwner {
+                    owner: owner.address.clone(),
+                }
+                .into());
+            }
+
+            total_weight = total_weight
+                .checked_add(owner.weight)
+                .ok_or(NativeMultisigContractError::WeightOverflow)?;
+        }
+
+        if threshold == 0 || threshold > total_weight {
+            return Err(NativeMultisigContractError::InvalidThreshold {
+                threshold,
+                total_weight,
+            }
+            .into());
+        }
+
+        let count_key = Bytes::from(WALLET_COUNT_KEY);
+        let count = match self
+            .state_adapter
+            .borrow()
+            .get::<FixedBytesSchema>(&count_key)?
+        {
+            Some(count) => decode_u64(&count).ok_or(NativeMultisigContractError::InvalidCount)?,
+            None => 0,
+        };
+
+        let caller = ictx.borrow().caller.clone();
+        let mut seed = Bytes::from("multisig");
+        seed.extend_from_slice(&caller.as_bytes());
+        seed.extend_from_slice(&count.to_be_bytes());
+        let address = UserAddress::from_pubkey_bytes(seed)?;
+
+        let wallet = MultisigWallet {
+            address: address.clone(),
+            owners,
+            threshold,
+        };
+
+        self.state_adapter
+            .borrow_mut()
+            .insert_cache::<FixedMultisigWalletSchema>(address, wallet.clone())?;
+        self.state_adapter
+            .borrow_mut()
+            .insert_cache::<FixedBytesSchema>(
+                count_key,
+                Bytes::from(&(count + 1).to_be_bytes()[..]),
+            )?;
+
+        let cycles_used = consume_cycles(
+            CyclesAction::MultisigCreate,
+            ictx.borrow().cycles_used,
+            ictx.borrow().cycles_limit,
+        )?;
+        ictx.borrow_mut().cycles_used = cycles_used;
+        Ok(wallet)
+    }
+
+    pub fn get_wallet(&self, address: &UserAddress) -> ProtocolResult<MultisigWallet> {
+        let wallet = self
+            .state_adapter
+            .borrow()
+            .get::<FixedMultisigWalletSchema>(address)?
+            .ok_or(NativeMultisigContractError::WalletNotFound {
+                address: address.clone(),
+            })?;
+        Ok(wallet)
+    }
+
+    pub fn get_proposal(&self, id: &Hash) -> ProtocolResult<MultisigProposal> {
+        let proposal = self
+            .state_adapter
+            .borrow()
+            .get::<FixedMultisigProposalSchema>(id)?
+            .ok_or(NativeMultisigContractError::ProposalNotFound { id: id.clone() })?;
+        Ok(proposal)
+    }
+
+    // Verify the signatures of the transaction signed off-chain, each signature
+    // is `pubkey ++ signature`. Return an error if the signers do not reach the
+    // threshold of the wallet.
+    pub fn verify_signatures(
+        &self,
+        ictx: RcInvokeContext,
+        wallet: &UserAddress,
+        tx_hash: &Hash,
+        signatures: &[Bytes],
+    ) -> ProtocolResult<()> {
+        let wallet = self.get_wallet(wallet)?;
+
+        let cycles_used = consume_cycles_by(
+            CyclesAction::MultisigVerify,
+            signatures.len() as u64,
+            ictx.borrow().cycles_used,
+            ictx.borrow().cycles_limit,
+        )?;
+        ictx.borrow_mut().cycles_used = cycles_used;
+
+        let mut signers = Vec::with_capacity(signatures.len());
+        for signature in signatures.iter() {
+            if signature.len() <= PUBKEY_LEN {
+                return Err(NativeMultisigContractError::InvalidSignature.into());
+            }
+
+            let pubkey = signature.slice_to(PUBKEY_LEN);
+            let signature = signature.slice_from(PUBKEY_LEN);
+            Secp256k1::verify_signature(&tx_hash.as_bytes(), &signature, &pubkey)
+                .map_err(|_| NativeMultisigContractError::InvalidSignature)?;
+
+            let signer = UserAddress::from_pubkey_bytes(pubkey)?;
+            if signers.contains(&signer) {
+                return Err(NativeMultisigContractError::DuplicateSigner { signer }.into());
+            }
+            signers.push(signer);
+        }
+
+        let weight = signed_weight(&wallet, &signers)?;
+        if weight < wallet.threshold {
+            return Err(NativeMultisigContractError::BelowThreshold {
+                weight,
+                threshold: wallet.threshold,
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    // Propose a transaction of the wallet, the proposal is approved by the
+    // caller at the same time. The id of the proposal is the hash of the
+    // transaction.
+    pub fn propose(
+        &mut self,
+        ictx: RcInvokeContext,
+        wallet: &UserAddress,
+        raw: RawTransaction,
+    ) -> ProtocolResult<MultisigProposal> {
+        let wallet = self.get_wallet(wallet)?;
+        let caller = caller_of(&ictx)?;
+        if !is_owner(&wallet, &caller) {
+            return Err(NativeMultisigContractError::NotOwner { address: caller }.into());
+        }
+
+        let id = Hash::digest(raw.encode_fixed()?);
+        if self
+            .state_adapter
+            .borrow()
+            .contains::<FixedMultisigProposalSchema>(&id)?
+        {
+            return Err(NativeMultisigContractError::ProposalExists { id }.into());
+        }
+
+        let proposal = MultisigProposal {
+            id: id.clone(),
+            wallet: wallet.address,
+            raw,
+            approvals: vec![caller],
+            executed: false,
+        };
+
+        self.state_adapter
+            .borrow_mut()
+            .insert_cache::<FixedMultisigProposalSchema>(id, proposal.clone())?;
+
+        let cycles_used = consume_cycles(
+            CyclesAction::MultisigPropose,
+            ictx.borrow().cycles_used,
+            ictx.borrow().cycles_limit,
+        )?;
+        ictx.borrow_mut().cycles_used = cycles_used;
+        Ok(proposal)
+    }
+
+    // Approve a proposal by the caller.
+    pub fn approve(
+        &mut self,
+        ictx: RcInvokeContext,
+        id: &Hash,
+    ) -> ProtocolResult<MultisigProposal> {
+        let mut proposal = self.get_proposal(id)?;
+        if proposal.executed {
+            return Err(NativeMultisigContractError::ProposalExecuted { id: id.clone() }.into());
+        }
+
+        let wallet = self.get_wallet(&proposal.wallet)?;
+        let caller = caller_of(&ictx)?;
+        if !is_owner(&wallet, &caller) {
+            return Err(NativeMultisigContractError::NotOwner { address: caller }.into());
+        }
+        if proposal.approvals.contains(&caller) {
+            return Err(NativeMultisigContractError::AlreadyApproved { address: caller }.into());
+        }
+
+        proposal.approvals.push(caller);
+        self.state_adapter
+            .borrow_mut()
+            .insert_cache::<FixedMultisigProposalSchema>(id.clone(), proposal.clone())?;
+
+        let cycles_used = consume_cycles(
+            CyclesAction::MultisigApprove,
+            ictx.borrow().cycles_used,
+            ictx.borrow().cycles_limit,
+        )?;
+        ictx.borrow_mut().cycles_used = cycles_used;
+        Ok(proposal)
+    }
+
+    // Whether the approvals of the pending proposal reach the threshold.
+    pub fn is_approved(&self, proposal: &MultisigProposal) -> ProtocolResult<bool> {
+        if proposal.executed {
+            return Ok(false);
+        }
+
+        let wallet = self.get_wallet(&proposal.wallet)?;
+        Ok(signed_weight(&wallet, &proposal.approvals)? >= wallet.threshold)
+    }
+
+    pub fn mark_executed(&mut self, id: &Hash) -> ProtocolResult<()> {
+        let mut proposal = self.get_proposal(id)?;
+        proposal.executed = true;
+
+        self.state_adapter
+            .borrow_mut()
+            .insert_cache::<FixedMultisigProposalSchema>(id.clone(), proposal)?;
+        Ok(())
+    }
+}
+
+impl<StateAdapter: ContractStateAdapter> NativeContract for NativeMultisigContract<StateAdapter> {
+    // Supported methods:
+    // create(threshold, fixed encoded owner...) -> wallet address
+    // get_wallet(address) -> fixed encoded wallet
+    // get_proposal(id) -> fixed encoded proposal
+    //
+    // The `threshold` is a big-endian u64. `propose`, `approve` and `execute`
+    // run the transaction of the wallet, so they are handled by the executor.
+    fn call(
+        &mut self,
+        ictx: RcInvokeContext,
+        method: &str,
+        args: Vec<Bytes>,
+    ) -> ProtocolResult<Bytes> {
+        let invalid_args = || NativeDispatcherError::InvalidArgs {
+            method: method.to_owned(),
+        };
+
+        match method {
+            "create" => {
+                if args.len() < 2 {
+                    return Err(invalid_args().into());
+                }
+
+                let threshold = decode_u64(&args[0]).ok_or_else(invalid_args)?;
+                let owners = args[1..]
+                    .iter()
+                    .map(|owner| MultisigOwner::decode_fixed(owner.clone()))
+                    .collect::<ProtocolResult<Vec<_>>>()?;
+
+                Ok(self.create(ictx, owners, threshold)?.address.as_bytes())
+            }
+            "get_wallet" => {
+                if args.len() != 1 {
+                    return Err(invalid_args().into());
+                }
+
+                let address = UserAddress::from_bytes(args[0].clone())?;
+                self.get_wallet(&address)?.encode_fixed()
+            }
+            "get_proposal" => {
+                if args.len() != 1 {
+                    return Err(invalid_args().into());
+                }
+
+                let id = Hash::from_bytes(args[0].clone())?;
+                self.get_proposal(&id)?.encode_fixed()
+            }
+            _ => Err(NativeDispatcherError::MethodNotFound {
+                method: method.to_owned(),
+            }
+            .into()),
+        }
+    }
+}
+
+fn caller_of(ictx: &RcInvokeContext) -> ProtocolResult<UserAddress> {
+    match &ictx.borrow().caller {
+        Address::User(user) => Ok(user.clone()),
+        Address::Contract(_) => Err(NativeMultisigContractError::InvalidCaller {
+            address: ictx.borrow().caller.clone(),
+        }
+        .into()),
+    }
+}
+
+fn is_owner(wallet: &MultisigWallet, address: &UserAddress) -> bool {
+    wallet.owners.iter().any(|owner| &owner.address == address)
+}
+
+// Sum the weights of the signers, all of them must be the owners.
+fn signed_weight(wallet: &MultisigWallet, signers: &[UserAddress]) -> ProtocolResult<u64> {
+    let mut weight: u64 = 0;
+    for signer in signers.iter() {
+        let owner = wallet
+            .owners
+            .iter()
+            .find(|owner| &owner.address == signer)
+            .ok_or_else(|| NativeMultisigContractError::NotOwner {
+                address: signer.clone(),
+            })?;
+
+        weight = weight.saturating_add(owner.weight);
+    }
+    Ok(weight)
+}
+
+// Decode a big-endian u64 of at most 8 bytes.
+fn decode_u64(bytes: &Bytes) -> Option<u64> {
+    if bytes.is_empty() || bytes.len() > 8 {
+        return None;
+    }
+
+    let mut buf = [0u8; 8];
+    buf[8 - bytes.len()..].copy_from_slice(bytes);
+    Some(u64::from_be_bytes(buf))
+}
+
+#[derive(Debug, Display, From)]
+pub enum NativeMultisigContractError {
+    #[display(fmt = "wallet {:?} not found", address)]
+    WalletNotFound { address: UserAddress },
+
+    #[display(fmt = "proposal {:?} not found", id)]
+    ProposalNotFound { id: Hash },
+
+    #[display(fmt = "proposal {:?} already exists", id)]
+    ProposalExists { id: Hash },
+
+    #[display(fmt = "proposal {:?} has been executed", id)]
+    ProposalExecuted { id: Hash },
+
+    #[display(fmt = "wallet has no owner")]
+    NoOwner,
+
+    #[display(fmt = "owner {:?} has zero weight", owner)]
+    ZeroWeight { owner: UserAddress },
+
+    #[display(fmt = "duplicate owner {:?}", owner)]
+    DuplicateOwner { owner: UserAddress },
+
+    #[display(fmt = "weight overflow")]
+    WeightOverflow,
+
+    #[display(fmt = "threshold {} should be in (0, {}]", threshold, total_weight)]
+    InvalidThreshold {
+        threshold:    u64,
+        total_weight: u64,
+    },
+
+    #[display(fmt = "invalid wallet count")]
+    InvalidCount,
+
+    #[display(fmt = "{:?} is not an owner of the wallet", address)]
+    NotOwner { address: UserAddress },
+
+    #[display(fmt = "contract {:?} can not own a wallet", address)]
+    InvalidCaller { address: Address },
+
+    #[display(fmt = "{:?} has approved the proposal", address)]
+    AlreadyApproved { address: UserAddress },
+
+    #[display(fmt = "invalid signature")]
+    InvalidSignature,
+
+    #[display(fmt = "duplicate signer {:?}", signer)]
+    DuplicateSigner { signer: UserAddress },
+
+    #[display(fmt = "signed weight {} is below threshold {}", weight, threshold)]
+    BelowThreshold { weight: u64, threshold: u64 },
+}
+
+impl Error for NativeMultisigContractError {}
+
+impl From<NativeMultisigContractError> for ProtocolError {
+    fn from(err: NativeMultisigContractError) -> ProtocolError {
+        ProtocolError::new(ProtocolErrorKind::Executor, Box::new(err))
+    }
+}
@@ -0,0 +1,234 @@
+use std::error::Error;
+use std::io::{Read, Write};
+use std::sync::Arc;
+
+use bytes::Bytes;
+use derive_more::{Display, From};
+use rlp::{Decodable, DecoderError, Encodable, Prototype, Rlp, RlpStream};
+
+use protocol::fixed_codec::ProtocolFixedCodec;
+use protocol::traits::executor::TrieDB;
+use protocol::types::{Account, Hash, MerkleRoot};
+use protocol::{ProtocolError, ProtocolErrorKind, ProtocolResult};
+
+use crate::native_contract::ACCOUNT_CONTRACT_ADDRESS;
+use crate::trie::MPTTrie;
+
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+// A snapshot file is the rlp of `[version, state_root, checksum, body]`, the
+// `checksum` is the hash of the `body`.
+//
+// The `body` is `[native_tries, storage_tries]`. `native_tries` are the tries
+// of the native contracts keyed by the contract address, the accounts and the
+// assets are in them. `storage_tries` are the storages of the contract
+// accounts keyed by the account key in the account contract.
+pub fn export_snapshot<DB: TrieDB, W: Write>(
+    db: Arc<DB>,
+    state_root: MerkleRoot,
+    mut writer: W,
+) -> ProtocolResult<()> {
+    let trie = MPTTrie::from(state_root.clone(), Arc::clone(&db))?;
+
+    let mut native_tries = vec![];
+    let mut storage_tries = vec![];
+    for (address, root) in trie.entries() {
+        let root = MerkleRoot::from_bytes(root)?;
+        let entries = MPTTrie::from(root.clone(), Arc::clone(&db))?.entries();
+
+        if address == ACCOUNT_CONTRACT_ADDRESS.as_bytes() {
+            for (key, value) in entries.iter() {
+                let storage_root = match Account::decode_fixed(value.clone())? {
+                    Account::Contract(account) => account.storage_root,
+                    Account::User(_) => continue,
+                };
+                if storage_root == Hash::from_empty() {
+                    continue;
+                }
+
+                let storage = MPTTrie::from(storage_root.clone(), Arc::clone(&db))?;
+                storage_tries.push(SnapshotTrie {
+                    key:     key.clone(),
+                    root:    storage_root,
+                    entries: storage.entries(),
+                });
+            }
+        }
+
+        native_tries.push(SnapshotTrie {
+            key: address,
+            root,
+            entries,
+        });
+    }
+
+    let mut body = RlpStream::new_list(2);
+    body.append_list(&native_tries);
+    body.append_list(&storage_tries);
+    let body = body.out();
+
+    let mut stream = RlpStream::new_list(4);
+    stream.append(&SNAPSHOT_VERSION);
+    stream.append(&state_root);
+    stream.append(&Hash::digest(Bytes::from(body.clone())));
+    stream.append(&body);
+
+    writer
+        .write_all(&stream.out())
+        .map_err(SnapshotError::from)?;
+    writer.flush().map_err(SnapshotError::from)?;
+    Ok(())
+}
+
+// Rebuild the tries of the snapshot in the `db`, return the state root after
+// verifying it.
+pub fn import_snapshot<DB: TrieDB, R: Read>(
+    db: Arc<DB>,
+    mut reader: R,
+) -> ProtocolResult<MerkleRoot> {
+    let mut buf = vec![];
+    reader.read_to_end(&mut buf).map_err(SnapshotError::from)?;
+
+    let rlp = Rlp::new(&buf);
+    let version: u32 = rlp.val_at(0).map_err(SnapshotError::from)?;
+    if version != SNAPSHOT_VERSION {
+        return Err(SnapshotError::UnsupportedVersion { version }.into());
+    }
+    let state_root: MerkleRoot = rlp.val_at(1).map_err(SnapshotError::from)?;
+    let checksum: Hash = rlp.val_at(2).map_err(SnapshotError::from)?;
+    let body: Vec<u8> = rlp.val_at(3).map_err(SnapshotError::from)?;
+
+    let real_checksum = Hash::digest(Bytes::from(body.clone()));
+    if checksum != real_checksum {
+        return Err(SnapshotError::ChecksumMismatch {
+            expect: checksum,
+            real:   real_checksum,
+        }
+        .into());
+    }
+
+    let body = Rlp::new(&body);
+    let native_tries: Vec<SnapshotTrie> = body.list_at(0).map_err(SnapshotError::from)?;
+    let storage_tries: Vec<SnapshotTrie> = body.list_at(1).map_err(SnapshotError::from)?;
+
+    for storage in storage_tries.into_iter() {
+        rebuild_trie(Arc::clone(&db), storage)?;
+    }
+
+    let mut trie = MPTTrie::new(Arc::clone(&db));
+    for native in native_tries.into_iter() {
+        let address = native.key.clone();
+        let root = rebuild_trie(Arc::clone(&db), native)?;
+
+        trie.insert(address, root.as_bytes())?;
+    }
+
+    let real_state_root = trie.commit()?;
+    if real_state_root != state_root {
+        return Err(SnapshotError::RootMismatch {
+            expect: state_root,
+            real:   real_state_root,
+        }
+        .into());
+    }
+    Ok(state_root)
+}
+
+fn rebuild_trie<DB: TrieDB>(
+    db: Arc<DB>,
+    snapshot_trie: SnapshotTrie,
+) -> ProtocolResult<MerkleRoot> {
+    let mut trie = MPTTrie::new(db);
+    for (key, value) in snapshot_trie.entries.into_iter() {
+        trie.insert(key, value)?;
+    }
+
+    let root = trie.commit()?;
+    if root != snapshot_trie.root {
+        return Err(SnapshotError::RootMismatch {
+            expect: snapshot_trie.root,
+            real:   root,
+        }
+        .into());
+    }
+    Ok(root)
+}
+
+struct SnapshotTrie {
+    key:     Bytes,
+    root:    MerkleRoot,
+    entries: Vec<(Bytes, Bytes)>,
+}
+
+impl Encodable for SnapshotTrie {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(3)
+            .append(&self.key.to_vec())
+            .append(&self.root);
+
+        s.begin_list(self.entries.len());
+        for (key, value) in self.entries.iter() {
+            s.begin_list(2)
+                .append(&key.to_vec())
+                .append(&value.to_vec());
+        }
+    }
+}
+
+impl Decodable for SnapshotTrie {
+    fn decode(r: &Rlp) -> Result<Self, DecoderError> {
+        if !r.is_list() && r.size() != 3 {
+            return Err(DecoderError::RlpIncorrectListLen);
+        }
+
+        let key: Vec<u8> = r.at(0)?.data()?.to_vec();
+        let root: MerkleRoot = r.val_at(1)?;
+
+        let mut entries = vec![];
+        for entry in r.at(2)?.iter() {
+            match entry.prototype()? {
+                Prototype::List(2) => {}
+                _ => return Err(DecoderError::RlpIncorrectListLen),
+            }
+
+            let key: Vec<u8> = entry.val_at(0)?;
+            let value: Vec<u8> = entry.val_at(1)?;
+            entries.push((Bytes::from(key), Bytes::from(value)));
+        }
+
+        Ok(SnapshotTrie {
+            key: Bytes::from(key),
+            root,
+            entries,
+        })
+    }
+}
+
+#[derive(Debug, Display, From)]
+pub enum SnapshotError {
+    #[display(fmt = "io {:?}", _0)]
+    Io(std::io::Error),
+
+    #[display(fmt = "rlp {:?}", _0)]
+    Rlp(DecoderError),
+
+    #[display(fmt = "unsupported snapshot version {}", version)]
+    UnsupportedVersion { version: u32 },
+
+    #[display(fmt = "checksum expect {:?}, get {:?}", expect, real)]
+    ChecksumMismatch { expect: Hash, real: Hash },
+
+    #[display(fmt = "root expect {:?}, get {:?}", expect, real)]
+    RootMismatch {
+        expect: MerkleRoot,
+        real:   MerkleRoot,
+    },
+}
+
+impl Error for SnapshotError {}
+
+impl From<SnapshotError> for ProtocolError {
+    fn from(err: SnapshotError) -> ProtocolError {
+        ProtocolError::new(ProtocolErrorKind::Executor, Box::new(err))
+    }
+}
@@ -0,0 +1,276 @@
+use std::collections::{HashSet, VecDeque};
+use std::error::Error;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use derive_more::Display;
+use rlp::{DecoderError, Rlp};
+
+use protocol::fixed_codec::ProtocolFixedCodec;
+use protocol::traits::executor::TrieDB;
+use protocol::types::{Account, Hash, MerkleRoot};
+use protocol::{ProtocolError, ProtocolErrorKind, ProtocolResult};
+
+use crate::native_contract::ACCOUNT_CONTRACT_ADDRESS;
+use crate::trie::MPTTrie;
+
+// The length of a node hash, the shorter nodes are inlined in the parent.
+const HASH_LENGTH: usize = 32;
+
+// The kinds of the tries in a state, see `export_snapshot`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TrieKind {
+    State,
+    Native { account: bool },
+    Storage,
+}
+
+/// StateSync assembles the state of a state root in the db from the trie
+/// nodes pulled from other nodes.
+///
+/// The nodes are pulled from the state root down. Every node must hash to the
+/// hash by which its parent refers to it, so the assembled state is the one
+/// of the state root. Once the tries being pulled are complete, the tries
+/// whose roots are kept in them are pulled, the native contract tries after
+/// the state trie and the storage tries of the contract accounts after the
+/// account trie.
+///
+/// The nodes are saved as they are pulled and the nodes already in the db are
+/// walked locally, so an interrupted sync goes on from where it stopped.
+pub struct StateSync<DB: TrieDB> {
+    db:         Arc<DB>,
+    state_root: MerkleRoot,
+
+    tries:   Vec<(MerkleRoot, TrieKind)>,
+    queue:   VecDeque<Hash>,
+    pending: HashSet<Hash>,
+    saved:   u64,
+}
+
+impl<DB: TrieDB> StateSync<DB> {
+    pub fn new(db: Arc<DB>, state_root: MerkleRoot) -> Self {
+        let mut queue = VecDeque::new();
+        queue.push_back(state_root.clone());
+
+        StateSync {
+            db,
+            state_root: state_root.clone(),
+
+            tries: vec![(state_root, TrieKind::State)],
+            queue,
+            pending: HashSet::new(),
+            saved: 0,
+        }
+    }
+
+    /// Take at most `limit` nodes missing in the db to pull. An empty list
+    /// means the state is complete once the pending nodes are saved.
+    pub fn missing_nodes(&mut self, limit: usize) -> ProtocolResult<Vec<Hash>> {
+        let mut missing = vec![];
+
+        loop {
+            while missing.len() < limit {
+                let hash = match self.queue.pop_front() {
+                    Some(hash) => hash,
+                    None => break,
+                };
+                if hash == Hash::from_empty() || self.pending.contains(&hash) {
+                    continue;
+                }
+
+                match self.get_node(&hash)? {
+                    Some(node) => self.queue.extend(node_children(&node)?),
+                    None => {
+                        self.pending.insert(hash.clone());
+                        missing.push(hash);
+                    }
+                }
+            }
+
+            // The sub tries are only found once the tries are complete.
+            if !missing.is_empty() || !self.pending.is_empty() || !self.next_tries()? {
+                return Ok(missing);
+            }
+        }
+    }
+
+    /// Save the pulled nodes, each of which must be a pending one. A node
+    /// which is not requested or not decoded is skipped and the others are
+    /// saved, return the number of the skipped nodes. The pending nodes which
+    /// are not saved are pulled again after `retry_pending`.
+    pub fn save_nodes(&mut self, nodes: Vec<Bytes>) -> ProtocolResult<usize> {
+        let mut skipped = 0;
+        for node in nodes.into_iter() {
+            let hash = Hash::digest(node.clone());
+            if !self.pending.contains(&hash) {
+                skipped += 1;
+                continue;
+            }
+            let children = match node_children(&node) {
+                Ok(children) => children,
+                Err(_) => {
+                    skipped += 1;
+                    continue;
+                }
+            };
+
+            self.db
+                .insert(hash.as_bytes().to_vec(), node.to_vec())
+                .map_err(|e| StateSyncError::DB(e.to_string()))?;
+            self.pending.remove(&hash);
+            self.queue.extend(children);
+            self.saved += 1;
+        }
+        Ok(skipped)
+    }
+
+    /// Pull the pending nodes again, which are not returned by the peers.
+    pub fn retry_pending(&mut self) {
+        self.queue.extend(self.pending.drain());
+    }
+
+    /// The number of the nodes saved.
+    pub fn saved_nodes(&self) -> u64 {
+        self.saved
+    }
+
+    /// Return the state root once the state is complete.
+    pub fn finish(self) -> ProtocolResult<MerkleRoot> {
+        if !self.queue.is_empty() || !self.pending.is_empty() || !self.tries.is_empty() {
+            return Err(StateSyncError::Incomplete {
+                state_root: self.state_root,
+            }
+            .into());
+        }
+        Ok(self.state_root)
+    }
+
+    // Move on to the tries whose roots are kept in the complete tries, return
+    // false if there is none.
+    fn next_tries(&mut self) -> ProtocolResult<bool> {
+        let mut next = vec![];
+        for (root, kind) in self.tries.iter() {
+            if *root == Hash::from_empty() {
+                continue;
+            }
+
+            let entries = MPTTrie::from(root.clone(), Arc::clone(&self.db))?.entries();
+            match kind {
+                TrieKind::State => {
+                    for (address, root) in entries.into_iter() {
+                        let account = address == ACCOUNT_CONTRACT_ADDRESS.as_bytes();
+                        next.push((MerkleRoot::from_bytes(root)?, TrieKind::Native { account }));
+                    }
+                }
+                TrieKind::Native { account: true } => {
+                    for (_, value) in entries.into_iter() {
+                        if let Account::Contract(account) = Account::decode_fixed(value)? {
+                            next.push((account.storage_root, TrieKind::Storage));
+                        }
+                    }
+                }
+                TrieKind::Native { account: false } | TrieKind::Storage => {}
+            }
+        }
+
+        self.queue = next.iter().map(|(root, _)| root.clone()).collect();
+        self.tries = next;
+        Ok(!self.tries.is_empty())
+    }
+
+    fn get_node(&self, hash: &Hash) -> ProtocolResult<Option<Vec<u8>>> {
+        let node = self
+            .db
+            .get(&hash.as_bytes())
+            .map_err(|e| StateSyncError::DB(e.to_string()))?;
+        Ok(node)
+    }
+}
+
+/// Get the trie nodes of the hashes in the db, the missing ones are skipped.
+pub fn get_trie_nodes<DB: TrieDB>(db: &DB, hashes: &[Hash]) -> ProtocolResult<Vec<Bytes>> {
+    let mut nodes = vec![];
+    for hash in hashes.iter() {
+        if let Some(node) = db
+            .get(&hash.as_bytes())
+            .map_err(|e| StateSyncError::DB(e.to_string()))?
+        {
+            nodes.push(Bytes::from(node));
+        }
+    }
+    Ok(nodes)
+}
+
+// The hashes of the children of an encoded node. A branch is a list of 16
+// children and a value, an extension or a leaf is a list of a hex-prefix
+// encoded path and a child or a value, told apart by the flag of the path.
+fn node_children(node: &[u8]) -> ProtocolResult<Vec<Hash>> {
+    let mut children = vec![];
+    collect_children(&Rlp::new(node), &mut children).map_err(StateSyncError::Rlp)?;
+
+    children
+        .into_iter()
+        .map(|child| Hash::from_bytes(Bytes::from(child)))
+        .collect()
+}
+
+fn collect_children(node: &Rlp, children: &mut Vec<Vec<u8>>) -> Result<(), DecoderError> {
+    if !node.is_list() {
+        return Ok(());
+    }
+
+    match node.item_count()? {
+        17 => {
+            for i in 0..16 {
+                collect_child(&node.at(i)?, children)?;
+            }
+        }
+        2 => {
+            let path = node.at(0)?.data()?;
+            let is_leaf = path.first().map_or(false, |flag| flag & 0x20 != 0);
+            if !is_leaf {
+                collect_child(&node.at(1)?, children)?;
+            }
+        }
+        _ => return Err(DecoderError::RlpIncorrectListLen),
+    }
+    Ok(())
+}
+
+// A child is the hash of the node, or the node itself if it is shorter than a
+// hash.
+fn collect_child(child: &Rlp, children: &mut Vec<Vec<u8>>) -> Result<(), DecoderError> {
+    if child.is_list() {
+        return collect_children(child, children);
+    }
+
+    let data = child.data()?;
+    match data.len() {
+        0 => Ok(()),
+        HASH_LENGTH => {
+            children.push(data.to_vec());
+            Ok(())
+        }
+        _ => Err(DecoderError::RlpInvalidLength),
+    }
+}
+
+#[derive(Debug, Display)]
+pub enum StateSyncError {
+    #[display(fmt = "rlp {:?}", _0)]
+    Rlp(DecoderError),
+
+    #[display(fmt = "db {}", _0)]
+    DB(String),
+
+    #[display(fmt = "state {:?} is incomplete", state_root)]
+    Incomplete { state_root: MerkleRoot },
+}
+
+impl Error for StateSyncError {}
+
+impl From<StateSyncError> for ProtocolError {
+    fn from(err: StateSyncError) -> ProtocolError {
+        ProtocolError::new(ProtocolErrorKind::Executor, Box::new(err))
+    }
+}
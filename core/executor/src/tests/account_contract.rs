@@ -2,7 +2,9 @@ use std::cell::RefCell;
 use std::rc::Rc;
 
 use protocol::traits::executor::contract::AccountContract;
-use protocol::types::{Address, AssetID, Balance, CarryingAsset};
+use protocol::types::{
+    Account, Address, AssetID, Balance, CarryingAsset, ContractAddress, TransferOutput, UserAddress,
+};
 
 use crate::native_contract::NativeAccountContract;
 use crate::tests::{create_state_adapter, mock_invoke_context};
@@ -35,3 +37,109 @@ fn test_account_contract() {
     let user2_balance = account.get_balance(&asset, &user2).unwrap();
     assert_eq!(user2_balance, Balance::from(1000u64));
 }
+
+#[test]
+fn test_account_contract_approve() {
+    let state = Rc::new(RefCell::new(create_state_adapter()));
+    let mut account = NativeAccountContract::new(state);
+
+    let asset =
+        AssetID::from_hex("0000000000000000000000000000000000000000000000000000000000000003")
+            .unwrap();
+    let fee_asset =
+        AssetID::from_hex("0000000000000000000000000000000000000000000000000000000000000004")
+            .unwrap();
+    let user1 = Address::from_hex("100000000000000000000000000000000000000001").unwrap();
+    let user2 = Address::from_hex("100000000000000000000000000000000000000002").unwrap();
+    let spender = ContractAddress::from_hex("210000000000000000000000000000000000000001").unwrap();
+    account
+        .add_balance(&asset, &user1, 10000u64.into())
+        .unwrap();
+
+    let ctx = mock_invoke_context(user1.clone(), None, 0, 1_000_000, fee_asset.clone());
+    account
+        .approve(Rc::clone(&ctx), &spender, &asset, 1000u64.into())
+        .unwrap();
+
+    let ctx = mock_invoke_context(
+        Address::Contract(spender.clone()),
+        None,
+        0,
+        1_000_000,
+        fee_asset,
+    );
+    account
+        .transfer_from(Rc::clone(&ctx), &user1, &user2, &asset, 600u64.into())
+        .unwrap();
+    let user1_balance = account.get_balance(&asset, &user1).unwrap();
+    assert_eq!(user1_balance, Balance::from(9400u64));
+    let user2_balance = account.get_balance(&asset, &user2).unwrap();
+    assert_eq!(user2_balance, Balance::from(600u64));
+
+    // exceed the approved amount
+    let res = account.transfer_from(Rc::clone(&ctx), &user1, &user2, &asset, 500u64.into());
+    assert_eq!(res.is_err(), true);
+
+    match account.get_account(&user1).unwrap() {
+        Account::User(user) => {
+            let approved = &user.assets.get(&asset).unwrap().approved[&spender];
+            assert_eq!(approved.max, Balance::from(1000u64));
+            assert_eq!(approved.used, Balance::from(600u64));
+        }
+        Account::Contract(_) => panic!("user1 should be a user account"),
+    }
+}
+
+#[test]
+fn test_account_contract_batch_transfer() {
+    let state = Rc::new(RefCell::new(create_state_adapter()));
+    let mut account = NativeAccountContract::new(state);
+
+    let asset =
+        AssetID::from_hex("0000000000000000000000000000000000000000000000000000000000000003")
+            .unwrap();
+    let fee_asset =
+        AssetID::from_hex("0000000000000000000000000000000000000000000000000000000000000004")
+            .unwrap();
+    let user1 = Address::from_hex("100000000000000000000000000000000000000001").unwrap();
+    let user2 = UserAddress::from_hex("100000000000000000000000000000000000000002").unwrap();
+    let user3 = UserAddress::from_hex("100000000000000000000000000000000000000003").unwrap();
+    account
+        .add_balance(&asset, &user1, 10000u64.into())
+        .unwrap();
+    account
+        .add_balance(&fee_asset, &user1, 500u64.into())
+        .unwrap();
+
+    let output = |receiver: &UserAddress, asset_id: &AssetID, amount: u64| TransferOutput {
+        receiver:       receiver.clone(),
+        carrying_asset: CarryingAsset {
+            asset_id: asset_id.clone(),
+            amount:   amount.into(),
+        },
+    };
+    let ctx = mock_invoke_context(user1.clone(), None, 0, 1_000_000, fee_asset.clone());
+    let results = account
+        .batch_transfer(Rc::clone(&ctx), &[
+            output(&user2, &asset, 1000),
+            output(&user3, &asset, 2000),
+            output(&user3, &fee_asset, 100),
+        ])
+        .unwrap();
+
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].before_amount, Balance::from(10000u64));
+    assert_eq!(results[0].after_amount, Balance::from(9000u64));
+    assert_eq!(results[1].before_amount, Balance::from(9000u64));
+    assert_eq!(results[1].after_amount, Balance::from(7000u64));
+    assert_eq!(results[2].before_amount, Balance::from(500u64));
+    assert_eq!(results[2].after_amount, Balance::from(400u64));
+    assert_eq!(ctx.borrow().logs.len(), 3);
+
+    let user2_balance = account.get_balance(&asset, &Address::User(user2)).unwrap();
+    assert_eq!(user2_balance, Balance::from(1000u64));
+    let user3_balance = account
+        .get_balance(&fee_asset, &Address::User(user3))
+        .unwrap();
+    assert_eq!(user3_balance, Balance::from(100u64));
+}
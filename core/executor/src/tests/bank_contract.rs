@@ -31,11 +31,13 @@ fn test_bank_contract() {
             name.clone(),
             symbol.clone(),
             supply.clone(),
+            18,
         )
         .unwrap();
     assert_eq!(&asset.symbol, &symbol);
     assert_eq!(&asset.name, &name);
     assert_eq!(&asset.supply, &supply);
+    assert_eq!(asset.decimals, 18);
     assert_eq!(&asset.manage_contract, &address);
 
     // use the same address to register
@@ -45,6 +47,7 @@ fn test_bank_contract() {
         name,
         symbol,
         supply,
+        18,
     );
     assert_eq!(asset2.is_err(), true);
 
@@ -54,3 +57,63 @@ fn test_bank_contract() {
         .unwrap();
     assert_eq!(&asset, &asset_get);
 }
+
+#[test]
+fn test_bank_mint_and_burn() {
+    let chain_id = Hash::from_empty();
+    let address = ContractAddress::from_hex("200000000000000000000000000000000000000000").unwrap();
+    let issuer = Address::from_hex("100000000000000000000000000000000000000010").unwrap();
+    let stranger = Address::from_hex("100000000000000000000000000000000000000011").unwrap();
+    let state = Rc::new(RefCell::new(create_state_adapter()));
+    let mut bank = NativeBankContract::new(chain_id, state);
+    let fee_asset = AssetID::from_empty();
+
+    let ctx = mock_invoke_context(issuer.clone(), None, 0, 1_000_000, fee_asset.clone());
+    let asset = bank
+        .register(
+            Rc::clone(&ctx),
+            &address,
+            "Stable coin".to_owned(),
+            "SC".to_owned(),
+            Balance::from(100u64),
+            8,
+        )
+        .unwrap();
+    assert_eq!(asset.issuer, issuer);
+
+    let asset = bank
+        .mint(Rc::clone(&ctx), &asset.id, Balance::from(50u64))
+        .unwrap();
+    assert_eq!(asset.supply, Balance::from(150u64));
+
+    let asset = bank
+        .burn(Rc::clone(&ctx), &asset.id, Balance::from(30u64))
+        .unwrap();
+    assert_eq!(asset.supply, Balance::from(120u64));
+    assert_eq!(
+        bank.get_asset(Rc::clone(&ctx), &asset.id).unwrap().supply,
+        Balance::from(120u64)
+    );
+
+    // Burn more than the supply.
+    assert_eq!(
+        bank.burn(Rc::clone(&ctx), &asset.id, Balance::from(121u64))
+            .is_err(),
+        true
+    );
+
+    // Only the issuer and the manage contract can change the supply.
+    let stranger_ctx = mock_invoke_context(stranger, None, 0, 1_000_000, fee_asset.clone());
+    assert_eq!(
+        bank.mint(stranger_ctx, &asset.id, Balance::from(1u64))
+            .is_err(),
+        true
+    );
+
+    let manager_ctx =
+        mock_invoke_context(Address::Contract(address), None, 0, 1_000_000, fee_asset);
+    let asset = bank
+        .mint(manager_ctx, &asset.id, Balance::from(1u64))
+        .unwrap();
+    assert_eq!(asset.supply, Balance::from(121u64));
+}
@@ -0,0 +1,61 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use protocol::traits::executor::contract::AccountContract;
+use protocol::traits::executor::Dispatcher;
+use protocol::types::{Address, AssetID, Balance, ContractAddress};
+
+use crate::dispatcher::NativeDispatcher;
+use crate::native_contract::NativeAccountContract;
+use crate::tests::{create_state_adapter, mock_invoke_context};
+
+#[test]
+fn test_dispatcher_invoke() {
+    let state = Rc::new(RefCell::new(create_state_adapter()));
+    let mut account = NativeAccountContract::new(Rc::clone(&state));
+
+    let asset =
+        AssetID::from_hex("0000000000000000000000000000000000000000000000000000000000000003")
+            .unwrap();
+    let user1 = Address::from_hex("100000000000000000000000000000000000000001").unwrap();
+    account
+        .add_balance(&asset, &user1, 10000u64.into())
+        .unwrap();
+
+    let address = ContractAddress::from_hex("230000000000000000000000000000000000000001").unwrap();
+    let mut dispatcher = NativeDispatcher::new();
+    dispatcher
+        .register(
+            address.clone(),
+            Rc::new(RefCell::new(NativeAccountContract::new(Rc::clone(&state)))),
+        )
+        .unwrap();
+
+    // register the same address twice
+    let res = dispatcher.register(
+        address.clone(),
+        Rc::new(RefCell::new(NativeAccountContract::new(Rc::clone(&state)))),
+    );
+    assert_eq!(res.is_err(), true);
+
+    let ctx = mock_invoke_context(user1.clone(), None, 0, 1_000_000, asset.clone());
+    let balance = dispatcher
+        .invoke(Rc::clone(&ctx), address.clone(), "get_balance", vec![
+            asset.as_bytes(),
+            user1.as_bytes(),
+        ])
+        .unwrap();
+    assert_eq!(
+        Balance::from_bytes_be(balance.as_ref()),
+        Balance::from(10000u64)
+    );
+
+    // unknown method
+    let res = dispatcher.invoke(Rc::clone(&ctx), address, "unknown", vec![]);
+    assert_eq!(res.is_err(), true);
+
+    // unknown contract
+    let unknown = ContractAddress::from_hex("230000000000000000000000000000000000000009").unwrap();
+    let res = dispatcher.invoke(Rc::clone(&ctx), unknown, "get_balance", vec![]);
+    assert_eq!(res.is_err(), true);
+}
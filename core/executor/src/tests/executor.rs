@@ -0,0 +1,1736 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use cita_trie::MemoryDB;
+use common_crypto::{PrivateKey, PublicKey};
+
+use protocol::fixed_codec::ProtocolFixedCodec;
+use protocol::traits::executor::{Executor, ExecutorFactory};
+use protocol::types::{
+    Account, Address, Asset, AssetID, AssetParams, Balance, BalanceDiff, BloomInput, CarryingAsset,
+    ChainParams, ContractAddress, ContractType, Fee, Genesis, GenesisAsset, GenesisContract,
+    GenesisParams, GenesisStateAlloc, GenesisStateAsset, GenesisSystemToken, GenesisValidator,
+    Hash, MerkleRoot, MultisigOwner, RawTransaction, ReceiptResult, SignedTransaction,
+    TransactionAction, TransferOutput, UserAddress, Validator,
+};
+
+use crate::native_contract::{
+    ACCOUNT_CONTRACT_ADDRESS, BANK_CONTRACT_ADDRESS, GOVERNANCE_CONTRACT_ADDRESS, MINT_LOG_TOPIC,
+    MULTISIG_CONTRACT_ADDRESS, MULTISIG_EXECUTE_LOG_TOPIC, TRANSFER_LOG_TOPIC,
+};
+use crate::snapshot::{export_snapshot, import_snapshot};
+use crate::state_sync::{get_trie_nodes, StateSync};
+use crate::tests::{
+    create_empty_memdb, mock_bls_pop, mock_bls_pub_key, mock_multisig_signer, multisig_sign,
+};
+use crate::TransactionExecutorFactory;
+
+const SYSTEM_TOKEN_CODE: &str = "ff";
+
+fn mock_executor(
+    chain_id: &Hash,
+    state_root: &MerkleRoot,
+    db: &Arc<MemoryDB>,
+    epoch_id: u64,
+    coinbase: &Address,
+) -> Box<dyn Executor> {
+    TransactionExecutorFactory::from_root(
+        chain_id.clone(),
+        state_root.clone(),
+        Arc::clone(db),
+        epoch_id,
+        1,
+        coinbase.clone(),
+    )
+    .unwrap()
+}
+
+fn system_token_id(chain_id: &Hash) -> AssetID {
+    let code = Bytes::from(hex::decode(SYSTEM_TOKEN_CODE).unwrap());
+    let address = ContractAddress::from_code(code, 0, ContractType::Asset).unwrap();
+    Hash::digest(Bytes::from(
+        [chain_id.as_bytes(), address.as_bytes()].concat(),
+    ))
+}
+
+// The system token is allocated to one account, whose balance is the supply.
+fn mock_genesis(alloc: &Address, asset_id: &AssetID, balance: &Balance) -> Genesis {
+    Genesis {
+        timestamp:    0,
+        prevhash:     "".to_owned(),
+        system_token: GenesisSystemToken {
+            code:     SYSTEM_TOKEN_CODE.to_owned(),
+            name:     "System token".to_owned(),
+            symbol:   "ST".to_owned(),
+            supply:   balance.to_string().parse().unwrap(),
+            decimals: 8,
+        },
+        state_alloc:  vec![GenesisStateAlloc {
+            address: alloc.as_hex(),
+            assets:  vec![GenesisStateAsset {
+                asset_id: asset_id.as_hex(),
+                balance:  hex::encode(balance.to_bytes_be()),
+            }],
+        }],
+        validators:   vec![GenesisValidator {
+            address:        alloc.as_hex(),
+            propose_weight: 1,
+            vote_weight:    1,
+            bls_pub_key:    hex::encode(mock_bls_pub_key(1)),
+            bls_pop:        hex::encode(mock_bls_pop(
+                1,
+                &UserAddress::from_hex(&alloc.as_hex()).unwrap(),
+            )),
+        }],
+        params:       GenesisParams {
+            cycles_limit: 99_999_999,
+            cycles_price: 1,
+            timeout_gap:  20,
+            interval:     3000,
+        },
+        assets:       vec![],
+        contracts:    vec![],
+    }
+}
+
+fn mock_signed_tx(
+    chain_id: &Hash,
+    pubkey: &Bytes,
+    fee_asset_id: &AssetID,
+    nonce: &str,
+    action: TransactionAction,
+) -> SignedTransaction {
+    let raw = RawTransaction {
+        chain_id: chain_id.clone(),
+        nonce: Hash::digest(Bytes::from(nonce)),
+        timeout: 10,
+        fee: Fee {
+            asset_id: fee_asset_id.clone(),
+            cycle:    1000,
+        },
+        action,
+    };
+
+    SignedTransaction {
+        raw,
+        tx_hash: Hash::digest(Bytes::from(nonce)),
+        pubkey: pubkey.clone(),
+        signature: Bytes::new(),
+    }
+}
+
+// A riscv contract which calls `CONTRACT_CALL` with its args, that is
+// `contract_call(argv[2], argv[3], argv[4])`, then exits with 0.
+fn mock_contract_call_code() -> Bytes {
+    let instructions: [u32; 10] = [
+        0x0181_3503, // ld a0, 24(sp)
+        0x0201_3583, // ld a1, 32(sp)
+        0x0281_3603, // ld a2, 40(sp)
+        0xc001_0693, // addi a3, sp, -1024
+        0x0000_18b7, // lui a7, 1
+        0x8888_8893, // addi a7, a7, -1912
+        0x0000_0073, // ecall
+        0x0000_0513, // li a0, 0
+        0x05d0_0893, // li a7, 93
+        0x0000_0073, // ecall
+    ];
+
+    // One loadable segment of the whole file, the code follows the headers.
+    let base = 0x10000u64;
+    let entry = base + 64 + 56;
+    let size = 64 + 56 + instructions.len() as u64 * 4;
+
+    let mut elf = vec![0x7f, b'E', b'L', b'F', 2, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+    elf.extend_from_slice(&2u16.to_le_bytes()); // executable
+    elf.extend_from_slice(&243u16.to_le_bytes()); // riscv
+    elf.extend_from_slice(&1u32.to_le_bytes());
+    elf.extend_from_slice(&entry.to_le_bytes());
+    elf.extend_from_slice(&64u64.to_le_bytes()); // program header offset
+    elf.extend_from_slice(&0u64.to_le_bytes()); // section header offset
+    elf.extend_from_slice(&0u32.to_le_bytes());
+    for half in [64u16, 56, 1, 64, 0, 0].iter() {
+        elf.extend_from_slice(&half.to_le_bytes());
+    }
+
+    elf.extend_from_slice(&1u32.to_le_bytes()); // load
+    elf.extend_from_slice(&5u32.to_le_bytes()); // read and execute
+    elf.extend_from_slice(&0u64.to_le_bytes());
+    elf.extend_from_slice(&base.to_le_bytes());
+    elf.extend_from_slice(&base.to_le_bytes());
+    elf.extend_from_slice(&size.to_le_bytes());
+    elf.extend_from_slice(&size.to_le_bytes());
+    elf.extend_from_slice(&0x1000u64.to_le_bytes());
+
+    for instruction in instructions.iter() {
+        elf.extend_from_slice(&instruction.to_le_bytes());
+    }
+    Bytes::from(elf)
+}
+
+#[test]
+fn test_genesis_assets_and_contracts() {
+    let chain_id = Hash::from_empty();
+    let coinbase = Address::from_hex("100000000000000000000000000000000000000009").unwrap();
+
+    let pubkey = Bytes::from("pubkey");
+    let sender = Address::User(UserAddress::from_pubkey_bytes(pubkey).unwrap());
+
+    let asset_code = Bytes::from(hex::decode("ee").unwrap());
+    let asset_address = ContractAddress::from_code(asset_code, 0, ContractType::Asset).unwrap();
+    let asset_id = Hash::digest(Bytes::from(
+        [chain_id.as_bytes(), asset_address.as_bytes()].concat(),
+    ));
+
+    let contract_code = Bytes::from(hex::decode("aa").unwrap());
+    let contract_address = ContractAddress::from_code(contract_code, 0, ContractType::App).unwrap();
+
+    let mut genesis = mock_genesis(
+        &sender,
+        &system_token_id(&chain_id),
+        &Balance::from(10_000u64),
+    );
+    genesis.assets.push(GenesisAsset {
+        code:     "ee".to_owned(),
+        name:     "Extra token".to_owned(),
+        symbol:   "ET".to_owned(),
+        supply:   1000,
+        decimals: 8,
+    });
+    genesis.state_alloc[0].assets.push(GenesisStateAsset {
+        asset_id: asset_id.as_hex(),
+        balance:  hex::encode(Balance::from(1000u64).to_bytes_be()),
+    });
+    genesis.contracts.push(GenesisContract {
+        code:    "aa".to_owned(),
+        library: false,
+    });
+
+    // The balances of an asset must add up to the supply of it.
+    let mut bad_genesis = genesis.clone();
+    bad_genesis.assets[0].supply = 1001;
+    let mut executor = mock_executor(
+        &chain_id,
+        &Hash::from_empty(),
+        &create_empty_memdb(),
+        1,
+        &coinbase,
+    );
+    assert!(executor.create_genesis(&bad_genesis).is_err());
+
+    // The contract address is derived from the code, which must be unique.
+    let mut bad_genesis = genesis.clone();
+    bad_genesis.contracts.push(genesis.contracts[0].clone());
+    let mut executor = mock_executor(
+        &chain_id,
+        &Hash::from_empty(),
+        &create_empty_memdb(),
+        1,
+        &coinbase,
+    );
+    assert!(executor.create_genesis(&bad_genesis).is_err());
+
+    let db = create_empty_memdb();
+    let mut executor = mock_executor(&chain_id, &Hash::from_empty(), &db, 1, &coinbase);
+    let state_root = executor.create_genesis(&genesis).unwrap();
+
+    // Every node builds the same genesis, whatever its coinbase is.
+    let other_coinbase = Address::from_hex("100000000000000000000000000000000000000008").unwrap();
+    let mut executor = mock_executor(
+        &chain_id,
+        &Hash::from_empty(),
+        &create_empty_memdb(),
+        1,
+        &other_coinbase,
+    );
+    assert_eq!(executor.create_genesis(&genesis).unwrap(), state_root);
+
+    let executor = mock_executor(&chain_id, &state_root, &db, 1, &coinbase);
+    assert_eq!(
+        executor.get_balance(&sender, &asset_id).unwrap(),
+        Balance::from(1000u64)
+    );
+
+    // The genesis assets are issued by the governance contract.
+    let asset = executor
+        .get_asset_proof(&asset_id)
+        .unwrap()
+        .verify_asset(&state_root, &BANK_CONTRACT_ADDRESS, &asset_id)
+        .unwrap()
+        .unwrap();
+    assert_eq!(asset.issuer, *GOVERNANCE_CONTRACT_ADDRESS);
+
+    let contract = Address::Contract(contract_address);
+    let proof = executor.get_account_proof(&contract).unwrap();
+    match proof
+        .verify_account(&state_root, &ACCOUNT_CONTRACT_ADDRESS, &contract)
+        .unwrap()
+    {
+        Some(Account::Contract(_)) => {}
+        _ => panic!("the contract should be deployed"),
+    }
+}
+
+#[test]
+fn test_exec_charge_fee() {
+    let chain_id = Hash::from_empty();
+    let asset_id = system_token_id(&chain_id);
+    let coinbase = Address::from_hex("100000000000000000000000000000000000000009").unwrap();
+    let receiver = UserAddress::from_hex("100000000000000000000000000000000000000001").unwrap();
+
+    let pubkey = Bytes::from("pubkey");
+    let sender = Address::User(UserAddress::from_pubkey_bytes(pubkey.clone()).unwrap());
+
+    let db = create_empty_memdb();
+    let mut executor = mock_executor(&chain_id, &Hash::from_empty(), &db, 1, &coinbase);
+    executor
+        .create_genesis(&mock_genesis(&sender, &asset_id, &Balance::from(10_000u64)))
+        .unwrap();
+
+    let transfer = mock_signed_tx(
+        &chain_id,
+        &pubkey,
+        &asset_id,
+        "transfer",
+        TransactionAction::Transfer {
+            receiver:       receiver.clone(),
+            carrying_asset: CarryingAsset {
+                asset_id: asset_id.clone(),
+                amount:   Balance::from(100u64),
+            },
+        },
+    );
+
+    // The call fails after the dispatcher has consumed cycles.
+    let account_contract = match ACCOUNT_CONTRACT_ADDRESS.clone() {
+        Address::Contract(contract) => contract,
+        Address::User(_) => unreachable!(),
+    };
+    let failed_call = mock_signed_tx(
+        &chain_id,
+        &pubkey,
+        &asset_id,
+        "failed_call",
+        TransactionAction::Call {
+            contract:       account_contract,
+            method:         "unknown".to_owned(),
+            args:           vec![],
+            carrying_asset: None,
+        },
+    );
+
+    let resp = executor.exec(vec![transfer, failed_call]).unwrap();
+
+    let transfer_fee = resp.receipts[0].cycles_used.cycle;
+    let call_fee = resp.receipts[1].cycles_used.cycle;
+    assert_eq!(transfer_fee > 0, true);
+    assert_eq!(call_fee > 0, true);
+    match &resp.receipts[1].result {
+        ReceiptResult::Fail { .. } => {}
+        _ => panic!("call to an unknown method should fail"),
+    }
+
+    assert_eq!(
+        executor.get_balance(&sender, &asset_id).unwrap(),
+        Balance::from(10_000u64 - 100 - transfer_fee - call_fee)
+    );
+    assert_eq!(
+        executor
+            .get_balance(&Address::User(receiver), &asset_id)
+            .unwrap(),
+        Balance::from(100u64)
+    );
+    assert_eq!(
+        executor.get_balance(&coinbase, &asset_id).unwrap(),
+        Balance::from(transfer_fee + call_fee)
+    );
+
+    // Can not afford the fee.
+    let pubkey = Bytes::from("poor");
+    let poor_tx = mock_signed_tx(
+        &chain_id,
+        &pubkey,
+        &asset_id,
+        "poor_tx",
+        TransactionAction::Transfer {
+            receiver,
+            carrying_asset: CarryingAsset {
+                asset_id: asset_id.clone(),
+                amount:   Balance::from(1u64),
+            },
+        },
+    );
+    let resp = executor.exec(vec![poor_tx]).unwrap();
+    assert_eq!(resp.receipts[0].cycles_used.cycle, 0);
+}
+
+#[test]
+fn test_exec_reject_replay() {
+    let chain_id = Hash::from_empty();
+    let asset_id = system_token_id(&chain_id);
+    let coinbase = Address::from_hex("100000000000000000000000000000000000000009").unwrap();
+    let receiver = UserAddress::from_hex("100000000000000000000000000000000000000001").unwrap();
+
+    let pubkey = Bytes::from("pubkey");
+    let sender = Address::User(UserAddress::from_pubkey_bytes(pubkey.clone()).unwrap());
+
+    let db = create_empty_memdb();
+    let mut executor = mock_executor(&chain_id, &Hash::from_empty(), &db, 1, &coinbase);
+    executor
+        .create_genesis(&mock_genesis(&sender, &asset_id, &Balance::from(10_000u64)))
+        .unwrap();
+
+    let transfer = mock_signed_tx(
+        &chain_id,
+        &pubkey,
+        &asset_id,
+        "nonce",
+        TransactionAction::Transfer {
+            receiver,
+            carrying_asset: CarryingAsset {
+                asset_id: asset_id.clone(),
+                amount:   Balance::from(100u64),
+            },
+        },
+    );
+    let nonce = transfer.raw.nonce.clone();
+
+    let resp = executor
+        .exec(vec![transfer.clone(), transfer.clone()])
+        .unwrap();
+    match &resp.receipts[0].result {
+        ReceiptResult::Transfer { .. } => {}
+        _ => panic!("the first transfer should succeed"),
+    }
+    match &resp.receipts[1].result {
+        ReceiptResult::Fail { .. } => {}
+        _ => panic!("the replayed transfer should fail"),
+    }
+    assert_eq!(resp.receipts[1].cycles_used.cycle, 0);
+    assert_eq!(executor.is_nonce_used(&sender, &nonce).unwrap(), true);
+
+    // The nonce is still recorded after restart.
+    let mut executor = mock_executor(&chain_id, &resp.state_root, &db, 2, &coinbase);
+    assert_eq!(executor.is_nonce_used(&sender, &nonce).unwrap(), true);
+
+    // The nonce can be read without an executor, as the mempool does.
+    let is_nonce_used = |nonce: &Hash| {
+        TransactionExecutorFactory::is_nonce_used(
+            resp.state_root.clone(),
+            Arc::clone(&db),
+            &sender,
+            nonce,
+        )
+        .unwrap()
+    };
+    assert_eq!(is_nonce_used(&nonce), true);
+    assert_eq!(is_nonce_used(&Hash::digest(Bytes::from("unused"))), false);
+
+    let resp = executor.exec(vec![transfer.clone()]).unwrap();
+    match &resp.receipts[0].result {
+        ReceiptResult::Fail { .. } => {}
+        _ => panic!("the replayed transfer should fail"),
+    }
+
+    // The timeout can not exceed `epoch_id + timeout_gap`, or the nonce would
+    // be kept for too long.
+    let mut far_transfer = transfer;
+    far_transfer.raw.nonce = Hash::digest(Bytes::from("far_nonce"));
+    far_transfer.raw.timeout = 2 + 20 + 1;
+    let far_nonce = far_transfer.raw.nonce.clone();
+
+    let resp = executor.exec(vec![far_transfer]).unwrap();
+    match &resp.receipts[0].result {
+        ReceiptResult::Fail { .. } => {}
+        _ => panic!("the transfer beyond the timeout gap should fail"),
+    }
+    assert_eq!(resp.receipts[0].cycles_used.cycle, 0);
+    assert_eq!(executor.is_nonce_used(&sender, &far_nonce).unwrap(), false);
+}
+
+#[test]
+fn test_exec_emit_logs() {
+    let chain_id = Hash::from_empty();
+    let asset_id = system_token_id(&chain_id);
+    let coinbase = Address::from_hex("100000000000000000000000000000000000000009").unwrap();
+    let receiver = UserAddress::from_hex("100000000000000000000000000000000000000001").unwrap();
+
+    let pubkey = Bytes::from("pubkey");
+    let sender = Address::User(UserAddress::from_pubkey_bytes(pubkey.clone()).unwrap());
+
+    let db = create_empty_memdb();
+    let mut executor = mock_executor(&chain_id, &Hash::from_empty(), &db, 1, &coinbase);
+    executor
+        .create_genesis(&mock_genesis(&sender, &asset_id, &Balance::from(10_000u64)))
+        .unwrap();
+
+    let transfer = mock_signed_tx(
+        &chain_id,
+        &pubkey,
+        &asset_id,
+        "transfer",
+        TransactionAction::Transfer {
+            receiver:       receiver.clone(),
+            carrying_asset: CarryingAsset {
+                asset_id: asset_id.clone(),
+                amount:   Balance::from(100u64),
+            },
+        },
+    );
+    // Transfer more than the balance, the logs should be dropped.
+    let failed_transfer = mock_signed_tx(
+        &chain_id,
+        &pubkey,
+        &asset_id,
+        "failed_transfer",
+        TransactionAction::Transfer {
+            receiver:       receiver.clone(),
+            carrying_asset: CarryingAsset {
+                asset_id: asset_id.clone(),
+                amount:   Balance::from(100_000u64),
+            },
+        },
+    );
+
+    let resp = executor.exec(vec![transfer, failed_transfer]).unwrap();
+
+    let logs = &resp.receipts[0].logs;
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0].address, ACCOUNT_CONTRACT_ADDRESS.clone());
+    assert_eq!(logs[0].topics, vec![
+        TRANSFER_LOG_TOPIC.clone(),
+        asset_id.clone()
+    ]);
+    assert_eq!(
+        logs[0].data.to_vec(),
+        [
+            sender.as_bytes().to_vec(),
+            receiver.as_bytes().to_vec(),
+            vec![100u8]
+        ]
+        .concat()
+    );
+    assert_eq!(resp.receipts[1].logs.is_empty(), true);
+
+    let logs_bloom = &resp.logs_bloom;
+    assert_eq!(
+        logs_bloom.contains_input(BloomInput::Raw(&ACCOUNT_CONTRACT_ADDRESS.as_bytes())),
+        true
+    );
+    assert_eq!(
+        logs_bloom.contains_input(BloomInput::Raw(&TRANSFER_LOG_TOPIC.as_bytes())),
+        true
+    );
+    assert_eq!(
+        logs_bloom.contains_input(BloomInput::Raw(&asset_id.as_bytes())),
+        true
+    );
+}
+
+#[test]
+fn test_simulate() {
+    let chain_id = Hash::from_empty();
+    let asset_id = system_token_id(&chain_id);
+    let coinbase = Address::from_hex("100000000000000000000000000000000000000009").unwrap();
+    let receiver = UserAddress::from_hex("100000000000000000000000000000000000000001").unwrap();
+
+    let pubkey = Bytes::from("pubkey");
+    let sender = Address::User(UserAddress::from_pubkey_bytes(pubkey.clone()).unwrap());
+
+    let db = create_empty_memdb();
+    let mut executor = mock_executor(&chain_id, &Hash::from_empty(), &db, 1, &coinbase);
+    executor
+        .create_genesis(&mock_genesis(&sender, &asset_id, &Balance::from(10_000u64)))
+        .unwrap();
+
+    let transfer = mock_signed_tx(
+        &chain_id,
+        &pubkey,
+        &asset_id,
+        "transfer",
+        TransactionAction::Transfer {
+            receiver:       receiver.clone(),
+            carrying_asset: CarryingAsset {
+                asset_id: asset_id.clone(),
+                amount:   Balance::from(100u64),
+            },
+        },
+    );
+
+    let resp = executor.simulate(transfer.clone()).unwrap();
+    match resp.result {
+        ReceiptResult::Transfer { .. } => {}
+        _ => panic!("the simulated transfer should succeed"),
+    }
+    assert_eq!(resp.cycles_used > 0, true);
+
+    // Nothing is changed by the simulation.
+    assert_eq!(
+        executor.get_balance(&sender, &asset_id).unwrap(),
+        Balance::from(10_000u64)
+    );
+    assert_eq!(
+        executor
+            .get_balance(&Address::User(receiver), &asset_id)
+            .is_err(),
+        true
+    );
+
+    // The cycles price is 1, so the fee equals to the estimated cycles.
+    let exec_resp = executor.exec(vec![transfer]).unwrap();
+    assert_eq!(exec_resp.receipts[0].cycles_used.cycle, resp.cycles_used);
+
+    let account_contract = match ACCOUNT_CONTRACT_ADDRESS.clone() {
+        Address::Contract(contract) => contract,
+        Address::User(_) => unreachable!(),
+    };
+    let resp = executor
+        .read_call(&sender, &account_contract, "unknown", vec![])
+        .unwrap();
+    match resp.result {
+        ReceiptResult::Fail { .. } => {}
+        _ => panic!("call to an unknown method should fail"),
+    }
+}
+
+#[test]
+fn test_exec_approve_and_transfer_from() {
+    let chain_id = Hash::from_empty();
+    let asset_id = system_token_id(&chain_id);
+    let coinbase = Address::from_hex("100000000000000000000000000000000000000009").unwrap();
+    let receiver = Address::from_hex("100000000000000000000000000000000000000001").unwrap();
+    let spender = ContractAddress::from_code(Bytes::from("spender"), 0, ContractType::App).unwrap();
+    let account_contract = match ACCOUNT_CONTRACT_ADDRESS.clone() {
+        Address::Contract(contract) => contract,
+        Address::User(_) => unreachable!(),
+    };
+
+    let pubkey = Bytes::from("pubkey");
+    let sender = Address::User(UserAddress::from_pubkey_bytes(pubkey.clone()).unwrap());
+
+    let db = create_empty_memdb();
+    let mut executor = mock_executor(&chain_id, &Hash::from_empty(), &db, 1, &coinbase);
+    executor
+        .create_genesis(&mock_genesis(&sender, &asset_id, &Balance::from(10_000u64)))
+        .unwrap();
+
+    let approve = mock_signed_tx(
+        &chain_id,
+        &pubkey,
+        &asset_id,
+        "approve",
+        TransactionAction::Call {
+            contract:       account_contract.clone(),
+            method:         "approve".to_owned(),
+            args:           vec![
+                spender.as_bytes(),
+                asset_id.as_bytes(),
+                Bytes::from(Balance::from(500u64).to_bytes_be()),
+            ],
+            carrying_asset: None,
+        },
+    );
+    let resp = executor.exec(vec![approve]).unwrap();
+    match resp.receipts[0].result {
+        ReceiptResult::Call { .. } => {}
+        _ => panic!("the approve should succeed"),
+    }
+
+    let transfer_from_args = |amount: u64| {
+        vec![
+            sender.as_bytes(),
+            receiver.as_bytes(),
+            asset_id.as_bytes(),
+            Bytes::from(Balance::from(amount).to_bytes_be()),
+        ]
+    };
+
+    // The approved contract spends within the allowance.
+    let resp = executor
+        .read_call(
+            &Address::Contract(spender.clone()),
+            &account_contract,
+            "transfer_from",
+            transfer_from_args(300),
+        )
+        .unwrap();
+    match resp.result {
+        ReceiptResult::Call { .. } => {}
+        _ => panic!("the transfer_from should succeed"),
+    }
+
+    // Beyond the allowance.
+    let resp = executor
+        .read_call(
+            &Address::Contract(spender),
+            &account_contract,
+            "transfer_from",
+            transfer_from_args(600),
+        )
+        .unwrap();
+    match resp.result {
+        ReceiptResult::Fail { .. } => {}
+        _ => panic!("the transfer_from beyond the allowance should fail"),
+    }
+
+    // A user can not spend the allowance.
+    let resp = executor
+        .read_call(
+            &sender,
+            &account_contract,
+            "transfer_from",
+            transfer_from_args(300),
+        )
+        .unwrap();
+    match resp.result {
+        ReceiptResult::Fail { .. } => {}
+        _ => panic!("the transfer_from by a user should fail"),
+    }
+}
+
+#[test]
+fn test_exec_contract_call_transfer_from() {
+    let chain_id = Hash::from_empty();
+    let asset_id = system_token_id(&chain_id);
+    let coinbase = Address::from_hex("100000000000000000000000000000000000000009").unwrap();
+    let receiver = Address::from_hex("100000000000000000000000000000000000000001").unwrap();
+    let account_contract = match ACCOUNT_CONTRACT_ADDRESS.clone() {
+        Address::Contract(contract) => contract,
+        Address::User(_) => unreachable!(),
+    };
+
+    let pubkey = Bytes::from("pubkey");
+    let sender = Address::User(UserAddress::from_pubkey_bytes(pubkey.clone()).unwrap());
+
+    let code = mock_contract_call_code();
+    let spender = ContractAddress::from_code(code.clone(), 0, ContractType::App).unwrap();
+    let mut genesis = mock_genesis(&sender, &asset_id, &Balance::from(10_000u64));
+    genesis.contracts.push(GenesisContract {
+        code:    hex::encode(code),
+        library: false,
+    });
+
+    let db = create_empty_memdb();
+    let mut executor = mock_executor(&chain_id, &Hash::from_empty(), &db, 1, &coinbase);
+    executor.create_genesis(&genesis).unwrap();
+
+    let approve = mock_signed_tx(
+        &chain_id,
+        &pubkey,
+        &asset_id,
+        "approve",
+        TransactionAction::Call {
+            contract:       account_contract.clone(),
+            method:         "approve".to_owned(),
+            args:           vec![
+                spender.as_bytes(),
+                asset_id.as_bytes(),
+                Bytes::from(Balance::from(500u64).to_bytes_be()),
+            ],
+            carrying_asset: None,
+        },
+    );
+
+    // The contract spends the allowance with itself as the caller.
+    let pay = |nonce: &str, amount: u64| {
+        let args = [
+            sender.as_hex(),
+            receiver.as_hex(),
+            asset_id.as_hex(),
+            hex::encode(Balance::from(amount).to_bytes_be()),
+        ]
+        .join(",");
+
+        mock_signed_tx(
+            &chain_id,
+            &pubkey,
+            &asset_id,
+            nonce,
+            TransactionAction::Call {
+                contract:       spender.clone(),
+                method:         "pay".to_owned(),
+                args:           vec![
+                    Bytes::from(account_contract.as_hex()),
+                    Bytes::from("transfer_from"),
+                    Bytes::from(args),
+                ],
+                carrying_asset: None,
+            },
+        )
+    };
+
+    let resp = executor
+        .exec(vec![approve, pay("pay", 300), pay("pay_beyond", 300)])
+        .unwrap();
+    match resp.receipts[0].result {
+        ReceiptResult::Call { .. } => {}
+        _ => panic!("the approve should succeed"),
+    }
+    match resp.receipts[1].result {
+        ReceiptResult::Call { .. } => {}
+        _ => panic!("the contract should spend within the allowance"),
+    }
+    match resp.receipts[2].result {
+        ReceiptResult::Fail { .. } => {}
+        _ => panic!("the contract should not spend beyond the allowance"),
+    }
+
+    let fee: u64 = resp.receipts.iter().map(|r| r.cycles_used.cycle).sum();
+    assert_eq!(
+        executor.get_balance(&receiver, &asset_id).unwrap(),
+        Balance::from(300u64)
+    );
+    assert_eq!(
+        executor.get_balance(&sender, &asset_id).unwrap(),
+        Balance::from(10_000u64 - 300 - fee)
+    );
+}
+
+#[test]
+fn test_account_proof() {
+    let chain_id = Hash::from_empty();
+    let asset_id = system_token_id(&chain_id);
+    let coinbase = Address::from_hex("100000000000000000000000000000000000000009").unwrap();
+    let stranger = Address::from_hex("100000000000000000000000000000000000000001").unwrap();
+
+    let pubkey = Bytes::from("pubkey");
+    let sender = Address::User(UserAddress::from_pubkey_bytes(pubkey).unwrap());
+
+    let db = create_empty_memdb();
+    let mut executor = mock_executor(&chain_id, &Hash::from_empty(), &db, 1, &coinbase);
+    let state_root = executor
+        .create_genesis(&mock_genesis(&sender, &asset_id, &Balance::from(10_000u64)))
+        .unwrap();
+
+    let executor = mock_executor(&chain_id, &state_root, &db, 1, &coinbase);
+    let proof = executor.get_account_proof(&sender).unwrap();
+    let account = proof
+        .verify_account(&state_root, &ACCOUNT_CONTRACT_ADDRESS, &sender)
+        .unwrap();
+    match account {
+        Some(Account::User(user)) => {
+            assert_eq!(user.assets[&asset_id].balance, Balance::from(10_000u64));
+        }
+        _ => panic!("the account should be proved"),
+    }
+
+    // The proof of an absent account.
+    let proof = executor.get_account_proof(&stranger).unwrap();
+    let account = proof
+        .verify_account(&state_root, &ACCOUNT_CONTRACT_ADDRESS, &stranger)
+        .unwrap();
+    assert_eq!(account.is_none(), true);
+
+    // The proof can not be verified with another state root.
+    let proof = executor.get_account_proof(&sender).unwrap();
+    assert_eq!(
+        proof
+            .verify_account(&Hash::from_empty(), &ACCOUNT_CONTRACT_ADDRESS, &sender)
+            .is_err(),
+        true
+    );
+
+    // The proof of the system token asset.
+    let proof = executor.get_asset_proof(&asset_id).unwrap();
+    let asset = proof
+        .verify_asset(&state_root, &BANK_CONTRACT_ADDRESS, &asset_id)
+        .unwrap();
+    match asset {
+        Some(asset) => {
+            assert_eq!(asset.id, asset_id);
+            assert_eq!(asset.supply, Balance::from(10_000u64));
+        }
+        None => panic!("the asset should be proved"),
+    }
+}
+
+#[test]
+fn test_exec_receipt_state_roots() {
+    let chain_id = Hash::from_empty();
+    let asset_id = system_token_id(&chain_id);
+    let coinbase = Address::from_hex("100000000000000000000000000000000000000009").unwrap();
+    let receiver = UserAddress::from_hex("100000000000000000000000000000000000000001").unwrap();
+
+    let pubkey = Bytes::from("pubkey");
+    let sender = Address::User(UserAddress::from_pubkey_bytes(pubkey.clone()).unwrap());
+
+    let db = create_empty_memdb();
+    let mut executor = mock_executor(&chain_id, &Hash::from_empty(), &db, 1, &coinbase);
+    let state_root = executor
+        .create_genesis(&mock_genesis(&sender, &asset_id, &Balance::from(10_000u64)))
+        .unwrap();
+
+    let transfer = |nonce: &str| {
+        mock_signed_tx(
+            &chain_id,
+            &pubkey,
+            &asset_id,
+            nonce,
+            TransactionAction::Transfer {
+                receiver:       receiver.clone(),
+                carrying_asset: CarryingAsset {
+                    asset_id: asset_id.clone(),
+                    amount:   Balance::from(100u64),
+                },
+            },
+        )
+    };
+
+    let mut executor = mock_executor(&chain_id, &state_root, &db, 1, &coinbase);
+    let resp = executor
+        .exec(vec![transfer("first"), transfer("second")])
+        .unwrap();
+    let first_root = &resp.receipts[0].state_root;
+    let second_root = &resp.receipts[1].state_root;
+    assert_ne!(first_root, second_root);
+    // The reward of coinbase is committed after the last transaction.
+    assert_ne!(second_root, &resp.state_root);
+
+    // Each receipt is proven by the state after its transaction.
+    let receiver = Address::User(receiver);
+    for &(state_root, amount) in [(first_root, 100u64), (second_root, 200)].iter() {
+        let executor = mock_executor(&chain_id, state_root, &db, 1, &coinbase);
+        let proof = executor.get_account_proof(&receiver).unwrap();
+        match proof
+            .verify_account(state_root, &ACCOUNT_CONTRACT_ADDRESS, &receiver)
+            .unwrap()
+        {
+            Some(Account::User(user)) => {
+                assert_eq!(user.assets[&asset_id].balance, Balance::from(amount));
+            }
+            _ => panic!("the receiver should be proved"),
+        }
+    }
+}
+
+#[test]
+fn test_snapshot() {
+    let chain_id = Hash::from_empty();
+    let asset_id = system_token_id(&chain_id);
+    let coinbase = Address::from_hex("100000000000000000000000000000000000000009").unwrap();
+
+    let pubkey = Bytes::from("pubkey");
+    let sender = Address::User(UserAddress::from_pubkey_bytes(pubkey).unwrap());
+
+    let db = create_empty_memdb();
+    let mut executor = mock_executor(&chain_id, &Hash::from_empty(), &db, 1, &coinbase);
+    let state_root = executor
+        .create_genesis(&mock_genesis(&sender, &asset_id, &Balance::from(10_000u64)))
+        .unwrap();
+
+    let mut snapshot = vec![];
+    export_snapshot(Arc::clone(&db), state_root.clone(), &mut snapshot).unwrap();
+
+    let new_db = create_empty_memdb();
+    let imported_root = import_snapshot(Arc::clone(&new_db), snapshot.as_slice()).unwrap();
+    assert_eq!(imported_root, state_root);
+
+    let executor = mock_executor(&chain_id, &imported_root, &new_db, 1, &coinbase);
+    assert_eq!(
+        executor.get_balance(&sender, &asset_id).unwrap(),
+        Balance::from(10_000u64)
+    );
+
+    // A corrupted snapshot is rejected.
+    let last = snapshot.len() - 1;
+    snapshot[last] ^= 0xff;
+    assert_eq!(
+        import_snapshot(create_empty_memdb(), snapshot.as_slice()).is_err(),
+        true
+    );
+}
+
+#[test]
+fn test_state_sync() {
+    let chain_id = Hash::from_empty();
+    let asset_id = system_token_id(&chain_id);
+    let coinbase = Address::from_hex("100000000000000000000000000000000000000009").unwrap();
+
+    let pubkey = Bytes::from("pubkey");
+    let sender = Address::User(UserAddress::from_pubkey_bytes(pubkey).unwrap());
+
+    let db = create_empty_memdb();
+    let mut executor = mock_executor(&chain_id, &Hash::from_empty(), &db, 1, &coinbase);
+    let state_root = executor
+        .create_genesis(&mock_genesis(&sender, &asset_id, &Balance::from(10_000u64)))
+        .unwrap();
+    let validator_set = executor.get_validator_set().unwrap();
+
+    let new_db = create_empty_memdb();
+    let mut state_sync = StateSync::new(Arc::clone(&new_db), state_root.clone());
+    loop {
+        let hashes = state_sync.missing_nodes(4).unwrap();
+        if hashes.is_empty() {
+            break;
+        }
+
+        // The peer returns a part of the nodes, the others are pulled again.
+        let nodes = get_trie_nodes(db.as_ref(), &hashes[..1]).unwrap();
+        state_sync.save_nodes(nodes).unwrap();
+        state_sync.retry_pending();
+    }
+    assert!(state_sync.saved_nodes() > 0);
+    assert_eq!(state_sync.finish().unwrap(), state_root);
+
+    let executor = mock_executor(&chain_id, &state_root, &new_db, 1, &coinbase);
+    assert_eq!(
+        executor.get_balance(&sender, &asset_id).unwrap(),
+        Balance::from(10_000u64)
+    );
+    assert_eq!(executor.get_validator_set().unwrap(), validator_set);
+
+    // A bad node of a batch is skipped, the others are saved and only the
+    // missing nodes are pulled again.
+    let mut state_sync = StateSync::new(create_empty_memdb(), state_root.clone());
+    let hashes = state_sync.missing_nodes(4).unwrap();
+    let nodes = get_trie_nodes(db.as_ref(), &hashes).unwrap();
+    assert_eq!(state_sync.save_nodes(nodes).unwrap(), 0);
+
+    let hashes = state_sync.missing_nodes(4).unwrap();
+    assert!(hashes.len() > 1);
+    let mut nodes = get_trie_nodes(db.as_ref(), &hashes[..1]).unwrap();
+    nodes.push(Bytes::from("not a node"));
+    assert_eq!(state_sync.save_nodes(nodes).unwrap(), 1);
+    assert_eq!(state_sync.saved_nodes(), 2);
+
+    state_sync.retry_pending();
+    let missing = state_sync.missing_nodes(100).unwrap();
+    assert!(!missing.contains(&hashes[0]));
+    assert!(hashes[1..].iter().all(|hash| missing.contains(hash)));
+    assert!(state_sync.finish().is_err());
+}
+
+#[test]
+fn test_exec_multisig() {
+    let chain_id = Hash::from_empty();
+    let asset_id = system_token_id(&chain_id);
+    let coinbase = Address::from_hex("100000000000000000000000000000000000000009").unwrap();
+    let receiver = UserAddress::from_hex("100000000000000000000000000000000000000001").unwrap();
+    let multisig_contract = match MULTISIG_CONTRACT_ADDRESS.clone() {
+        Address::Contract(contract) => contract,
+        Address::User(_) => unreachable!(),
+    };
+
+    let (alice_key, alice) = mock_multisig_signer(1);
+    let (bob_key, bob) = mock_multisig_signer(2);
+    let alice_pubkey = alice_key.pub_key().to_bytes();
+    let bob_pubkey = bob_key.pub_key().to_bytes();
+
+    let db = create_empty_memdb();
+    let mut executor = mock_executor(&chain_id, &Hash::from_empty(), &db, 1, &coinbase);
+    executor
+        .create_genesis(&mock_genesis(
+            &Address::User(alice.clone()),
+            &asset_id,
+            &Balance::from(100_000u64),
+        ))
+        .unwrap();
+
+    let call = |method: &str, args: Vec<Bytes>| TransactionAction::Call {
+        contract: multisig_contract.clone(),
+        method: method.to_owned(),
+        args,
+        carrying_asset: None,
+    };
+    let transfer = |receiver: &UserAddress, amount: u64| TransactionAction::Transfer {
+        receiver:       receiver.clone(),
+        carrying_asset: CarryingAsset {
+            asset_id: asset_id.clone(),
+            amount:   Balance::from(amount),
+        },
+    };
+
+    // Create a 2-of-2 wallet.
+    let owners = vec![
+        MultisigOwner {
+            address: alice.clone(),
+            weight:  1,
+        },
+        MultisigOwner {
+            address: bob.clone(),
+            weight:  1,
+        },
+    ];
+    let mut args = vec![Bytes::from(vec![2u8])];
+    for owner in owners.iter() {
+        args.push(owner.encode_fixed().unwrap());
+    }
+    let mut create = mock_signed_tx(
+        &chain_id,
+        &alice_pubkey,
+        &asset_id,
+        "create",
+        call("create", args),
+    );
+    create.raw.fee.cycle = 10_000;
+
+    let resp = executor.exec(vec![create]).unwrap();
+    let wallet = match &resp.receipts[0].result {
+        ReceiptResult::Call { return_value, .. } => {
+            UserAddress::from_bytes(return_value.clone()).unwrap()
+        }
+        _ => panic!("the wallet should be created"),
+    };
+
+    // Execute a transaction signed by both of the owners.
+    let wallet_tx = RawTransaction {
+        chain_id: chain_id.clone(),
+        nonce:    Hash::digest(Bytes::from("wallet_tx")),
+        timeout:  10,
+        fee:      Fee {
+            asset_id: asset_id.clone(),
+            cycle:    0,
+        },
+        action:   transfer(&receiver, 100),
+    };
+    let tx_hash = Hash::digest(wallet_tx.encode_fixed().unwrap());
+    let args = vec![
+        wallet.as_bytes(),
+        wallet_tx.encode_fixed().unwrap(),
+        multisig_sign(&alice_key, &tx_hash),
+        multisig_sign(&bob_key, &tx_hash),
+    ];
+
+    let fund_wallet = mock_signed_tx(
+        &chain_id,
+        &alice_pubkey,
+        &asset_id,
+        "fund_wallet",
+        transfer(&wallet, 1000),
+    );
+    let fund_bob = mock_signed_tx(
+        &chain_id,
+        &alice_pubkey,
+        &asset_id,
+        "fund_bob",
+        transfer(&bob, 1000),
+    );
+    let execute = mock_signed_tx(
+        &chain_id,
+        &alice_pubkey,
+        &asset_id,
+        "execute",
+        call("execute", args.clone()),
+    );
+    let replay = mock_signed_tx(
+        &chain_id,
+        &alice_pubkey,
+        &asset_id,
+        "replay",
+        call("execute", args.clone()),
+    );
+    let below_threshold = mock_signed_tx(
+        &chain_id,
+        &alice_pubkey,
+        &asset_id,
+        "below_threshold",
+        call("execute", args[..3].to_vec()),
+    );
+
+    let resp = executor
+        .exec(vec![
+            fund_wallet,
+            fund_bob,
+            execute,
+            replay,
+            below_threshold,
+        ])
+        .unwrap();
+    match &resp.receipts[2].result {
+        ReceiptResult::Call { .. } => {}
+        _ => panic!("the wallet transaction should be executed"),
+    }
+    let logs = &resp.receipts[2].logs;
+    assert_eq!(logs.last().unwrap().topics, vec![
+        MULTISIG_EXECUTE_LOG_TOPIC.clone(),
+        tx_hash.clone()
+    ]);
+    match &resp.receipts[3].result {
+        ReceiptResult::Fail { .. } => {}
+        _ => panic!("the wallet transaction should not be replayed"),
+    }
+    match &resp.receipts[4].result {
+        ReceiptResult::Fail { .. } => {}
+        _ => panic!("the wallet transaction should be signed by enough owners"),
+    }
+    assert_eq!(
+        executor
+            .get_balance(&Address::User(wallet.clone()), &asset_id)
+            .unwrap(),
+        Balance::from(900u64)
+    );
+    assert_eq!(
+        executor
+            .get_balance(&Address::User(receiver.clone()), &asset_id)
+            .unwrap(),
+        Balance::from(100u64)
+    );
+
+    // Propose a transaction and approve it on chain.
+    let wallet_tx = RawTransaction {
+        nonce: Hash::digest(Bytes::from("proposed_wallet_tx")),
+        action: transfer(&receiver, 200),
+        ..wallet_tx
+    };
+    let proposal_id = Hash::digest(wallet_tx.encode_fixed().unwrap());
+
+    let propose = mock_signed_tx(
+        &chain_id,
+        &alice_pubkey,
+        &asset_id,
+        "propose",
+        call("propose", vec![
+            wallet.as_bytes(),
+            wallet_tx.encode_fixed().unwrap(),
+        ]),
+    );
+    let resp = executor.exec(vec![propose]).unwrap();
+    match &resp.receipts[0].result {
+        ReceiptResult::Call { return_value, .. } => {
+            assert_eq!(return_value, &proposal_id.as_bytes())
+        }
+        _ => panic!("the wallet transaction should be proposed"),
+    }
+    assert_eq!(
+        executor
+            .get_balance(&Address::User(receiver.clone()), &asset_id)
+            .unwrap(),
+        Balance::from(100u64)
+    );
+
+    let approve = mock_signed_tx(
+        &chain_id,
+        &bob_pubkey,
+        &asset_id,
+        "approve",
+        call("approve", vec![proposal_id.as_bytes()]),
+    );
+    let resp = executor.exec(vec![approve]).unwrap();
+    match &resp.receipts[0].result {
+        ReceiptResult::Call { .. } => {}
+        _ => panic!("the proposal should be approved"),
+    }
+    assert_eq!(
+        executor
+            .get_balance(&Address::User(receiver), &asset_id)
+            .unwrap(),
+        Balance::from(300u64)
+    );
+}
+
+#[test]
+fn test_exec_mint_and_burn() {
+    let chain_id = Hash::from_empty();
+    let asset_id = system_token_id(&chain_id);
+    let coinbase = Address::from_hex("100000000000000000000000000000000000000009").unwrap();
+    let receiver = Address::from_hex("100000000000000000000000000000000000000001").unwrap();
+    let bank_contract = match BANK_CONTRACT_ADDRESS.clone() {
+        Address::Contract(contract) => contract,
+        Address::User(_) => unreachable!(),
+    };
+
+    let pubkey = Bytes::from("pubkey");
+    let issuer = Address::User(UserAddress::from_pubkey_bytes(pubkey.clone()).unwrap());
+    let stranger_pubkey = Bytes::from("stranger");
+    let stranger = UserAddress::from_pubkey_bytes(stranger_pubkey.clone()).unwrap();
+
+    let db = create_empty_memdb();
+    let mut executor = mock_executor(&chain_id, &Hash::from_empty(), &db, 1, &coinbase);
+    executor
+        .create_genesis(&mock_genesis(
+            &issuer,
+            &asset_id,
+            &Balance::from(100_000u64),
+        ))
+        .unwrap();
+
+    let mut deploy = mock_signed_tx(
+        &chain_id,
+        &pubkey,
+        &asset_id,
+        "deploy",
+        TransactionAction::Deploy {
+            code:          Bytes::from("stable coin"),
+            contract_type: ContractType::Asset,
+            asset_params:  Some(AssetParams {
+                name:     "Stable coin".to_owned(),
+                symbol:   "SC".to_owned(),
+                supply:   Balance::from(1000u64),
+                decimals: 8,
+            }),
+        },
+    );
+    deploy.raw.fee.cycle = 10_000;
+
+    let resp = executor.exec(vec![deploy]).unwrap();
+    let coin_id = match &resp.receipts[0].result {
+        ReceiptResult::Deploy { contract, .. } => Hash::digest(Bytes::from(
+            [chain_id.as_bytes(), contract.as_bytes()].concat(),
+        )),
+        _ => panic!("the asset should be deployed"),
+    };
+
+    let call = |method: &str, args: Vec<Bytes>| TransactionAction::Call {
+        contract: bank_contract.clone(),
+        method: method.to_owned(),
+        args,
+        carrying_asset: None,
+    };
+    let amount = |amount: u64| Bytes::from(Balance::from(amount).to_bytes_be());
+
+    let fund_stranger = mock_signed_tx(
+        &chain_id,
+        &pubkey,
+        &asset_id,
+        "fund_stranger",
+        TransactionAction::Transfer {
+            receiver:       stranger,
+            carrying_asset: CarryingAsset {
+                asset_id: asset_id.clone(),
+                amount:   Balance::from(10_000u64),
+            },
+        },
+    );
+    let mint = mock_signed_tx(
+        &chain_id,
+        &pubkey,
+        &asset_id,
+        "mint",
+        call("mint", vec![
+            coin_id.as_bytes(),
+            receiver.as_bytes(),
+            amount(500),
+        ]),
+    );
+    let burn = mock_signed_tx(
+        &chain_id,
+        &pubkey,
+        &asset_id,
+        "burn",
+        call("burn", vec![coin_id.as_bytes(), amount(200)]),
+    );
+    let stranger_mint = mock_signed_tx(
+        &chain_id,
+        &stranger_pubkey,
+        &asset_id,
+        "stranger_mint",
+        call("mint", vec![
+            coin_id.as_bytes(),
+            receiver.as_bytes(),
+            amount(500),
+        ]),
+    );
+    let burn_too_much = mock_signed_tx(
+        &chain_id,
+        &pubkey,
+        &asset_id,
+        "burn_too_much",
+        call("burn", vec![coin_id.as_bytes(), amount(1000)]),
+    );
+
+    let resp = executor
+        .exec(vec![
+            fund_stranger,
+            mint,
+            burn,
+            stranger_mint,
+            burn_too_much,
+        ])
+        .unwrap();
+    match &resp.receipts[1].result {
+        ReceiptResult::Call { .. } => {}
+        _ => panic!("the issuer should mint the asset"),
+    }
+    assert_eq!(resp.receipts[1].logs[0].topics, vec![
+        MINT_LOG_TOPIC.clone(),
+        coin_id.clone()
+    ]);
+    match &resp.receipts[2].result {
+        ReceiptResult::Call { .. } => {}
+        _ => panic!("the issuer should burn the asset"),
+    }
+    match &resp.receipts[3].result {
+        ReceiptResult::Fail { .. } => {}
+        _ => panic!("only the issuer can mint the asset"),
+    }
+    assert_eq!(resp.receipts[3].cycles_used.cycle > 0, true);
+    match &resp.receipts[4].result {
+        ReceiptResult::Fail { .. } => {}
+        _ => panic!("the burned amount can not exceed the balance"),
+    }
+
+    // The supply equals to the total balances.
+    let issuer_balance = executor.get_balance(&issuer, &coin_id).unwrap();
+    let receiver_balance = executor.get_balance(&receiver, &coin_id).unwrap();
+    assert_eq!(issuer_balance, Balance::from(800u64));
+    assert_eq!(receiver_balance, Balance::from(500u64));
+
+    let resp = executor
+        .read_call(&issuer, &bank_contract, "get_asset", vec![
+            coin_id.as_bytes()
+        ])
+        .unwrap();
+    let asset = match resp.result {
+        ReceiptResult::Call { return_value, .. } => Asset::decode_fixed(return_value).unwrap(),
+        _ => panic!("the asset should be found"),
+    };
+    assert_eq!(asset.supply, issuer_balance + receiver_balance);
+    assert_eq!(asset.issuer, issuer);
+}
+
+#[test]
+fn test_exec_batch_transfer() {
+    let chain_id = Hash::from_empty();
+    let asset_id = system_token_id(&chain_id);
+    let coinbase = Address::from_hex("100000000000000000000000000000000000000009").unwrap();
+    let alice = UserAddress::from_hex("100000000000000000000000000000000000000001").unwrap();
+    let bob = UserAddress::from_hex("100000000000000000000000000000000000000002").unwrap();
+
+    let pubkey = Bytes::from("pubkey");
+    let sender = Address::User(UserAddress::from_pubkey_bytes(pubkey.clone()).unwrap());
+
+    let db = create_empty_memdb();
+    let mut executor = mock_executor(&chain_id, &Hash::from_empty(), &db, 1, &coinbase);
+    executor
+        .create_genesis(&mock_genesis(
+            &sender,
+            &asset_id,
+            &Balance::from(100_000u64),
+        ))
+        .unwrap();
+
+    let mut deploy = mock_signed_tx(
+        &chain_id,
+        &pubkey,
+        &asset_id,
+        "deploy",
+        TransactionAction::Deploy {
+            code:          Bytes::from("stable coin"),
+            contract_type: ContractType::Asset,
+            asset_params:  Some(AssetParams {
+                name:     "Stable coin".to_owned(),
+                symbol:   "SC".to_owned(),
+                supply:   Balance::from(1000u64),
+                decimals: 8,
+            }),
+        },
+    );
+    deploy.raw.fee.cycle = 10_000;
+
+    let resp = executor.exec(vec![deploy]).unwrap();
+    let coin_id = match &resp.receipts[0].result {
+        ReceiptResult::Deploy { contract, .. } => Hash::digest(Bytes::from(
+            [chain_id.as_bytes(), contract.as_bytes()].concat(),
+        )),
+        _ => panic!("the asset should be deployed"),
+    };
+
+    let output = |receiver: &UserAddress, asset_id: &AssetID, amount: u64| TransferOutput {
+        receiver:       receiver.clone(),
+        carrying_asset: CarryingAsset {
+            asset_id: asset_id.clone(),
+            amount:   Balance::from(amount),
+        },
+    };
+    let batch_transfer = mock_signed_tx(
+        &chain_id,
+        &pubkey,
+        &asset_id,
+        "batch_transfer",
+        TransactionAction::BatchTransfer {
+            outputs: vec![
+                output(&alice, &asset_id, 100),
+                output(&bob, &asset_id, 200),
+                output(&alice, &coin_id, 300),
+            ],
+        },
+    );
+    // The second output overdraws, so the first one is reverted too.
+    let overdraw = mock_signed_tx(
+        &chain_id,
+        &pubkey,
+        &asset_id,
+        "overdraw",
+        TransactionAction::BatchTransfer {
+            outputs: vec![output(&bob, &asset_id, 10), output(&bob, &coin_id, 5000)],
+        },
+    );
+    let empty = mock_signed_tx(
+        &chain_id,
+        &pubkey,
+        &asset_id,
+        "empty",
+        TransactionAction::BatchTransfer { outputs: vec![] },
+    );
+
+    let resp = executor
+        .exec(vec![batch_transfer, overdraw, empty])
+        .unwrap();
+    let outputs = match &resp.receipts[0].result {
+        ReceiptResult::BatchTransfer { outputs } => outputs.clone(),
+        _ => panic!("the batch transfer should succeed"),
+    };
+    assert_eq!(outputs.len(), 3);
+    assert_eq!(outputs[0].receiver, alice);
+    assert_eq!(
+        outputs[0].before_amount.clone() - outputs[0].after_amount.clone(),
+        Balance::from(100u64)
+    );
+    assert_eq!(outputs[1].before_amount, outputs[0].after_amount);
+    assert_eq!(outputs[2].asset_id, coin_id);
+    assert_eq!(outputs[2].before_amount, Balance::from(1000u64));
+    assert_eq!(outputs[2].after_amount, Balance::from(700u64));
+    assert_eq!(resp.receipts[0].logs.len(), 3);
+    assert_eq!(resp.receipts[0].logs[0].topics, vec![
+        TRANSFER_LOG_TOPIC.clone(),
+        asset_id.clone()
+    ]);
+
+    match &resp.receipts[1].result {
+        ReceiptResult::Fail { .. } => {}
+        _ => panic!("the overdrawn batch transfer should fail"),
+    }
+    assert_eq!(resp.receipts[1].logs.is_empty(), true);
+    match &resp.receipts[2].result {
+        ReceiptResult::Fail { .. } => {}
+        _ => panic!("the batch transfer without outputs should fail"),
+    }
+
+    let alice = Address::User(alice);
+    let bob = Address::User(bob);
+    assert_eq!(
+        executor.get_balance(&alice, &asset_id).unwrap(),
+        Balance::from(100u64)
+    );
+    assert_eq!(
+        executor.get_balance(&alice, &coin_id).unwrap(),
+        Balance::from(300u64)
+    );
+    assert_eq!(
+        executor.get_balance(&bob, &asset_id).unwrap(),
+        Balance::from(200u64)
+    );
+    assert_eq!(
+        executor.get_balance(&sender, &coin_id).unwrap(),
+        Balance::from(700u64)
+    );
+}
+
+#[test]
+fn test_exec_trace_state() {
+    let chain_id = Hash::from_empty();
+    let asset_id = system_token_id(&chain_id);
+    let coinbase = Address::from_hex("100000000000000000000000000000000000000009").unwrap();
+    let receiver = UserAddress::from_hex("100000000000000000000000000000000000000001").unwrap();
+
+    let pubkey = Bytes::from("pubkey");
+    let sender = Address::User(UserAddress::from_pubkey_bytes(pubkey.clone()).unwrap());
+
+    let db = create_empty_memdb();
+    let mut executor = mock_executor(&chain_id, &Hash::from_empty(), &db, 1, &coinbase);
+    let state_root = executor
+        .create_genesis(&mock_genesis(&sender, &asset_id, &Balance::from(10_000u64)))
+        .unwrap();
+
+    let transfer = |nonce: &str, amount: u64| {
+        mock_signed_tx(
+            &chain_id,
+            &pubkey,
+            &asset_id,
+            nonce,
+            TransactionAction::Transfer {
+                receiver:       receiver.clone(),
+                carrying_asset: CarryingAsset {
+                    asset_id: asset_id.clone(),
+                    amount:   Balance::from(amount),
+                },
+            },
+        )
+    };
+    let txs = vec![transfer("transfer", 100), transfer("overdraw", 100_000)];
+
+    // Nothing is traced by default.
+    let resp = executor.exec(txs.clone()).unwrap();
+    assert_eq!(resp.state_diffs.is_empty(), true);
+
+    let mut executor = mock_executor(&chain_id, &state_root, &db, 1, &coinbase);
+    executor.enable_state_trace();
+    let resp = executor.exec(txs).unwrap();
+    assert_eq!(resp.state_diffs.len(), 2);
+
+    let transfer_fee = resp.receipts[0].cycles_used.cycle;
+    let state_diff = &resp.state_diffs[0];
+    assert_eq!(state_diff.tx_hash, resp.receipts[0].tx_hash);
+    assert_eq!(state_diff.balances.len(), 2);
+    assert_eq!(
+        state_diff.balances.contains(&BalanceDiff {
+            address:  Address::User(receiver.clone()),
+            asset_id: asset_id.clone(),
+            before:   Balance::from(0u64),
+            after:    Balance::from(100u64),
+        }),
+        true
+    );
+    assert_eq!(
+        state_diff.balances.contains(&BalanceDiff {
+            address:  sender.clone(),
+            asset_id: asset_id.clone(),
+            before:   Balance::from(10_000u64),
+            after:    Balance::from(10_000u64 - 100 - transfer_fee),
+        }),
+        true
+    );
+
+    // Only the fee is charged by the failed transaction.
+    let overdraw_fee = resp.receipts[1].cycles_used.cycle;
+    assert_eq!(resp.state_diffs[1].balances, vec![BalanceDiff {
+        address:  sender,
+        asset_id: asset_id.clone(),
+        before:   Balance::from(10_000u64 - 100 - transfer_fee),
+        after:    Balance::from(10_000u64 - 100 - transfer_fee - overdraw_fee),
+    }]);
+
+    // The raw writes are listed as well, the account of the receiver is new.
+    let receiver_key = Address::User(receiver.clone()).encode_fixed().unwrap();
+    let storage = state_diff
+        .storages
+        .iter()
+        .find(|diff| diff.address == *ACCOUNT_CONTRACT_ADDRESS && diff.key == receiver_key)
+        .expect("the account of the receiver should be traced");
+    assert_eq!(storage.before.is_empty(), true);
+    match Account::decode_fixed(storage.after.clone()).unwrap() {
+        Account::User(user) => assert_eq!(user.assets[&asset_id].balance, Balance::from(100u64)),
+        Account::Contract(_) => panic!("the receiver should be a user"),
+    }
+}
+
+#[test]
+fn test_exec_update_validators() {
+    let chain_id = Hash::from_empty();
+    let asset_id = system_token_id(&chain_id);
+    let coinbase = Address::from_hex("100000000000000000000000000000000000000009").unwrap();
+    let governance_contract = match GOVERNANCE_CONTRACT_ADDRESS.clone() {
+        Address::Contract(contract) => contract,
+        Address::User(_) => unreachable!(),
+    };
+
+    let pubkey = Bytes::from("pubkey");
+    let validator = UserAddress::from_pubkey_bytes(pubkey.clone()).unwrap();
+    let sender = Address::User(validator.clone());
+
+    let db = create_empty_memdb();
+    let mut executor = mock_executor(&chain_id, &Hash::from_empty(), &db, 1, &coinbase);
+    executor
+        .create_genesis(&mock_genesis(&sender, &asset_id, &Balance::from(10_000u64)))
+        .unwrap();
+
+    let new_validators = vec![
+        Validator {
+            address:        validator,
+            propose_weight: 1,
+            vote_weight:    1,
+            bls_pub_key:    mock_bls_pub_key(1),
+        },
+        Validator {
+            address:        UserAddress::from_hex("100000000000000000000000000000000000000001")
+                .unwrap(),
+            propose_weight: 1,
+            vote_weight:    1,
+            bls_pub_key:    mock_bls_pub_key(2),
+        },
+    ];
+    let propose = mock_signed_tx(
+        &chain_id,
+        &pubkey,
+        &asset_id,
+        "propose",
+        TransactionAction::Call {
+            contract:       governance_contract,
+            method:         "propose_validators".to_owned(),
+            args:           new_validators
+                .iter()
+                .zip(1..)
+                .flat_map(|(v, seed)| {
+                    vec![v.encode_fixed().unwrap(), mock_bls_pop(seed, &v.address)]
+                })
+                .collect(),
+            carrying_asset: None,
+        },
+    );
+
+    // The only validator approves the proposal by itself.
+    executor.enable_state_trace();
+    let resp = executor.exec(vec![propose]).unwrap();
+    match &resp.receipts[0].result {
+        ReceiptResult::Call { .. } => {}
+        _ => panic!("the validator should propose the validators"),
+    }
+    assert_eq!(resp.validator_set.version, 1);
+    assert_eq!(resp.validator_set.validators, new_validators);
+
+    // The writes to the governance contract are traced.
+    assert_eq!(
+        resp.state_diffs[0]
+            .storages
+            .iter()
+            .any(|diff| diff.address == *GOVERNANCE_CONTRACT_ADDRESS),
+        true
+    );
+
+    // The validators are kept in the state.
+    let mut executor = mock_executor(&chain_id, &resp.state_root, &db, 2, &coinbase);
+    let resp = executor.exec(vec![]).unwrap();
+    assert_eq!(resp.validator_set.version, 1);
+    assert_eq!(resp.validator_set.validators, new_validators);
+}
+
+#[test]
+fn test_exec_update_params() {
+    let chain_id = Hash::from_empty();
+    let asset_id = system_token_id(&chain_id);
+    let coinbase = Address::from_hex("100000000000000000000000000000000000000009").unwrap();
+    let governance_contract = match GOVERNANCE_CONTRACT_ADDRESS.clone() {
+        Address::Contract(contract) => contract,
+        Address::User(_) => unreachable!(),
+    };
+
+    let pubkey = Bytes::from("pubkey");
+    let sender = Address::User(UserAddress::from_pubkey_bytes(pubkey.clone()).unwrap());
+
+    let db = create_empty_memdb();
+    let mut executor = mock_executor(&chain_id, &Hash::from_empty(), &db, 1, &coinbase);
+    let genesis = mock_genesis(&sender, &asset_id, &Balance::from(10_000u64));
+    let state_root = executor.create_genesis(&genesis).unwrap();
+
+    // The params of the genesis are kept in the state.
+    let mut executor = mock_executor(&chain_id, &state_root, &db, 1, &coinbase);
+    let params = executor.get_chain_params().unwrap();
+    assert_eq!(params.cycles_limit, genesis.params.cycles_limit);
+    assert_eq!(params.timeout_gap, genesis.params.timeout_gap);
+
+    let new_params = ChainParams {
+        timeout_gap: 40,
+        ..params
+    };
+    let propose = mock_signed_tx(
+        &chain_id,
+        &pubkey,
+        &asset_id,
+        "propose",
+        TransactionAction::Call {
+            contract:       governance_contract,
+            method:         "propose_params".to_owned(),
+            args:           vec![new_params.encode_fixed().unwrap()],
+            carrying_asset: None,
+        },
+    );
+
+    let resp = executor.exec(vec![propose]).unwrap();
+    match &resp.receipts[0].result {
+        ReceiptResult::Call { .. } => {}
+        _ => panic!("the validator should propose the params"),
+    }
+    assert_eq!(resp.params, new_params);
+
+    let executor = mock_executor(&chain_id, &resp.state_root, &db, 2, &coinbase);
+    assert_eq!(executor.get_chain_params().unwrap(), new_params);
+}
@@ -0,0 +1,228 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use bytes::Bytes;
+
+use protocol::types::{Address, AssetID, ChainParams, UserAddress, Validator};
+
+use crate::native_contract::NativeGovernanceContract;
+use crate::tests::{create_state_adapter, mock_bls_pop, mock_bls_pub_key, mock_invoke_context};
+
+// The bls key of the validator is made from the seed, return the validator
+// with the proof of possession of the key.
+fn mock_validator(seed: u8, vote_weight: u8) -> (Validator, Bytes) {
+    let address = UserAddress::from_hex(&format!(
+        "1000000000000000000000000000000000000000{:02x}",
+        seed
+    ))
+    .unwrap();
+    let pop = mock_bls_pop(seed, &address);
+    let validator = Validator {
+        address,
+        propose_weight: 1,
+        vote_weight,
+        bls_pub_key: mock_bls_pub_key(seed),
+    };
+    (validator, pop)
+}
+
+#[test]
+fn test_governance_init_validators() {
+    let state = Rc::new(RefCell::new(create_state_adapter()));
+    let mut governance = NativeGovernanceContract::new(state);
+    assert!(governance.get_validator_set().is_err());
+
+    let (alice, alice_pop) = mock_validator(1, 1);
+    let (bob, bob_pop) = mock_validator(2, 1);
+
+    assert!(governance.init_validators(vec![], vec![]).is_err());
+    assert!(governance
+        .init_validators(vec![alice.clone(), alice.clone()], vec![
+            alice_pop.clone(),
+            alice_pop.clone()
+        ])
+        .is_err());
+    let mut zero_weight = bob.clone();
+    zero_weight.vote_weight = 0;
+    assert!(governance
+        .init_validators(vec![alice.clone(), zero_weight], vec![
+            alice_pop.clone(),
+            bob_pop.clone()
+        ])
+        .is_err());
+    let mut bad_key = bob.clone();
+    bad_key.bls_pub_key = Bytes::from("bls");
+    assert!(governance
+        .init_validators(vec![alice.clone(), bad_key], vec![
+            alice_pop.clone(),
+            bob_pop.clone()
+        ])
+        .is_err());
+
+    // Every key must come with the proof of possession of it.
+    assert!(governance
+        .init_validators(vec![alice.clone(), bob.clone()], vec![alice_pop.clone()])
+        .is_err());
+    assert!(governance
+        .init_validators(vec![alice.clone(), bob.clone()], vec![
+            alice_pop.clone(),
+            alice_pop.clone()
+        ])
+        .is_err());
+    // The proof is bound to the address.
+    let mut copied_key = bob.clone();
+    copied_key.bls_pub_key = alice.bls_pub_key.clone();
+    assert!(governance
+        .init_validators(vec![alice.clone(), copied_key.clone()], vec![
+            alice_pop.clone(),
+            alice_pop.clone()
+        ])
+        .is_err());
+    // A key is registered once, even if its owner proves it for two addresses.
+    assert!(governance
+        .init_validators(vec![alice.clone(), copied_key], vec![
+            alice_pop.clone(),
+            mock_bls_pop(1, &bob.address)
+        ])
+        .is_err());
+
+    governance
+        .init_validators(vec![alice.clone(), bob.clone()], vec![alice_pop, bob_pop])
+        .unwrap();
+    let validator_set = governance.get_validator_set().unwrap();
+    assert_eq!(validator_set.version, 0);
+    assert_eq!(validator_set.validators, vec![alice, bob]);
+}
+
+#[test]
+fn test_governance_update_validators() {
+    let state = Rc::new(RefCell::new(create_state_adapter()));
+    let mut governance = NativeGovernanceContract::new(state);
+
+    let (alice, alice_pop) = mock_validator(1, 1);
+    let (bob, bob_pop) = mock_validator(2, 1);
+    let (carol, carol_pop) = mock_validator(3, 2);
+    let (dave, dave_pop) = mock_validator(4, 1);
+    governance
+        .init_validators(vec![alice.clone(), bob.clone(), carol.clone()], vec![
+            alice_pop.clone(),
+            bob_pop.clone(),
+            carol_pop,
+        ])
+        .unwrap();
+
+    let ctx_of = |validator: &Validator| {
+        mock_invoke_context(
+            Address::User(validator.address.clone()),
+            None,
+            0,
+            1_000_000,
+            AssetID::from_empty(),
+        )
+    };
+    let validators = vec![alice.clone(), bob.clone(), dave.clone()];
+    let pops = vec![alice_pop, bob_pop, dave_pop];
+
+    // Only the validators can propose.
+    assert!(governance
+        .propose_validators(ctx_of(&dave), validators.clone(), pops.clone())
+        .is_err());
+
+    let proposal = governance
+        .propose_validators(ctx_of(&alice), validators.clone(), pops.clone())
+        .unwrap();
+    assert_eq!(proposal.approvals, vec![alice.address.clone()]);
+    assert_eq!(proposal.validator_version, 0);
+    assert_eq!(proposal.executed, false);
+
+    // The same action makes another proposal.
+    let other = governance
+        .propose_validators(ctx_of(&bob), validators.clone(), pops.clone())
+        .unwrap();
+    assert_ne!(proposal.id, other.id);
+
+    assert!(governance.approve(ctx_of(&alice), &proposal.id).is_err());
+    assert!(governance.approve(ctx_of(&dave), &proposal.id).is_err());
+
+    // 2 of 4 is not more than 2/3.
+    let proposal = governance.approve(ctx_of(&bob), &proposal.id).unwrap();
+    assert_eq!(proposal.executed, false);
+    assert_eq!(governance.get_validator_set().unwrap().version, 0);
+
+    let proposal = governance.approve(ctx_of(&carol), &proposal.id).unwrap();
+    assert_eq!(proposal.executed, true);
+    assert_eq!(governance.get_proposal(&proposal.id).unwrap(), proposal);
+
+    let validator_set = governance.get_validator_set().unwrap();
+    assert_eq!(validator_set.version, 1);
+    assert_eq!(validator_set.validators, vec![alice, bob, dave.clone()]);
+
+    // Carol is not a validator anymore.
+    assert!(governance.approve(ctx_of(&carol), &other.id).is_err());
+    assert!(governance.approve(ctx_of(&dave), &proposal.id).is_err());
+
+    // The proposals made under the old validator set are outdated, even for
+    // the new validators.
+    assert!(governance.approve(ctx_of(&dave), &other.id).is_err());
+    assert_eq!(governance.get_proposal(&other.id).unwrap(), other);
+
+    let proposal = governance
+        .propose_validators(ctx_of(&dave), validators, pops)
+        .unwrap();
+    assert_eq!(proposal.validator_version, 1);
+    let proposal = governance.approve(ctx_of(&alice), &proposal.id).unwrap();
+    assert_eq!(proposal.executed, false);
+    let proposal = governance.approve(ctx_of(&bob), &proposal.id).unwrap();
+    assert_eq!(proposal.executed, true);
+    assert_eq!(governance.get_validator_set().unwrap().version, 2);
+}
+
+#[test]
+fn test_governance_update_params() {
+    let state = Rc::new(RefCell::new(create_state_adapter()));
+    let mut governance = NativeGovernanceContract::new(state);
+
+    let (alice, alice_pop) = mock_validator(1, 1);
+    let (bob, bob_pop) = mock_validator(2, 1);
+    governance
+        .init_validators(vec![alice.clone(), bob.clone()], vec![alice_pop, bob_pop])
+        .unwrap();
+
+    let params = ChainParams {
+        cycles_limit: 99_999_999,
+        cycles_price: 1,
+        interval:     3000,
+        timeout_gap:  20,
+    };
+    governance.init_params(params.clone()).unwrap();
+    assert_eq!(governance.get_params().unwrap(), params);
+
+    let ctx_of = |validator: &Validator| {
+        mock_invoke_context(
+            Address::User(validator.address.clone()),
+            None,
+            0,
+            1_000_000,
+            AssetID::from_empty(),
+        )
+    };
+
+    let mut zero_interval = params.clone();
+    zero_interval.interval = 0;
+    assert!(governance
+        .propose_params(ctx_of(&alice), zero_interval)
+        .is_err());
+
+    let mut new_params = params.clone();
+    new_params.cycles_price = 2;
+    new_params.timeout_gap = 40;
+    let proposal = governance
+        .propose_params(ctx_of(&alice), new_params.clone())
+        .unwrap();
+    assert_eq!(governance.get_params().unwrap(), params);
+
+    governance.approve(ctx_of(&bob), &proposal.id).unwrap();
+    assert_eq!(governance.get_params().unwrap(), new_params);
+    // The validators are not changed.
+    assert_eq!(governance.get_validator_set().unwrap().version, 0);
+}
@@ -1,16 +1,29 @@
 mod account_contract;
 mod bank_contract;
+mod dispatcher;
+mod executor;
 mod general_state_adapter;
+mod governance_contract;
+mod multisig_contract;
 mod trie;
+mod vm;
 
 use std::cell::RefCell;
+use std::convert::TryFrom;
 use std::rc::Rc;
 use std::sync::Arc;
 
+use bytes::Bytes;
+use common_crypto::{
+    BLS12381PrivateKey, Crypto, PrivateKey, PublicKey, Secp256k1, Secp256k1PrivateKey, Signature,
+    BLS12381,
+};
+
 use protocol::traits::executor::{InvokeContext, RcInvokeContext};
-use protocol::types::{Address, AssetID, CarryingAsset, Hash, MerkleRoot};
+use protocol::types::{Address, AssetID, CarryingAsset, Hash, MerkleRoot, UserAddress};
 
 use crate::adapter::GeneralContractStateAdapter;
+use crate::native_contract::bls_pop_hash;
 use crate::trie::MPTTrie;
 
 type MemTrie = MPTTrie<cita_trie::MemoryDB>;
@@ -50,7 +63,38 @@ fn mock_invoke_context(
         cycles_used,
         cycles_limit,
         carrying_asset,
+        logs: vec![],
     };
 
     Rc::new(RefCell::new(ictx))
 }
+
+fn mock_multisig_signer(seed: u8) -> (Secp256k1PrivateKey, UserAddress) {
+    let privkey = Secp256k1PrivateKey::try_from([seed; 32].as_ref()).unwrap();
+    let address = UserAddress::from_pubkey_bytes(privkey.pub_key().to_bytes()).unwrap();
+    (privkey, address)
+}
+
+fn mock_bls_pub_key(seed: u8) -> Bytes {
+    let privkey = BLS12381PrivateKey::try_from([seed; 32].as_ref()).unwrap();
+    privkey.pub_key().to_bytes()
+}
+
+fn mock_bls_pop(seed: u8, address: &UserAddress) -> Bytes {
+    let privkey = BLS12381PrivateKey::try_from([seed; 32].as_ref()).unwrap();
+    let hash = bls_pop_hash(address, &privkey.pub_key().to_bytes());
+    BLS12381::sign_message(&hash.as_bytes(), &privkey.to_bytes())
+        .unwrap()
+        .to_bytes()
+}
+
+// The signature of a multisig wallet transaction is `pubkey ++ signature`.
+fn multisig_sign(privkey: &Secp256k1PrivateKey, hash: &Hash) -> Bytes {
+    let signature = Secp256k1::sign_message(&hash.as_bytes(), &privkey.to_bytes())
+        .unwrap()
+        .to_bytes();
+
+    let mut res = privkey.pub_key().to_bytes();
+    res.extend_from_slice(&signature);
+    res
+}
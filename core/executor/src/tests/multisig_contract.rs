@@ -0,0 +1,185 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use bytes::Bytes;
+
+use protocol::fixed_codec::ProtocolFixedCodec;
+use protocol::types::{
+    Address, AssetID, Balance, CarryingAsset, Fee, Hash, MultisigOwner, RawTransaction,
+    TransactionAction, UserAddress,
+};
+
+use crate::native_contract::NativeMultisigContract;
+use crate::tests::{
+    create_state_adapter, mock_invoke_context, mock_multisig_signer, multisig_sign,
+};
+
+fn mock_wallet_tx(receiver: &UserAddress) -> RawTransaction {
+    RawTransaction {
+        chain_id: Hash::from_empty(),
+        nonce:    Hash::digest(Bytes::from("nonce")),
+        timeout:  10,
+        fee:      Fee {
+            asset_id: AssetID::from_empty(),
+            cycle:    0,
+        },
+        action:   TransactionAction::Transfer {
+            receiver:       receiver.clone(),
+            carrying_asset: CarryingAsset {
+                asset_id: AssetID::from_empty(),
+                amount:   Balance::from(100u64),
+            },
+        },
+    }
+}
+
+#[test]
+fn test_multisig_create() {
+    let (_, alice) = mock_multisig_signer(1);
+    let (_, bob) = mock_multisig_signer(2);
+    let caller = Address::User(alice.clone());
+    let state = Rc::new(RefCell::new(create_state_adapter()));
+    let mut multisig = NativeMultisigContract::new(state);
+
+    let ctx = mock_invoke_context(caller, None, 0, 1_000_000, AssetID::from_empty());
+    let owners = vec![
+        MultisigOwner {
+            address: alice.clone(),
+            weight:  1,
+        },
+        MultisigOwner {
+            address: bob.clone(),
+            weight:  2,
+        },
+    ];
+
+    let wallet = multisig.create(Rc::clone(&ctx), owners.clone(), 3).unwrap();
+    assert_eq!(wallet.owners, owners);
+    assert_eq!(multisig.get_wallet(&wallet.address).unwrap(), wallet);
+
+    // The same owners make a different wallet.
+    let wallet2 = multisig.create(Rc::clone(&ctx), owners.clone(), 2).unwrap();
+    assert_ne!(wallet.address, wallet2.address);
+
+    // The threshold can not be reached.
+    assert!(multisig.create(Rc::clone(&ctx), owners.clone(), 4).is_err());
+    assert!(multisig.create(Rc::clone(&ctx), owners.clone(), 0).is_err());
+    assert!(multisig.create(Rc::clone(&ctx), vec![], 1).is_err());
+
+    let duplicate_owners = vec![owners[0].clone(), owners[0].clone()];
+    assert!(multisig
+        .create(Rc::clone(&ctx), duplicate_owners, 1)
+        .is_err());
+
+    let zero_weight = vec![MultisigOwner {
+        address: bob,
+        weight:  0,
+    }];
+    assert!(multisig.create(Rc::clone(&ctx), zero_weight, 1).is_err());
+}
+
+#[test]
+fn test_multisig_verify_signatures() {
+    let (alice_key, alice) = mock_multisig_signer(1);
+    let (bob_key, bob) = mock_multisig_signer(2);
+    let (carol_key, _) = mock_multisig_signer(3);
+    let caller = Address::User(alice.clone());
+    let state = Rc::new(RefCell::new(create_state_adapter()));
+    let mut multisig = NativeMultisigContract::new(state);
+
+    let ctx = mock_invoke_context(caller, None, 0, 1_000_000, AssetID::from_empty());
+    let owners = vec![
+        MultisigOwner {
+            address: alice,
+            weight:  1,
+        },
+        MultisigOwner {
+            address: bob.clone(),
+            weight:  2,
+        },
+    ];
+    let wallet = multisig.create(Rc::clone(&ctx), owners, 2).unwrap();
+
+    let raw = mock_wallet_tx(&bob);
+    let tx_hash = Hash::digest(raw.encode_fixed().unwrap());
+    let alice_sig = multisig_sign(&alice_key, &tx_hash);
+    let bob_sig = multisig_sign(&bob_key, &tx_hash);
+    let carol_sig = multisig_sign(&carol_key, &tx_hash);
+
+    let verify = |sigs: Vec<Bytes>| {
+        multisig.verify_signatures(Rc::clone(&ctx), &wallet.address, &tx_hash, &sigs)
+    };
+
+    assert!(verify(vec![bob_sig.clone()]).is_ok());
+    assert!(verify(vec![alice_sig.clone(), bob_sig.clone()]).is_ok());
+    // Below the threshold.
+    assert!(verify(vec![alice_sig.clone()]).is_err());
+    // The same signer twice.
+    assert!(verify(vec![alice_sig.clone(), alice_sig.clone()]).is_err());
+    // Not an owner.
+    assert!(verify(vec![bob_sig.clone(), carol_sig]).is_err());
+    // Signed another transaction.
+    let other_hash = Hash::digest(Bytes::from("other"));
+    let other_sig = multisig_sign(&bob_key, &other_hash);
+    assert!(verify(vec![other_sig]).is_err());
+}
+
+#[test]
+fn test_multisig_propose_and_approve() {
+    let (_, alice) = mock_multisig_signer(1);
+    let (_, bob) = mock_multisig_signer(2);
+    let (_, carol) = mock_multisig_signer(3);
+    let state = Rc::new(RefCell::new(create_state_adapter()));
+    let mut multisig = NativeMultisigContract::new(state);
+
+    let ctx_of = |user: &UserAddress| {
+        mock_invoke_context(
+            Address::User(user.clone()),
+            None,
+            0,
+            1_000_000,
+            AssetID::from_empty(),
+        )
+    };
+    let owners = vec![
+        MultisigOwner {
+            address: alice.clone(),
+            weight:  1,
+        },
+        MultisigOwner {
+            address: bob.clone(),
+            weight:  1,
+        },
+    ];
+    let wallet = multisig.create(ctx_of(&alice), owners, 2).unwrap();
+
+    let raw = mock_wallet_tx(&carol);
+    assert!(multisig
+        .propose(ctx_of(&carol), &wallet.address, raw.clone())
+        .is_err());
+
+    let proposal = multisig
+        .propose(ctx_of(&alice), &wallet.address, raw.clone())
+        .unwrap();
+    assert_eq!(proposal.id, Hash::digest(raw.encode_fixed().unwrap()));
+    assert_eq!(proposal.approvals, vec![alice.clone()]);
+    assert_eq!(multisig.is_approved(&proposal).unwrap(), false);
+
+    // The same transaction can not be proposed twice.
+    assert!(multisig
+        .propose(ctx_of(&bob), &wallet.address, raw)
+        .is_err());
+
+    assert!(multisig.approve(ctx_of(&alice), &proposal.id).is_err());
+    assert!(multisig.approve(ctx_of(&carol), &proposal.id).is_err());
+
+    let proposal = multisig.approve(ctx_of(&bob), &proposal.id).unwrap();
+    assert_eq!(proposal.approvals, vec![alice, bob.clone()]);
+    assert_eq!(multisig.is_approved(&proposal).unwrap(), true);
+
+    multisig.mark_executed(&proposal.id).unwrap();
+    let proposal = multisig.get_proposal(&proposal.id).unwrap();
+    assert_eq!(proposal.executed, true);
+    assert_eq!(multisig.is_approved(&proposal).unwrap(), false);
+    assert!(multisig.approve(ctx_of(&bob), &proposal.id).is_err());
+}
@@ -0,0 +1,54 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use bytes::Bytes;
+
+use protocol::types::{Address, AssetID, ContractAddress, ContractType, Hash};
+
+use crate::adapter::GeneralContractStateAdapter;
+use crate::dispatcher::NativeDispatcher;
+use crate::tests::{create_empty_memdb, create_empty_trie, mock_invoke_context};
+use crate::vm::RiscvVM;
+
+#[test]
+fn test_vm_deploy() {
+    let memdb = create_empty_memdb();
+    let state = Rc::new(RefCell::new(GeneralContractStateAdapter::new(
+        create_empty_trie(Arc::clone(&memdb)),
+    )));
+    let mut vm = RiscvVM::new(
+        Arc::clone(&memdb),
+        Rc::clone(&state),
+        Rc::new(NativeDispatcher::new()),
+    );
+
+    let fee_asset =
+        AssetID::from_hex("0000000000000000000000000000000000000000000000000000000000000003")
+            .unwrap();
+    let user1 = Address::from_hex("100000000000000000000000000000000000000001").unwrap();
+    let ctx = mock_invoke_context(user1, None, 0, 1_000_000, fee_asset);
+
+    let code = Bytes::from("fake riscv code");
+    let app = ContractAddress::from_code(code.clone(), 0, ContractType::App).unwrap();
+    vm.deploy(Rc::clone(&ctx), &app, code.clone()).unwrap();
+    assert_eq!(ctx.borrow().cycles_used > 0, true);
+
+    // deploy to the same address twice
+    let res = vm.deploy(Rc::clone(&ctx), &app, code.clone());
+    assert_eq!(res.is_err(), true);
+
+    // empty code
+    let library = ContractAddress::from_code(code.clone(), 1, ContractType::Library).unwrap();
+    let res = vm.deploy(Rc::clone(&ctx), &library, Bytes::new());
+    assert_eq!(res.is_err(), true);
+
+    // asset contract is not run by vm
+    let asset = ContractAddress::from_code(code.clone(), 2, ContractType::Asset).unwrap();
+    let res = vm.deploy(Rc::clone(&ctx), &asset, code);
+    assert_eq!(res.is_err(), true);
+
+    // call contract without code
+    let res = vm.call(Rc::clone(&ctx), &library, Hash::from_empty(), "main", &[]);
+    assert_eq!(res.is_err(), true);
+}
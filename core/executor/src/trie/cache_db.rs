@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::Arc;
+
+use derive_more::Display;
+use parking_lot::Mutex;
+
+use protocol::traits::executor::TrieDB;
+use protocol::{ProtocolError, ProtocolErrorKind, ProtocolResult};
+
+/// CacheDB keeps the nodes written by the tries of an executor in memory, so
+/// that the state root after every transaction is computed without writing
+/// the database. The nodes are written to the database at once when the
+/// epoch is committed.
+pub struct CacheDB<DB: TrieDB> {
+    db: Arc<DB>,
+
+    // The nodes inserted since the last committed epoch, with the number of
+    // times they are inserted.
+    inserted: Mutex<HashMap<Vec<u8>, (Vec<u8>, usize)>>,
+    // The nodes of the database removed since the last committed epoch.
+    removed: Mutex<Vec<Vec<u8>>>,
+}
+
+impl<DB: TrieDB> CacheDB<DB> {
+    pub fn new(db: Arc<DB>) -> Self {
+        CacheDB {
+            db,
+            inserted: Mutex::new(HashMap::new()),
+            removed: Mutex::new(Vec::new()),
+        }
+    }
+
+    // Write the cached nodes to the database, the nodes inserted for several
+    // times are passed as many times, so that the reference counts of them
+    // are kept.
+    fn write_back(&self) -> Result<(), DB::Error> {
+        let (keys, values): (Vec<_>, Vec<_>) = self
+            .inserted
+            .lock()
+            .drain()
+            .flat_map(|(key, (value, count))| (0..count).map(move |_| (key.clone(), value.clone())))
+            .unzip();
+        let removed = self.removed.lock().split_off(0);
+
+        if !keys.is_empty() {
+            self.db.insert_batch(keys, values)?;
+        }
+        if !removed.is_empty() {
+            self.db.remove_batch(&removed)?;
+        }
+        self.db.flush()
+    }
+}
+
+impl<DB: TrieDB> cita_trie::DB for CacheDB<DB> {
+    type Error = DB::Error;
+
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Self::Error> {
+        if let Some((value, _)) = self.inserted.lock().get(key) {
+            return Ok(Some(value.clone()));
+        }
+        self.db.get(key)
+    }
+
+    fn contains(&self, key: &[u8]) -> Result<bool, Self::Error> {
+        if self.inserted.lock().contains_key(key) {
+            return Ok(true);
+        }
+        self.db.contains(key)
+    }
+
+    fn insert(&self, key: Vec<u8>, value: Vec<u8>) -> Result<(), Self::Error> {
+        self.inserted
+            .lock()
+            .entry(key)
+            .or_insert_with(|| (value, 0))
+            .1 += 1;
+        Ok(())
+    }
+
+    fn insert_batch(&self, keys: Vec<Vec<u8>>, values: Vec<Vec<u8>>) -> Result<(), Self::Error> {
+        let mut inserted = self.inserted.lock();
+        for (key, value) in keys.into_iter().zip(values.into_iter()) {
+            inserted.entry(key).or_insert_with(|| (value, 0)).1 += 1;
+        }
+        Ok(())
+    }
+
+    fn remove(&self, key: &[u8]) -> Result<(), Self::Error> {
+        self.remove_batch(&[key.to_vec()])
+    }
+
+    // A node inserted since the last committed epoch never reaches the
+    // database if it is removed as many times.
+    fn remove_batch(&self, keys: &[Vec<u8>]) -> Result<(), Self::Error> {
+        let mut inserted = self.inserted.lock();
+        let mut removed = self.removed.lock();
+        for key in keys.iter() {
+            match inserted.get_mut(key) {
+                Some((_, count)) if *count > 1 => *count -= 1,
+                Some(_) => {
+                    inserted.remove(key);
+                }
+                None => removed.push(key.clone()),
+            }
+        }
+        Ok(())
+    }
+
+    fn flush(&self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl<DB: TrieDB> TrieDB for CacheDB<DB> {
+    fn commit_epoch(&self, epoch_id: u64) -> ProtocolResult<()> {
+        self.write_back()
+            .map_err(|e| CacheDBError::WriteBack(e.to_string()))?;
+        self.db.commit_epoch(epoch_id)
+    }
+}
+
+#[derive(Debug, Display)]
+pub enum CacheDBError {
+    #[display(fmt = "write back the trie nodes failed {}", _0)]
+    WriteBack(String),
+}
+
+impl Error for CacheDBError {}
+
+impl From<CacheDBError> for ProtocolError {
+    fn from(err: CacheDBError) -> ProtocolError {
+        ProtocolError::new(ProtocolErrorKind::Executor, Box::new(err))
+    }
+}
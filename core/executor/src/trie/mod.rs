@@ -1,6 +1,8 @@
+mod cache_db;
 mod trie_db;
 
-pub use trie_db::RocksTrieDB;
+pub use cache_db::CacheDB;
+pub use trie_db::{RocksTrieDB, DEFAULT_KEEP_EPOCHS};
 
 use std::error::Error;
 use std::sync::Arc;
@@ -41,6 +43,10 @@ impl<DB: TrieDB> MPTTrie<DB> {
         Ok(Self { root, trie })
     }
 
+    pub fn root(&self) -> MerkleRoot {
+        self.root.clone()
+    }
+
     pub fn get(&self, key: &Bytes) -> ProtocolResult<Option<Bytes>> {
         Ok(self
             .trie
@@ -53,6 +59,20 @@ impl<DB: TrieDB> MPTTrie<DB> {
         Ok(self.trie.contains(key).map_err(MPTTrieError::from)?)
     }
 
+    // The proof only covers the committed data.
+    pub fn get_proof(&self, key: &Bytes) -> ProtocolResult<Vec<Bytes>> {
+        let proof = self.trie.get_proof(key).map_err(MPTTrieError::from)?;
+        Ok(proof.into_iter().map(Bytes::from).collect())
+    }
+
+    // Collect all the key-value pairs of the trie.
+    pub fn entries(&self) -> Vec<(Bytes, Bytes)> {
+        self.trie
+            .iter()
+            .map(|(key, value)| (Bytes::from(key), Bytes::from(value)))
+            .collect()
+    }
+
     pub fn insert(&mut self, key: Bytes, value: Bytes) -> ProtocolResult<()> {
         self.trie
             .insert(key.to_vec(), value.to_vec())
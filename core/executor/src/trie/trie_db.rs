@@ -1,31 +1,120 @@
+use std::collections::HashMap;
 use std::error::Error;
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::{self, JoinHandle};
 
 use bytes::Bytes;
 use derive_more::{Display, From};
-use rocksdb::{Options, WriteBatch, DB};
+use log::error;
+use rocksdb::{ColumnFamily, Options, WriteBatch, DB};
 
+use protocol::traits::executor::TrieDB;
 use protocol::{ProtocolError, ProtocolErrorKind, ProtocolResult};
 
+pub const DEFAULT_KEEP_EPOCHS: u64 = 1000;
+
+// The reference counts of the nodes, keyed by the node key.
+const C_REFCOUNT: &str = "c1";
+// The nodes removed by the tries of an epoch, keyed by the epoch id.
+const C_JOURNAL: &str = "c2";
+
+/// RocksTrieDB stores the MPT nodes of all the states.
+///
+/// With `light` enabled, the states of the last `keep_epochs` epochs are kept
+/// and the older nodes are garbage-collected in the background. Every node
+/// has a reference count, which is increased when a trie inserts it. The nodes
+/// removed by the tries are recorded in the journal of the epoch, once the
+/// epoch falls out of the window, the reference counts of them are decreased
+/// and the nodes not referenced anymore are deleted.
 pub struct RocksTrieDB {
-    light: bool,
-    db:    Arc<DB>,
+    light:       bool,
+    keep_epochs: u64,
+    db:          Arc<DB>,
+
+    // The nodes removed since the last committed epoch.
+    pending_removed: Mutex<Vec<Vec<u8>>>,
+    // Serialize the updates of the reference counts.
+    refcount_lock: Arc<Mutex<()>>,
+    prune_sender:  Mutex<mpsc::Sender<u64>>,
+    pruner_handle: Option<JoinHandle<()>>,
 }
 
 impl RocksTrieDB {
-    pub fn new<P: AsRef<Path>>(path: P, light: bool) -> ProtocolResult<Self> {
+    pub fn new<P: AsRef<Path>>(path: P, light: bool, keep_epochs: u64) -> ProtocolResult<Self> {
         let mut opts = Options::default();
         opts.create_if_missing(true);
         opts.create_missing_column_families(true);
 
-        let db = DB::open(&opts, path).map_err(RocksTrieDBError::from)?;
+        let db = DB::open_cf(&opts, path, [C_REFCOUNT, C_JOURNAL].iter())
+            .map_err(RocksTrieDBError::from)?;
+        let db = Arc::new(db);
+
+        let refcount_lock = Arc::new(Mutex::new(()));
+        let (prune_sender, prune_receiver) = mpsc::channel();
+
+        let pruner = Pruner {
+            db:            Arc::clone(&db),
+            refcount_lock: Arc::clone(&refcount_lock),
+        };
+        let pruner_handle = thread::spawn(move || {
+            for epoch_id in prune_receiver.iter() {
+                if let Err(e) = pruner.prune(epoch_id) {
+                    error!("trie db: prune epoch {} failed {:?}", epoch_id, e);
+                }
+            }
+        });
 
         Ok(RocksTrieDB {
             light,
-            db: Arc::new(db),
+            keep_epochs,
+            db,
+
+            pending_removed: Mutex::new(vec![]),
+            refcount_lock,
+            prune_sender: Mutex::new(prune_sender),
+            pruner_handle: Some(pruner_handle),
         })
     }
+
+    fn inc_refcounts(&self, keys: &[Vec<u8>]) -> Result<(), RocksTrieDBError> {
+        let _lock = self
+            .refcount_lock
+            .lock()
+            .map_err(|_| RocksTrieDBError::Poisoned)?;
+        let cf = get_cf(&self.db, C_REFCOUNT)?;
+
+        let mut counts: HashMap<&[u8], u64> = HashMap::new();
+        for key in keys {
+            let count = match counts.get(key.as_slice()) {
+                Some(count) => *count,
+                None => match get_refcount(&self.db, cf, key)? {
+                    Some(count) => count,
+                    // The node was written before the reference counting, it
+                    // is never pruned.
+                    None if self.db.get(key)?.is_some() => continue,
+                    None => 0,
+                },
+            };
+            counts.insert(key.as_slice(), count + 1);
+        }
+
+        let mut batch = WriteBatch::default();
+        for (key, count) in counts {
+            batch.put_cf(cf, key, &count.to_be_bytes())?;
+        }
+
+        self.db.write(batch)?;
+        Ok(())
+    }
+
+    fn remove_later(&self, keys: &[Vec<u8>]) -> Result<(), RocksTrieDBError> {
+        self.pending_removed
+            .lock()
+            .map_err(|_| RocksTrieDBError::Poisoned)?
+            .extend_from_slice(keys);
+        Ok(())
+    }
 }
 
 impl cita_trie::DB for RocksTrieDB {
@@ -44,6 +133,10 @@ impl cita_trie::DB for RocksTrieDB {
     }
 
     fn insert(&self, key: Vec<u8>, value: Vec<u8>) -> Result<(), Self::Error> {
+        if self.light {
+            self.inc_refcounts(&[key.clone()])?;
+        }
+
         self.db
             .put(Bytes::from(key), Bytes::from(value))
             .map_err(RocksTrieDBError::from)?;
@@ -55,6 +148,10 @@ impl cita_trie::DB for RocksTrieDB {
             return Err(RocksTrieDBError::BatchLengthMismatch);
         }
 
+        if self.light {
+            self.inc_refcounts(&keys)?;
+        }
+
         let mut batch = WriteBatch::default();
         for i in 0..keys.len() {
             let key = &keys[i];
@@ -66,41 +163,175 @@ impl cita_trie::DB for RocksTrieDB {
         Ok(())
     }
 
+    // The removed nodes may still be referenced by the states of the recent
+    // epochs, so they are only deleted by the pruning.
     fn remove(&self, key: &[u8]) -> Result<(), Self::Error> {
         if self.light {
-            self.db.delete(key).map_err(RocksTrieDBError::from)?;
+            self.remove_later(&[key.to_vec()])?;
         }
         Ok(())
     }
 
     fn remove_batch(&self, keys: &[Vec<u8>]) -> Result<(), Self::Error> {
         if self.light {
-            let mut batch = WriteBatch::default();
-            for key in keys {
-                batch.delete(key).map_err(RocksTrieDBError::from)?;
+            self.remove_later(keys)?;
+        }
+        Ok(())
+    }
+
+    fn flush(&self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl TrieDB for RocksTrieDB {
+    fn commit_epoch(&self, epoch_id: u64) -> ProtocolResult<()> {
+        if !self.light {
+            return Ok(());
+        }
+
+        let removed = {
+            let mut pending = self
+                .pending_removed
+                .lock()
+                .map_err(|_| RocksTrieDBError::Poisoned)?;
+            pending.split_off(0)
+        };
+
+        if !removed.is_empty() {
+            let cf = get_cf(&self.db, C_JOURNAL)?;
+            let mut stream = rlp::RlpStream::new_list(removed.len());
+            for key in removed.iter() {
+                stream.append(key);
             }
 
-            self.db.write(batch).map_err(RocksTrieDBError::from)?;
+            self.db
+                .put_cf(cf, &epoch_id.to_be_bytes(), &stream.out())
+                .map_err(RocksTrieDBError::from)?;
         }
 
+        if epoch_id >= self.keep_epochs {
+            self.prune_sender
+                .lock()
+                .map_err(|_| RocksTrieDBError::Poisoned)?
+                .send(epoch_id - self.keep_epochs)
+                .map_err(|_| RocksTrieDBError::PrunerStopped)?;
+        }
         Ok(())
     }
+}
 
-    fn flush(&self) -> Result<(), Self::Error> {
+// The pruner holds the rocksdb, wait for it to stop so that the db can be
+// reopened after drop.
+impl Drop for RocksTrieDB {
+    fn drop(&mut self) {
+        if let Ok(mut sender) = self.prune_sender.lock() {
+            *sender = mpsc::channel().0;
+        }
+
+        if let Some(handle) = self.pruner_handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+struct Pruner {
+    db:            Arc<DB>,
+    refcount_lock: Arc<Mutex<()>>,
+}
+
+impl Pruner {
+    // Release the nodes removed at the epoch, the states before the epoch are
+    // not queryable after that.
+    fn prune(&self, epoch_id: u64) -> Result<(), RocksTrieDBError> {
+        let journal_cf = get_cf(&self.db, C_JOURNAL)?;
+        let refcount_cf = get_cf(&self.db, C_REFCOUNT)?;
+
+        let journal_key = epoch_id.to_be_bytes();
+        let journal = match self.db.get_cf(journal_cf, &journal_key)? {
+            Some(journal) => journal,
+            None => return Ok(()),
+        };
+        let removed: Vec<Vec<u8>> = rlp::Rlp::new(&journal).as_list()?;
+
+        let _lock = self
+            .refcount_lock
+            .lock()
+            .map_err(|_| RocksTrieDBError::Poisoned)?;
+
+        let mut counts: HashMap<&[u8], u64> = HashMap::new();
+        for key in removed.iter() {
+            let count = match counts.get(key.as_slice()) {
+                Some(count) => *count,
+                None => match get_refcount(&self.db, refcount_cf, key)? {
+                    Some(count) => count,
+                    None => continue,
+                },
+            };
+            counts.insert(key.as_slice(), count.saturating_sub(1));
+        }
+
+        let mut batch = WriteBatch::default();
+        for (key, count) in counts {
+            if count == 0 {
+                batch.delete(key)?;
+                batch.delete_cf(refcount_cf, key)?;
+            } else {
+                batch.put_cf(refcount_cf, key, &count.to_be_bytes())?;
+            }
+        }
+        batch.delete_cf(journal_cf, &journal_key)?;
+
+        self.db.write(batch)?;
         Ok(())
     }
 }
 
+fn get_cf(db: &DB, name: &str) -> Result<ColumnFamily, RocksTrieDBError> {
+    db.cf_handle(name)
+        .ok_or_else(|| RocksTrieDBError::ColumnFamilyNotFound(name.to_owned()))
+}
+
+// Fetch the reference count of the node, `None` means the node is not counted.
+fn get_refcount(db: &DB, cf: ColumnFamily, key: &[u8]) -> Result<Option<u64>, RocksTrieDBError> {
+    let count = match db.get_cf(cf, key)? {
+        Some(count) => count,
+        None => return Ok(None),
+    };
+
+    if count.len() != 8 {
+        return Err(RocksTrieDBError::InvalidRefcount);
+    }
+    let mut buf = [0u8; 8];
+    buf.copy_from_slice(&count);
+    Ok(Some(u64::from_be_bytes(buf)))
+}
+
 #[derive(Debug, Display, From)]
 pub enum RocksTrieDBError {
     #[display(fmt = "rocksdb {}", _0)]
     RocksDB(rocksdb::Error),
 
+    #[display(fmt = "rlp {:?}", _0)]
+    Rlp(rlp::DecoderError),
+
     #[display(fmt = "parameters do not match")]
     InsertParameter,
 
     #[display(fmt = "batch length dont match")]
     BatchLengthMismatch,
+
+    #[display(fmt = "column family {} not found", _0)]
+    ColumnFamilyNotFound(String),
+
+    #[display(fmt = "invalid reference count")]
+    InvalidRefcount,
+
+    #[display(fmt = "lock poisoned")]
+    Poisoned,
+
+    #[display(fmt = "pruner stopped")]
+    PrunerStopped,
 }
 
 impl Error for RocksTrieDBError {}
@@ -0,0 +1,287 @@
+mod syscall;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::error::Error;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use ckb_vm::{DefaultCoreMachine, DefaultMachineBuilder, SparseMemory, SupportMachine};
+use derive_more::{Display, From};
+
+use protocol::traits::executor::contract::ContractStateAdapter;
+use protocol::traits::executor::{RcInvokeContext, TrieDB};
+use protocol::types::{Address, ContractAddress, ContractType, Hash, MerkleRoot};
+use protocol::{ProtocolError, ProtocolErrorKind, ProtocolResult};
+
+use crate::adapter::{GeneralContractStateAdapter, RcGeneralContractStateAdapter, Traced};
+use crate::cycles::{consume_cycles, cycles_of, CyclesAction, CyclesError};
+use crate::dispatcher::NativeDispatcher;
+use crate::fixed_types::FixedContractCodeSchema;
+use crate::trie::MPTTrie;
+use crate::vm::syscall::{RcVMContext, SyscallEnvironment, VMContext};
+
+/// RiscvVM runs the rv64imc code of `App` and `Library` contracts.
+///
+/// The code of the contracts is stored in the state of the VM itself, keyed by
+/// the contract address. Each contract keeps its own storage trie, the root of
+/// which is recorded in `ContractAccount.storage_root`.
+///
+/// The storage of a contract follows the `cache`, `stash` and `commit` of the
+/// executor, so nothing is written to the trie until the epoch is committed.
+///
+/// A contract calls the native contracts through the `dispatcher`.
+pub struct RiscvVM<DB: TrieDB> {
+    db:            Arc<DB>,
+    state_adapter: RcGeneralContractStateAdapter<DB>,
+    dispatcher:    Rc<NativeDispatcher>,
+    // The opened storages with their storage roots before the execution.
+    storages: HashMap<ContractAddress, (MerkleRoot, RcGeneralContractStateAdapter<DB>)>,
+    // Whether the storages opened are traced.
+    trace: bool,
+}
+
+impl<DB: 'static + TrieDB> RiscvVM<DB> {
+    pub fn new(
+        db: Arc<DB>,
+        state_adapter: RcGeneralContractStateAdapter<DB>,
+        dispatcher: Rc<NativeDispatcher>,
+    ) -> Self {
+        Self {
+            db,
+            state_adapter,
+            dispatcher,
+            storages: HashMap::new(),
+            trace: false,
+        }
+    }
+
+    pub fn enable_trace(&mut self) {
+        self.trace = true;
+        for (_, (_, storage)) in self.storages.iter() {
+            storage.borrow_mut().enable_trace();
+        }
+    }
+
+    // Take the keys stashed in the storages since the last call.
+    pub fn take_trace(&mut self) -> Vec<(ContractAddress, Vec<Traced>)> {
+        self.storages
+            .iter()
+            .map(|(address, (_, storage))| (address.clone(), storage.borrow_mut().take_trace()))
+            .collect()
+    }
+
+    pub fn stash(&mut self) -> ProtocolResult<()> {
+        for (_, (_, storage)) in self.storages.iter() {
+            storage.borrow_mut().stash()?;
+        }
+        Ok(())
+    }
+
+    pub fn revert(&mut self) -> ProtocolResult<()> {
+        for (_, (_, storage)) in self.storages.iter() {
+            storage.borrow_mut().revert_cache()?;
+        }
+        Ok(())
+    }
+
+    // Commit the storages and return the contracts whose storage root has
+    // changed.
+    pub fn commit(&mut self) -> ProtocolResult<Vec<(ContractAddress, MerkleRoot)>> {
+        let mut changed = vec![];
+
+        for (address, (storage_root, storage)) in self.storages.iter_mut() {
+            let new_storage_root = storage.borrow_mut().commit()?;
+
+            if &new_storage_root != storage_root {
+                *storage_root = new_storage_root.clone();
+                changed.push((address.clone(), new_storage_root));
+            }
+        }
+        Ok(changed)
+    }
+
+    fn open_storage(
+        &mut self,
+        address: &ContractAddress,
+        storage_root: MerkleRoot,
+    ) -> ProtocolResult<RcGeneralContractStateAdapter<DB>> {
+        if let Some((_, storage)) = self.storages.get(address) {
+            return Ok(Rc::clone(storage));
+        }
+
+        let trie = {
+            if storage_root == Hash::from_empty() {
+                MPTTrie::new(Arc::clone(&self.db))
+            } else {
+                MPTTrie::from(storage_root.clone(), Arc::clone(&self.db))?
+            }
+        };
+        let mut storage = GeneralContractStateAdapter::new(trie);
+        if self.trace {
+            storage.enable_trace();
+        }
+        let storage = Rc::new(RefCell::new(storage));
+
+        self.storages
+            .insert(address.clone(), (storage_root, Rc::clone(&storage)));
+        Ok(storage)
+    }
+
+    pub fn deploy(
+        &mut self,
+        ictx: RcInvokeContext,
+        address: &ContractAddress,
+        code: Bytes,
+    ) -> ProtocolResult<()> {
+        match address.contract_type() {
+            ContractType::App | ContractType::Library => {}
+            _ => return Err(VMError::InvalidAddress.into()),
+        }
+
+        if code.is_empty() {
+            return Err(VMError::EmptyCode.into());
+        }
+
+        if self
+            .state_adapter
+            .borrow()
+            .contains::<FixedContractCodeSchema>(address)?
+        {
+            return Err(VMError::ContractExists {
+                address: address.clone(),
+            }
+            .into());
+        }
+
+        self.state_adapter
+            .borrow_mut()
+            .insert_cache::<FixedContractCodeSchema>(address.clone(), code)?;
+
+        let cycles_used = consume_cycles(
+            CyclesAction::VMDeploy,
+            ictx.borrow().cycles_used,
+            ictx.borrow().cycles_limit,
+        )?;
+        ictx.borrow_mut().cycles_used = cycles_used;
+        Ok(())
+    }
+
+    // Run the contract code with the arguments `[address, method, args...]`,
+    // return the value passed to the `RET` syscall. The `storage_root` is only
+    // used to open the storage at the first call of the contract.
+    //
+    // NOTE: The cycles of the executed instructions and syscalls are counted
+    // by the machine against the cycles left, and charged even if the call
+    // fails.
+    pub fn call(
+        &mut self,
+        ictx: RcInvokeContext,
+        address: &ContractAddress,
+        storage_root: MerkleRoot,
+        method: &str,
+        args: &[Bytes],
+    ) -> ProtocolResult<Bytes> {
+        let code = self
+            .state_adapter
+            .borrow()
+            .get::<FixedContractCodeSchema>(address)?
+            .ok_or(VMError::CodeNotFound {
+                address: address.clone(),
+            })?;
+
+        let storage = self.open_storage(address, storage_root)?;
+
+        let vm_ctx: RcVMContext<DB> = Rc::new(RefCell::new(VMContext {
+            ictx:       Rc::clone(&ictx),
+            contract:   address.clone(),
+            storage:    Rc::clone(&storage),
+            dispatcher: Rc::clone(&self.dispatcher),
+            ret:        Bytes::new(),
+            error:      None,
+        }));
+
+        let (cycles_used, cycles_limit) = {
+            let ictx = ictx.borrow();
+            (ictx.cycles_used, ictx.cycles_limit)
+        };
+        let cycles_left = cycles_limit.saturating_sub(cycles_used);
+        let instruction_cycles = cycles_of(CyclesAction::VMInstruction);
+
+        let core_machine =
+            DefaultCoreMachine::<u64, SparseMemory<u64>>::new_with_max_cycles(cycles_left);
+        let mut machine = DefaultMachineBuilder::new(core_machine)
+            .instruction_cycle_func(Box::new(move |_| instruction_cycles))
+            .syscall(Box::new(SyscallEnvironment::new(Rc::clone(&vm_ctx))))
+            .build();
+
+        let mut vm_args = vec![Bytes::from(address.as_hex()), Bytes::from(method)];
+        vm_args.extend_from_slice(args);
+        machine
+            .load_program(&code, &vm_args)
+            .map_err(VMError::from)?;
+        let run_result = machine.run();
+
+        // The machine stops once the cycles left are not enough for the next
+        // instruction or syscall, then all the cycles left are charged.
+        if let Err(ckb_vm::Error::InvalidCycles) = run_result {
+            ictx.borrow_mut().cycles_used = cycles_limit.max(cycles_used);
+            return Err(CyclesError::OutOfCycles.into());
+        }
+        ictx.borrow_mut().cycles_used = cycles_used + machine.cycles();
+
+        if let Some(err) = vm_ctx.borrow_mut().error.take() {
+            return Err(err.into());
+        }
+
+        let exit_code = run_result.map_err(VMError::from)?;
+        if exit_code != 0 {
+            return Err(VMError::ExitCode(exit_code).into());
+        }
+
+        let ret = vm_ctx.borrow().ret.clone();
+        Ok(ret)
+    }
+}
+
+#[derive(Debug, Display, From)]
+pub enum VMError {
+    #[display(fmt = "ckb-vm {:?}", _0)]
+    VM(ckb_vm::Error),
+
+    #[display(fmt = "contract exits with code {}", _0)]
+    ExitCode(i8),
+
+    #[display(fmt = "contract {:?} already exists", address)]
+    ContractExists { address: ContractAddress },
+
+    #[display(fmt = "code of contract {:?} not found", address)]
+    CodeNotFound { address: ContractAddress },
+
+    #[display(fmt = "library contract {:?} is not allowed to write state", address)]
+    WriteInLibrary { address: ContractAddress },
+
+    #[display(fmt = "{}", _0)]
+    Syscall(String),
+
+    #[display(fmt = "contract can only call the native contracts, not {:?}", address)]
+    CallNonNative { address: Address },
+
+    #[display(fmt = "invalid args of the contract call")]
+    InvalidCallArgs,
+
+    #[display(fmt = "empty code")]
+    EmptyCode,
+
+    #[display(fmt = "invalid address")]
+    InvalidAddress,
+}
+
+impl Error for VMError {}
+
+impl From<VMError> for ProtocolError {
+    fn from(err: VMError) -> ProtocolError {
+        ProtocolError::new(ProtocolErrorKind::Executor, Box::new(err))
+    }
+}
@@ -0,0 +1,303 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use bytes::Bytes;
+use ckb_vm::registers::{A0, A1, A2, A3, A7};
+use ckb_vm::{Error as CKBVMError, Memory, Register, SupportMachine, Syscalls};
+
+use protocol::traits::executor::contract::ContractStateAdapter;
+use protocol::traits::executor::{Dispatcher, RcInvokeContext, TrieDB};
+use protocol::types::{Address, ContractAddress, ContractType, Hash, Log};
+
+use crate::adapter::RcGeneralContractStateAdapter;
+use crate::cycles::{cycles_of, CyclesAction};
+use crate::dispatcher::NativeDispatcher;
+use crate::fixed_types::FixedBytesSchema;
+use crate::vm::VMError;
+
+pub const SYSCODE_STORAGE_SET: u64 = 2180;
+pub const SYSCODE_STORAGE_GET: u64 = 2181;
+pub const SYSCODE_RET: u64 = 2182;
+pub const SYSCODE_LOG: u64 = 2183;
+pub const SYSCODE_CONTRACT_CALL: u64 = 2184;
+
+// The max length of a string passed from the contract, include the trailing
+// zero.
+const MAX_STRING_LEN: usize = 64 * 1024;
+
+pub type RcVMContext<DB> = Rc<RefCell<VMContext<DB>>>;
+
+// `error` keeps the detail of the error which aborts the execution in
+// syscalls, since ckb-vm only reports an `InvalidEcall`.
+
+pub struct VMContext<DB: TrieDB> {
+    pub ictx:       RcInvokeContext,
+    pub contract:   ContractAddress,
+    pub storage:    RcGeneralContractStateAdapter<DB>,
+    pub dispatcher: Rc<NativeDispatcher>,
+    pub ret:        Bytes,
+    pub error:      Option<VMError>,
+}
+
+pub struct SyscallEnvironment<DB: TrieDB> {
+    vm_ctx: RcVMContext<DB>,
+}
+
+impl<DB: TrieDB> SyscallEnvironment<DB> {
+    pub fn new(vm_ctx: RcVMContext<DB>) -> Self {
+        Self { vm_ctx }
+    }
+
+    fn storage_set(&mut self, key: Bytes, value: Bytes) -> Result<(), VMError> {
+        let vm_ctx = self.vm_ctx.borrow();
+
+        if vm_ctx.contract.contract_type() == ContractType::Library {
+            return Err(VMError::WriteInLibrary {
+                address: vm_ctx.contract.clone(),
+            });
+        }
+
+        vm_ctx
+            .storage
+            .borrow_mut()
+            .insert_cache::<FixedBytesSchema>(key, value)
+            .map_err(|e| VMError::Syscall(e.to_string()))
+    }
+
+    fn storage_get(&mut self, key: &Bytes) -> Result<Bytes, VMError> {
+        let vm_ctx = self.vm_ctx.borrow();
+
+        let value = vm_ctx
+            .storage
+            .borrow()
+            .get::<FixedBytesSchema>(key)
+            .map_err(|e| VMError::Syscall(e.to_string()))?;
+        Ok(value.unwrap_or_else(Bytes::new))
+    }
+
+    // The topic string is hashed, as the topics of a log are `Hash`es.
+    fn log(&mut self, topic: Bytes, data: Bytes) -> Result<(), VMError> {
+        let vm_ctx = self.vm_ctx.borrow();
+
+        vm_ctx.ictx.borrow_mut().logs.push(Log {
+            address: Address::Contract(vm_ctx.contract.clone()),
+            topics: vec![Hash::digest(topic)],
+            data,
+        });
+        Ok(())
+    }
+
+    // Call a native contract with the contract as the caller, so that the
+    // contract can spend the assets approved to it. The arguments are hex
+    // encoded and separated by commas, the return value is hex encoded.
+    //
+    // The native contract runs with the cycles left by the machine, the
+    // cycles it uses are returned along with the result, even if it fails.
+    fn contract_call(
+        &mut self,
+        address: &Bytes,
+        method: &Bytes,
+        args: &Bytes,
+        cycles_left: u64,
+    ) -> (u64, Result<Bytes, VMError>) {
+        let vm_ctx = self.vm_ctx.borrow();
+
+        if vm_ctx.contract.contract_type() == ContractType::Library {
+            return (
+                0,
+                Err(VMError::WriteInLibrary {
+                    address: vm_ctx.contract.clone(),
+                }),
+            );
+        }
+        let (address, method, args) = match decode_call(address, method, args) {
+            Ok(call) => call,
+            Err(e) => return (0, Err(e)),
+        };
+
+        let mut ictx = vm_ctx.ictx.borrow().clone();
+        ictx.caller = Address::Contract(vm_ctx.contract.clone());
+        ictx.carrying_asset = None;
+        ictx.cycles_used = 0;
+        ictx.cycles_limit = cycles_left;
+        ictx.logs = vec![];
+        let ictx = Rc::new(RefCell::new(ictx));
+
+        let res = vm_ctx
+            .dispatcher
+            .invoke(Rc::clone(&ictx), address, &method, args)
+            .map(|ret| Bytes::from(hex::encode(ret)))
+            .map_err(|e| VMError::Syscall(e.to_string()));
+
+        let ictx = ictx.borrow();
+        if res.is_ok() {
+            vm_ctx
+                .ictx
+                .borrow_mut()
+                .logs
+                .extend(ictx.logs.iter().cloned());
+        }
+        (ictx.cycles_used, res)
+    }
+}
+
+impl<DB: TrieDB, Mac: SupportMachine> Syscalls<Mac> for SyscallEnvironment<DB> {
+    fn initialize(&mut self, _machine: &mut Mac) -> Result<(), CKBVMError> {
+        Ok(())
+    }
+
+    fn ecall(&mut self, machine: &mut Mac) -> Result<bool, CKBVMError> {
+        let code = machine.registers()[A7].to_u64();
+
+        // The cycles of a syscall are counted by the machine, so that they
+        // take from the cycles left for the instructions.
+        let action = match code {
+            SYSCODE_STORAGE_SET => Some(CyclesAction::VMStorageSet),
+            SYSCODE_STORAGE_GET => Some(CyclesAction::VMStorageGet),
+            SYSCODE_LOG => Some(CyclesAction::VMLog),
+            SYSCODE_CONTRACT_CALL => Some(CyclesAction::VMContractCall),
+            _ => None,
+        };
+        if let Some(action) = action {
+            machine.add_cycles(cycles_of(action))?;
+        }
+
+        let res = match code {
+            SYSCODE_STORAGE_SET => {
+                let key_ptr = machine.registers()[A0].to_u64();
+                let value_ptr = machine.registers()[A1].to_u64();
+                let key = load_c_string(machine, key_ptr)?;
+                let value = load_c_string(machine, value_ptr)?;
+
+                self.storage_set(key, value)
+            }
+            SYSCODE_STORAGE_GET => {
+                let key_ptr = machine.registers()[A0].to_u64();
+                let value_ptr = machine.registers()[A1].to_u64();
+                let key = load_c_string(machine, key_ptr)?;
+
+                match self.storage_get(&key) {
+                    Ok(value) => {
+                        let mut value = value.to_vec();
+                        value.push(0);
+                        machine.memory_mut().store_bytes(value_ptr, &value)?;
+                        Ok(())
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+            SYSCODE_RET => {
+                let ret_ptr = machine.registers()[A0].to_u64();
+                let ret = load_c_string(machine, ret_ptr)?;
+
+                self.vm_ctx.borrow_mut().ret = ret;
+                Ok(())
+            }
+            SYSCODE_LOG => {
+                let topic_ptr = machine.registers()[A0].to_u64();
+                let data_ptr = machine.registers()[A1].to_u64();
+                let topic = load_c_string(machine, topic_ptr)?;
+                let data = load_c_string(machine, data_ptr)?;
+
+                self.log(topic, data)
+            }
+            SYSCODE_CONTRACT_CALL => {
+                let address_ptr = machine.registers()[A0].to_u64();
+                let method_ptr = machine.registers()[A1].to_u64();
+                let args_ptr = machine.registers()[A2].to_u64();
+                let ret_ptr = machine.registers()[A3].to_u64();
+                let address = load_c_string(machine, address_ptr)?;
+                let method = load_c_string(machine, method_ptr)?;
+                let args = load_c_string(machine, args_ptr)?;
+
+                // The cycles used by the machine are only counted into the
+                // invoke context after the execution.
+                let cycles_left = {
+                    let vm_ctx = self.vm_ctx.borrow();
+                    let ictx = vm_ctx.ictx.borrow();
+                    ictx.cycles_limit
+                        .saturating_sub(ictx.cycles_used)
+                        .saturating_sub(machine.cycles())
+                };
+
+                let (cycles_used, res) = self.contract_call(&address, &method, &args, cycles_left);
+                machine.add_cycles(cycles_used)?;
+
+                match res {
+                    Ok(ret) => {
+                        let mut ret = ret.to_vec();
+                        ret.push(0);
+                        machine.memory_mut().store_bytes(ret_ptr, &ret)?;
+                        Ok(())
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+            _ => return Ok(false),
+        };
+
+        match res {
+            Ok(()) => {
+                machine.set_register(A0, Mac::REG::from_u8(0));
+                Ok(true)
+            }
+            Err(e) => {
+                self.vm_ctx.borrow_mut().error = Some(e);
+                Err(CKBVMError::InvalidEcall(code))
+            }
+        }
+    }
+}
+
+fn load_c_string<Mac: SupportMachine>(machine: &mut Mac, addr: u64) -> Result<Bytes, CKBVMError> {
+    let mut buffer = Vec::new();
+
+    for offset in 0..MAX_STRING_LEN as u64 {
+        let byte = machine
+            .memory_mut()
+            .load8(&Mac::REG::from_u64(addr.wrapping_add(offset)))?
+            .to_u8();
+        if byte == 0 {
+            return Ok(Bytes::from(buffer));
+        }
+        buffer.push(byte);
+    }
+
+    Err(CKBVMError::OutOfBound)
+}
+
+// Decode the native contract address, the method and the hex encoded
+// arguments of a contract call.
+fn decode_call(
+    address: &Bytes,
+    method: &Bytes,
+    args: &Bytes,
+) -> Result<(ContractAddress, String, Vec<Bytes>), VMError> {
+    let address = String::from_utf8(address.to_vec())
+        .ok()
+        .and_then(|address| Address::from_hex(&address).ok())
+        .ok_or(VMError::InvalidCallArgs)?;
+    let address = match &address {
+        Address::Contract(contract) if contract.contract_type() == ContractType::Native => {
+            contract.clone()
+        }
+        _ => {
+            return Err(VMError::CallNonNative {
+                address: address.clone(),
+            })
+        }
+    };
+
+    let method = String::from_utf8(method.to_vec()).map_err(|_| VMError::InvalidCallArgs)?;
+
+    let args = if args.is_empty() {
+        vec![]
+    } else {
+        args.split(|byte| *byte == b',')
+            .map(|arg| hex::decode(arg).map(Bytes::from))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| VMError::InvalidCallArgs)?
+    };
+
+    Ok((address, method, args))
+}
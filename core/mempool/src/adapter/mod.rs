@@ -1,12 +1,6 @@
 pub mod message;
 
-use std::{
-    error::Error,
-    marker::PhantomData,
-    sync::atomic::{AtomicU64, Ordering},
-    sync::Arc,
-    time::Duration,
-};
+use std::{error::Error, marker::PhantomData, sync::Arc, time::Duration};
 
 use async_trait::async_trait;
 use derive_more::Display;
@@ -20,12 +14,14 @@ use futures::{
 };
 use futures_timer::Delay;
 use log::{debug, error};
+use parking_lot::RwLock;
 
 use common_crypto::Crypto;
 use protocol::{
     fixed_codec::ProtocolFixedCodec,
+    traits::executor::{ExecutorFactory, TrieDB},
     traits::{Context, Gossip, MemPoolAdapter, Priority, Rpc, Storage},
-    types::{Hash, SignedTransaction},
+    types::{Address, Hash, MerkleRoot, SignedTransaction, UserAddress},
     ProtocolError, ProtocolErrorKind, ProtocolResult,
 };
 
@@ -130,28 +126,41 @@ impl IntervalTxsBroadcaster {
     }
 }
 
-pub struct DefaultMemPoolAdapter<C, N, S> {
-    network: N,
-    storage: Arc<S>,
+// The latest committed epoch and the state after executing it, they are
+// refreshed by `set_args` once per epoch.
+struct EpochArgs {
+    epoch_id:    u64,
+    state_root:  MerkleRoot,
+    timeout_gap: u64,
+}
 
-    timeout_gap: AtomicU64,
+pub struct DefaultMemPoolAdapter<C, N, S, EF, DB> {
+    network:  N,
+    storage:  Arc<S>,
+    trie_db:  Arc<DB>,
+    chain_id: Hash,
+    args:     RwLock<EpochArgs>,
 
     stx_tx: UnboundedSender<SignedTransaction>,
     err_rx: Mutex<UnboundedReceiver<ProtocolError>>,
 
-    pin_c: PhantomData<C>,
+    pin_c:  PhantomData<C>,
+    pin_ef: PhantomData<EF>,
 }
 
-impl<C, N, S> DefaultMemPoolAdapter<C, N, S>
+impl<C, N, S, EF, DB> DefaultMemPoolAdapter<C, N, S, EF, DB>
 where
     C: Crypto,
     N: Rpc + Gossip + Clone + Unpin + 'static,
     S: Storage,
+    EF: ExecutorFactory<DB>,
+    DB: TrieDB,
 {
     pub fn new(
         network: N,
         storage: Arc<S>,
-        timeout_gap: u64,
+        trie_db: Arc<DB>,
+        chain_id: Hash,
         broadcast_txs_size: usize,
         broadcast_txs_interval: u64,
     ) -> Self {
@@ -175,23 +184,32 @@ where
         DefaultMemPoolAdapter {
             network,
             storage,
-
-            timeout_gap: AtomicU64::new(timeout_gap),
+            trie_db,
+            chain_id,
+            // No transaction is accepted until the args are set.
+            args: RwLock::new(EpochArgs {
+                epoch_id:    0,
+                state_root:  Hash::from_empty(),
+                timeout_gap: 0,
+            }),
 
             stx_tx,
             err_rx: Mutex::new(err_rx),
 
             pin_c: PhantomData,
+            pin_ef: PhantomData,
         }
     }
 }
 
 #[async_trait]
-impl<C, N, S> MemPoolAdapter for DefaultMemPoolAdapter<C, N, S>
+impl<C, N, S, EF, DB> MemPoolAdapter for DefaultMemPoolAdapter<C, N, S, EF, DB>
 where
     C: Crypto + Send + Sync + 'static,
     N: Rpc + Gossip + Clone + Unpin + 'static,
     S: Storage + 'static,
+    EF: ExecutorFactory<DB> + 'static,
+    DB: TrieDB + 'static,
 {
     async fn pull_txs(
         &self,
@@ -238,7 +256,6 @@ where
     }
 
     // TODO: Verify Fee?
-    // TODO: Verify Nonce?
     // TODO: Cycle limit?
     async fn check_transaction(&self, _ctx: Context, stx: SignedTransaction) -> ProtocolResult<()> {
         // Verify transaction hash
@@ -255,8 +272,7 @@ where
         }
 
         // Verify chain id
-        let latest_epoch = self.storage.get_latest_epoch().await?;
-        if latest_epoch.header.chain_id != stx.raw.chain_id {
+        if self.chain_id != stx.raw.chain_id {
             let wrong_chain_id = MemPoolError::WrongChain {
                 tx_hash: stx.tx_hash,
             };
@@ -264,10 +280,12 @@ where
             return Err(wrong_chain_id.into());
         }
 
-        // Verify timeout
-        let latest_epoch_id = latest_epoch.header.epoch_id;
-        let timeout_gap = self.timeout_gap.load(Ordering::SeqCst);
+        let (latest_epoch_id, state_root, timeout_gap) = {
+            let args = self.args.read();
+            (args.epoch_id, args.state_root.clone(), args.timeout_gap)
+        };
 
+        // Verify timeout
         if stx.raw.timeout > latest_epoch_id + timeout_gap {
             let invalid_timeout = MemPoolError::InvalidTimeout {
                 tx_hash: stx.tx_hash,
@@ -285,6 +303,25 @@ where
             return Err(timeout.into());
         }
 
+        // Verify nonce, the executor checks it again since a transaction may
+        // reach the pool before the one using the same nonce is committed.
+        // Only the account of the sender is read, rather than building an
+        // executor for every transaction.
+        let sender = Address::User(UserAddress::from_pubkey_bytes(stx.pubkey.clone())?);
+        if EF::is_nonce_used(
+            state_root,
+            Arc::clone(&self.trie_db),
+            &sender,
+            &stx.raw.nonce,
+        )? {
+            let used_nonce = MemPoolError::UsedNonce {
+                tx_hash: stx.tx_hash,
+                nonce:   stx.raw.nonce,
+            };
+
+            return Err(used_nonce.into());
+        }
+
         Ok(())
     }
 
@@ -303,8 +340,19 @@ where
     }
 
     async fn get_latest_epoch_id(&self, _ctx: Context) -> ProtocolResult<u64> {
-        let epoch_id = self.storage.get_latest_epoch().await?.header.epoch_id;
-        Ok(epoch_id)
+        Ok(self.args.read().epoch_id)
+    }
+
+    async fn get_timeout_gap(&self, _ctx: Context) -> ProtocolResult<u64> {
+        Ok(self.args.read().timeout_gap)
+    }
+
+    fn set_args(&self, epoch_id: u64, state_root: MerkleRoot, timeout_gap: u64) {
+        *self.args.write() = EpochArgs {
+            epoch_id,
+            state_root,
+            timeout_gap,
+        };
     }
 }
 
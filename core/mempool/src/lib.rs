@@ -20,7 +20,7 @@ use async_trait::async_trait;
 use derive_more::{Display, From};
 
 use protocol::traits::{Context, MemPool, MemPoolAdapter, MixedTxHashes};
-use protocol::types::{Hash, SignedTransaction};
+use protocol::types::{Hash, MerkleRoot, SignedTransaction};
 use protocol::{ProtocolError, ProtocolErrorKind, ProtocolResult};
 
 use crate::context::TxContext;
@@ -31,8 +31,6 @@ use crate::tx_cache::TxCache;
 pub struct HashMemPool<Adapter: MemPoolAdapter> {
     /// Pool size limit.
     pool_size: usize,
-    /// A system param limits the life time of an off-chain transaction.
-    timeout_gap: u64,
     /// A structure for caching new transactions and responsible transactions of
     /// propose-sync.
     tx_cache: TxCache,
@@ -46,10 +44,9 @@ impl<Adapter> HashMemPool<Adapter>
 where
     Adapter: MemPoolAdapter,
 {
-    pub fn new(pool_size: usize, timeout_gap: u64, adapter: Adapter) -> Self {
+    pub fn new(pool_size: usize, adapter: Adapter) -> Self {
         HashMemPool {
             pool_size,
-            timeout_gap,
             tx_cache: TxCache::new(pool_size * 2),
             callback_cache: Map::new(pool_size),
             adapter,
@@ -99,11 +96,13 @@ where
 
     async fn package(&self, ctx: Context, cycle_limit: u64) -> ProtocolResult<MixedTxHashes> {
         let current_epoch_id = self.adapter.get_latest_epoch_id(ctx.clone()).await?;
+        // A system param limits the life time of an off-chain transaction.
+        let timeout_gap = self.adapter.get_timeout_gap(ctx.clone()).await?;
 
         self.tx_cache.package(
             cycle_limit,
             current_epoch_id,
-            current_epoch_id + self.timeout_gap,
+            current_epoch_id + timeout_gap,
         )
     }
 
@@ -181,6 +180,10 @@ where
         }
         Ok(())
     }
+
+    fn set_args(&self, epoch_id: u64, state_root: MerkleRoot, timeout_gap: u64) {
+        self.adapter.set_args(epoch_id, state_root, timeout_gap);
+    }
 }
 
 #[derive(Debug, Display, From)]
@@ -220,6 +223,9 @@ pub enum MemPoolError {
 
     #[display(fmt = "Tx: {:?} invalid timeout", tx_hash)]
     InvalidTimeout { tx_hash: Hash },
+
+    #[display(fmt = "Tx: {:?} nonce {:?} has been used", tx_hash, nonce)]
+    UsedNonce { tx_hash: Hash, nonce: Hash },
 }
 
 impl Error for MemPoolError {}
@@ -22,7 +22,7 @@ use common_crypto::{
 use protocol::codec::ProtocolCodec;
 use protocol::traits::{Context, MemPool, MemPoolAdapter, MixedTxHashes};
 use protocol::types::{
-    CarryingAsset, Fee, Hash, RawTransaction, SignedTransaction, TransactionAction,
+    CarryingAsset, Fee, Hash, MerkleRoot, RawTransaction, SignedTransaction, TransactionAction,
     UserAddress as Address,
 };
 use protocol::ProtocolResult;
@@ -39,12 +39,14 @@ const TX_CYCLE: u64 = 1;
 
 pub struct HashMemPoolAdapter {
     network_txs: CHashMap<Hash, SignedTransaction>,
+    timeout_gap: u64,
 }
 
 impl HashMemPoolAdapter {
-    fn new() -> HashMemPoolAdapter {
+    fn new(timeout_gap: u64) -> HashMemPoolAdapter {
         HashMemPoolAdapter {
             network_txs: CHashMap::new(),
+            timeout_gap,
         }
     }
 }
@@ -86,6 +88,12 @@ impl MemPoolAdapter for HashMemPoolAdapter {
     async fn get_latest_epoch_id(&self, _ctx: Context) -> ProtocolResult<u64> {
         Ok(CURRENT_EPOCH_ID)
     }
+
+    async fn get_timeout_gap(&self, _ctx: Context) -> ProtocolResult<u64> {
+        Ok(self.timeout_gap)
+    }
+
+    fn set_args(&self, _epoch_id: u64, _state_root: MerkleRoot, _timeout_gap: u64) {}
 }
 
 pub fn default_mock_txs(size: usize) -> Vec<SignedTransaction> {
@@ -114,8 +122,8 @@ fn default_mempool() -> HashMemPool<HashMemPoolAdapter> {
 }
 
 fn new_mempool(pool_size: usize, timeout_gap: u64) -> HashMemPool<HashMemPoolAdapter> {
-    let adapter = HashMemPoolAdapter::new();
-    HashMemPool::new(pool_size, timeout_gap, adapter)
+    let adapter = HashMemPoolAdapter::new(timeout_gap);
+    HashMemPool::new(pool_size, adapter)
 }
 
 fn pub_key_to_address(pub_key: &Secp256k1PublicKey) -> ProtocolResult<Address> {
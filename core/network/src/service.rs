@@ -15,7 +15,7 @@ use futures::{
 };
 use log::{debug, error, info};
 use protocol::{
-    traits::{Context, Gossip, MessageCodec, MessageHandler, Priority, Rpc},
+    traits::{Context, Gossip, MessageCodec, MessageHandler, PeerTrust, Priority, Rpc},
     types::UserAddress,
     ProtocolResult,
 };
@@ -28,13 +28,14 @@ use crate::{
     },
     endpoint::{Endpoint, EndpointScheme},
     error::NetworkError,
-    event::{ConnectionEvent, PeerManagerEvent},
+    event::{ConnectionEvent, PeerManagerEvent, RemoveKind},
     message::RawSessionMessage,
     outbound::{NetworkGossip, NetworkRpc},
     peer_manager::{DiscoveryAddrManager, IdentifyCallback, PeerManager, PeerManagerConfig},
     protocols::CoreProtocol,
     reactor::{MessageRouter, Reactor},
     rpc_map::RpcMap,
+    traits::NetworkContext,
     NetworkConfig,
 };
 
@@ -42,6 +43,7 @@ use crate::{
 pub struct NetworkServiceHandle {
     gossip: NetworkGossip<ConnectionServiceControl<CoreProtocol>, Snappy>,
     rpc:    NetworkRpc<ConnectionServiceControl<CoreProtocol>, Snappy>,
+    mgr_tx: UnboundedSender<PeerManagerEvent>,
 }
 
 #[async_trait]
@@ -86,6 +88,24 @@ impl Rpc for NetworkServiceHandle {
     }
 }
 
+impl PeerTrust for NetworkServiceHandle {
+    fn report_bad(&self, ctx: Context, reason: &str) {
+        let sid = match ctx.session_id() {
+            Ok(sid) => sid,
+            Err(e) => {
+                debug!("network: report bad peer {}", e);
+                return;
+            }
+        };
+
+        let kind = RemoveKind::BadSessionPeer(reason.to_owned());
+        let remove_peer_by_session = PeerManagerEvent::RemovePeerBySession { sid, kind };
+        if self.mgr_tx.unbounded_send(remove_peer_by_session).is_err() {
+            error!("network: peer manager is closed");
+        }
+    }
+}
+
 enum NetworkConnectionService {
     NoListen(ConnectionService<CoreProtocol>), // no listen address yet
     Ready(ConnectionService<CoreProtocol>),
@@ -242,6 +262,7 @@ impl NetworkService {
         NetworkServiceHandle {
             gossip: self.gossip.clone(),
             rpc:    self.rpc.clone(),
+            mgr_tx: self.mgr_tx.clone(),
         }
     }
 
@@ -17,12 +17,15 @@ use protocol::codec::ProtocolCodec;
 use protocol::traits::{
     Storage, StorageAdapter, StorageBatchModify, StorageCategory, StorageSchema,
 };
-use protocol::types::{Epoch, EpochId, Hash, Proof, Receipt, SignedTransaction};
+use protocol::types::{
+    Epoch, EpochId, Hash, MerkleRoot, Proof, Receipt, SignedTransaction, StateDiff,
+};
 use protocol::{ProtocolError, ProtocolErrorKind, ProtocolResult};
 
 lazy_static! {
     pub static ref LATEST_EPOCH_KEY: Hash = Hash::digest(Bytes::from("latest_hash"));
     pub static ref LATEST_PROOF_KEY: Hash = Hash::digest(Bytes::from("latest_proof"));
+    pub static ref GENESIS_HASH_KEY: Hash = Hash::digest(Bytes::from("genesis_hash"));
 }
 
 #[derive(Debug)]
@@ -58,10 +61,13 @@ impl_storage_schema_for!(
     SignedTransaction
 );
 impl_storage_schema_for!(ReceiptSchema, Hash, Receipt, Receipt);
+impl_storage_schema_for!(StateDiffSchema, Hash, StateDiff, StateDiff);
 impl_storage_schema_for!(EpochSchema, EpochId, Epoch, Epoch);
 impl_storage_schema_for!(HashEpochSchema, Hash, EpochId, Epoch);
 impl_storage_schema_for!(LatestEpochSchema, Hash, Epoch, Epoch);
 impl_storage_schema_for!(LatestProofSchema, Hash, Proof, Epoch);
+impl_storage_schema_for!(GenesisHashSchema, Hash, Hash, Epoch);
+impl_storage_schema_for!(ExecutedStateRootSchema, Hash, Hash, Epoch);
 
 macro_rules! batch_insert {
     ($self_: ident,$vec: expr, $schema: ident) => {
@@ -133,6 +139,12 @@ impl<Adapter: StorageAdapter> Storage for ImplStorage<Adapter> {
         Ok(())
     }
 
+    async fn insert_state_diffs(&self, state_diffs: Vec<StateDiff>) -> ProtocolResult<()> {
+        batch_insert!(self, state_diffs, StateDiffSchema);
+
+        Ok(())
+    }
+
     async fn update_latest_proof(&self, proof: Proof) -> ProtocolResult<()> {
         self.adapter
             .insert::<LatestProofSchema>(LATEST_PROOF_KEY.clone(), proof)
@@ -141,6 +153,26 @@ impl<Adapter: StorageAdapter> Storage for ImplStorage<Adapter> {
         Ok(())
     }
 
+    async fn update_genesis_hash(&self, genesis_hash: Hash) -> ProtocolResult<()> {
+        self.adapter
+            .insert::<GenesisHashSchema>(GENESIS_HASH_KEY.clone(), genesis_hash)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn insert_executed_state_root(
+        &self,
+        epoch_hash: Hash,
+        state_root: MerkleRoot,
+    ) -> ProtocolResult<()> {
+        self.adapter
+            .insert::<ExecutedStateRootSchema>(epoch_hash, state_root)
+            .await?;
+
+        Ok(())
+    }
+
     async fn get_transaction_by_hash(&self, tx_hash: Hash) -> ProtocolResult<SignedTransaction> {
         let stx = get!(self, tx_hash, TransactionSchema);
 
@@ -185,11 +217,29 @@ impl<Adapter: StorageAdapter> Storage for ImplStorage<Adapter> {
         Ok(receipts)
     }
 
+    async fn get_state_diff(&self, tx_hash: Hash) -> ProtocolResult<StateDiff> {
+        let state_diff = get!(self, tx_hash, StateDiffSchema);
+
+        Ok(state_diff)
+    }
+
     async fn get_latest_proof(&self) -> ProtocolResult<Proof> {
         let proof = get!(self, LATEST_PROOF_KEY.clone(), LatestProofSchema);
 
         Ok(proof)
     }
+
+    async fn get_genesis_hash(&self) -> ProtocolResult<Hash> {
+        let genesis_hash = get!(self, GENESIS_HASH_KEY.clone(), GenesisHashSchema);
+
+        Ok(genesis_hash)
+    }
+
+    async fn get_executed_state_root(&self, epoch_hash: Hash) -> ProtocolResult<MerkleRoot> {
+        let state_root = get!(self, epoch_hash, ExecutedStateRootSchema);
+
+        Ok(state_root)
+    }
 }
 
 fn opts_to_flat<T>(values: Vec<Option<T>>) -> Vec<T> {
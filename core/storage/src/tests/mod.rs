@@ -14,8 +14,9 @@ use num_traits::FromPrimitive;
 use rand::random;
 
 use protocol::types::{
-    CarryingAsset, Epoch, EpochHeader, Fee, Hash, Proof, RawTransaction, Receipt, ReceiptResult,
-    SignedTransaction, TransactionAction, UserAddress,
+    Address, BalanceDiff, CarryingAsset, Epoch, EpochHeader, Fee, Hash, Proof, RawTransaction,
+    Receipt, ReceiptResult, SignedTransaction, StateDiff, StorageDiff, TransactionAction,
+    UserAddress,
 };
 
 fn mock_signed_tx(tx_hash: Hash) -> SignedTransaction {
@@ -69,6 +70,30 @@ fn mock_receipt(tx_hash: Hash) -> Receipt {
         tx_hash,
         cycles_used,
         result,
+        logs: vec![],
+    }
+}
+
+fn mock_state_diff(tx_hash: Hash) -> StateDiff {
+    let nonce = Hash::digest(Bytes::from("XXXX"));
+    let addr_str = "10CAB8EEA4799C21379C20EF5BAA2CC8AF1BEC475B";
+    let balance = BalanceDiff {
+        address:  Address::from_hex(addr_str).unwrap(),
+        asset_id: nonce,
+        before:   FromPrimitive::from_i32(20).unwrap(),
+        after:    FromPrimitive::from_i32(10).unwrap(),
+    };
+    let storage = StorageDiff {
+        address: Address::from_hex("230000000000000000000000000000000000000002").unwrap(),
+        key:     Bytes::from("key"),
+        before:  Bytes::new(),
+        after:   Bytes::from("value"),
+    };
+
+    StateDiff {
+        tx_hash,
+        balances: vec![balance],
+        storages: vec![storage],
     }
 }
 
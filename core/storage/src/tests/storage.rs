@@ -5,7 +5,9 @@ use protocol::traits::Storage;
 use protocol::types::Hash;
 
 use crate::adapter::memory::MemoryAdapter;
-use crate::tests::{get_random_bytes, mock_epoch, mock_proof, mock_receipt, mock_signed_tx};
+use crate::tests::{
+    get_random_bytes, mock_epoch, mock_proof, mock_receipt, mock_signed_tx, mock_state_diff,
+};
 use crate::ImplStorage;
 
 #[test]
@@ -53,6 +55,22 @@ fn test_storage_receipts_insert() {
     }
 }
 
+#[test]
+fn test_storage_state_diffs_insert() {
+    let storage = ImplStorage::new(Arc::new(MemoryAdapter::new()));
+
+    let state_diffs = (0..10)
+        .map(|_| mock_state_diff(Hash::digest(get_random_bytes(10))))
+        .collect::<Vec<_>>();
+
+    exec!(storage.insert_state_diffs(state_diffs.clone()));
+
+    for state_diff in state_diffs.into_iter() {
+        let state_diff_2 = exec!(storage.get_state_diff(state_diff.tx_hash.clone()));
+        assert_eq!(state_diff, state_diff_2);
+    }
+}
+
 #[test]
 fn test_storage_transactions_insert() {
     let storage = ImplStorage::new(Arc::new(MemoryAdapter::new()));
@@ -90,3 +108,28 @@ fn test_storage_latest_proof_insert() {
 
     assert_eq!(proof.epoch_hash, proof_2.epoch_hash);
 }
+
+#[test]
+fn test_storage_genesis_hash_insert() {
+    let storage = ImplStorage::new(Arc::new(MemoryAdapter::new()));
+
+    let genesis_hash = Hash::digest(get_random_bytes(10));
+
+    exec!(storage.update_genesis_hash(genesis_hash.clone()));
+    let genesis_hash_2 = exec!(storage.get_genesis_hash());
+
+    assert_eq!(genesis_hash, genesis_hash_2);
+}
+
+#[test]
+fn test_storage_executed_state_root_insert() {
+    let storage = ImplStorage::new(Arc::new(MemoryAdapter::new()));
+
+    let epoch_hash = Hash::digest(get_random_bytes(10));
+    let state_root = Hash::digest(get_random_bytes(10));
+
+    exec!(storage.insert_executed_state_root(epoch_hash.clone(), state_root.clone()));
+    let state_root_2 = exec!(storage.get_executed_state_root(epoch_hash));
+
+    assert_eq!(state_root, state_root_2);
+}
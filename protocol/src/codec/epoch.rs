@@ -100,6 +100,9 @@ pub struct Validator {
 
     #[prost(uint32, tag = "3")]
     pub vote_weight: u32,
+
+    #[prost(bytes, tag = "4")]
+    pub bls_pub_key: Vec<u8>,
 }
 
 #[derive(Clone, Message)]
@@ -296,6 +299,7 @@ impl From<epoch::Validator> for Validator {
             address,
             propose_weight: u32::from(validator.propose_weight),
             vote_weight: u32::from(validator.vote_weight),
+            bls_pub_key: validator.bls_pub_key.to_vec(),
         }
     }
 }
@@ -310,6 +314,7 @@ impl TryFrom<Validator> for epoch::Validator {
             address:        protocol_primitive::UserAddress::try_from(address)?,
             propose_weight: validator.propose_weight as u8,
             vote_weight:    validator.vote_weight as u8,
+            bls_pub_key:    Bytes::from(validator.bls_pub_key),
         };
 
         Ok(validator)
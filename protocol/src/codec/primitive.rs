@@ -88,6 +88,12 @@ pub struct Asset {
 
     #[prost(message, tag = "6")]
     pub storage_root: Option<MerkleRoot>,
+
+    #[prost(uint64, tag = "7")]
+    pub decimals: u64,
+
+    #[prost(message, tag = "8")]
+    pub issuer: Option<Address>,
 }
 
 #[derive(Clone, Message)]
@@ -248,6 +254,9 @@ impl From<protocol_primitive::Asset> for Asset {
         let supply = Balance::from(asset.supply);
         let manage_contract = ContractAddress::from(asset.manage_contract);
         let storage_root = MerkleRoot::from(asset.storage_root);
+        let issuer = Address {
+            value: asset.issuer.as_bytes().to_vec(),
+        };
 
         Asset {
             id:              Some(id),
@@ -256,6 +265,8 @@ impl From<protocol_primitive::Asset> for Asset {
             supply:          Some(supply),
             manage_contract: Some(manage_contract),
             storage_root:    Some(storage_root),
+            decimals:        asset.decimals,
+            issuer:          Some(issuer),
         }
     }
 }
@@ -268,6 +279,7 @@ impl TryFrom<Asset> for protocol_primitive::Asset {
         let supply = field!(asset.supply, "Asset", "supply")?;
         let manage_contract = field!(asset.manage_contract, "Asset", "manage_contract")?;
         let storage_root = field!(asset.storage_root, "Asset", "storage_root")?;
+        let issuer = field!(asset.issuer, "Asset", "issuer")?;
 
         let asset = protocol_primitive::Asset {
             id:              protocol_primitive::AssetID::try_from(id)?,
@@ -276,6 +288,8 @@ impl TryFrom<Asset> for protocol_primitive::Asset {
             supply:          protocol_primitive::Balance::try_from(supply)?,
             manage_contract: protocol_primitive::ContractAddress::try_from(manage_contract)?,
             storage_root:    protocol_primitive::MerkleRoot::try_from(storage_root)?,
+            decimals:        asset.decimals,
+            issuer:          protocol_primitive::Address::from_bytes(Bytes::from(issuer.value))?,
         };
 
         Ok(asset)
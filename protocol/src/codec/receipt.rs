@@ -32,8 +32,65 @@ pub struct Receipt {
     #[prost(message, tag = "4")]
     pub cycles_used: Option<Fee>,
 
-    #[prost(oneof = "ReceiptResult", tags = "5, 6, 7, 8, 9")]
+    #[prost(oneof = "ReceiptResult", tags = "5, 6, 7, 8, 9, 11")]
     pub result: Option<ReceiptResult>,
+
+    #[prost(message, repeated, tag = "10")]
+    pub logs: Vec<Log>,
+}
+
+#[derive(Clone, Message)]
+pub struct Log {
+    #[prost(bytes, tag = "1")]
+    pub address: Vec<u8>,
+
+    #[prost(message, repeated, tag = "2")]
+    pub topics: Vec<Hash>,
+
+    #[prost(bytes, tag = "3")]
+    pub data: Vec<u8>,
+}
+
+#[derive(Clone, Message)]
+pub struct StateDiff {
+    #[prost(message, tag = "1")]
+    pub tx_hash: Option<Hash>,
+
+    #[prost(message, repeated, tag = "2")]
+    pub balances: Vec<BalanceDiff>,
+
+    #[prost(message, repeated, tag = "3")]
+    pub storages: Vec<StorageDiff>,
+}
+
+#[derive(Clone, Message)]
+pub struct BalanceDiff {
+    #[prost(bytes, tag = "1")]
+    pub address: Vec<u8>,
+
+    #[prost(message, tag = "2")]
+    pub asset_id: Option<AssetID>,
+
+    #[prost(message, tag = "3")]
+    pub before: Option<Balance>,
+
+    #[prost(message, tag = "4")]
+    pub after: Option<Balance>,
+}
+
+#[derive(Clone, Message)]
+pub struct StorageDiff {
+    #[prost(bytes, tag = "1")]
+    pub address: Vec<u8>,
+
+    #[prost(bytes, tag = "2")]
+    pub key: Vec<u8>,
+
+    #[prost(bytes, tag = "3")]
+    pub before: Vec<u8>,
+
+    #[prost(bytes, tag = "4")]
+    pub after: Vec<u8>,
 }
 
 #[derive(Clone, Oneof)]
@@ -48,6 +105,8 @@ pub enum ReceiptResult {
     Call(Call),
     #[prost(message, tag = "9")]
     Fail(Fail),
+    #[prost(message, tag = "11")]
+    BatchTransfer(BatchTransfer),
 }
 
 #[derive(Clone, Message)]
@@ -62,6 +121,13 @@ pub struct Transfer {
     pub after_amount: Option<Balance>,
 }
 
+// The results of the outputs are encoded as the transfer results.
+#[derive(Clone, Message)]
+pub struct BatchTransfer {
+    #[prost(message, repeated, tag = "1")]
+    pub outputs: Vec<Transfer>,
+}
+
 #[derive(Clone, Message)]
 pub struct Approve {
     #[prost(message, tag = "1")]
@@ -102,6 +168,45 @@ pub struct Fail {
 // Conversion
 // #################
 
+// TransferOutputResult
+
+impl From<receipt::TransferOutputResult> for Transfer {
+    fn from(output: receipt::TransferOutputResult) -> Transfer {
+        Transfer {
+            receiver:      Some(UserAddress::from(output.receiver)),
+            asset_id:      Some(AssetID::from(output.asset_id)),
+            before_amount: Some(Balance::from(output.before_amount)),
+            after_amount:  Some(Balance::from(output.after_amount)),
+        }
+    }
+}
+
+impl TryFrom<Transfer> for receipt::TransferOutputResult {
+    type Error = ProtocolError;
+
+    fn try_from(transfer: Transfer) -> Result<receipt::TransferOutputResult, Self::Error> {
+        let receiver = field!(transfer.receiver, "TransferOutputResult", "receiver")?;
+        let asset_id = field!(transfer.asset_id, "TransferOutputResult", "asset_id")?;
+        let before_amount = field!(
+            transfer.before_amount,
+            "TransferOutputResult",
+            "before_amount"
+        )?;
+        let after_amount = field!(
+            transfer.after_amount,
+            "TransferOutputResult",
+            "after_amount"
+        )?;
+
+        Ok(receipt::TransferOutputResult {
+            receiver:      protocol_primitive::UserAddress::try_from(receiver)?,
+            asset_id:      protocol_primitive::AssetID::try_from(asset_id)?,
+            before_amount: protocol_primitive::Balance::try_from(before_amount)?,
+            after_amount:  protocol_primitive::Balance::try_from(after_amount)?,
+        })
+    }
+}
+
 // ReceiptResult
 
 impl From<receipt::ReceiptResult> for ReceiptResult {
@@ -164,6 +269,13 @@ impl From<receipt::ReceiptResult> for ReceiptResult {
 
                 ReceiptResult::Fail(fail)
             }
+            receipt::ReceiptResult::BatchTransfer { outputs } => {
+                let batch_transfer = BatchTransfer {
+                    outputs: outputs.into_iter().map(Transfer::from).collect(),
+                };
+
+                ReceiptResult::BatchTransfer(batch_transfer)
+            }
         }
     }
 }
@@ -249,6 +361,38 @@ impl TryFrom<ReceiptResult> for receipt::ReceiptResult {
     }
 }
 
+// Log
+
+impl From<receipt::Log> for Log {
+    fn from(log: receipt::Log) -> Log {
+        Log {
+            address: log.address.as_bytes().to_vec(),
+            topics:  log.topics.into_iter().map(Hash::from).collect(),
+            data:    log.data.to_vec(),
+        }
+    }
+}
+
+impl TryFrom<Log> for receipt::Log {
+    type Error = ProtocolError;
+
+    fn try_from(log: Log) -> Result<receipt::Log, Self::Error> {
+        let topics = log
+            .topics
+            .into_iter()
+            .map(protocol_primitive::Hash::try_from)
+            .collect::<Result<Vec<_>, ProtocolError>>()?;
+
+        let log = receipt::Log {
+            address: protocol_primitive::Address::from_bytes(Bytes::from(log.address))?,
+            topics,
+            data: Bytes::from(log.data),
+        };
+
+        Ok(log)
+    }
+}
+
 // Receipt
 
 impl From<receipt::Receipt> for Receipt {
@@ -257,6 +401,7 @@ impl From<receipt::Receipt> for Receipt {
         let tx_hash = Some(Hash::from(receipt.tx_hash));
         let cycles_used = Some(Fee::from(receipt.cycles_used));
         let result = Some(ReceiptResult::from(receipt.result));
+        let logs = receipt.logs.into_iter().map(Log::from).collect();
 
         Receipt {
             state_root,
@@ -264,6 +409,7 @@ impl From<receipt::Receipt> for Receipt {
             tx_hash,
             cycles_used,
             result,
+            logs,
         }
     }
 }
@@ -276,21 +422,121 @@ impl TryFrom<Receipt> for receipt::Receipt {
         let tx_hash = field!(receipt.tx_hash, "Receipt", "tx_hash")?;
         let cycles_used = field!(receipt.cycles_used, "Receipt", "cycles_used")?;
         let result = field!(receipt.result, "Receipt", "result")?;
+        let logs = receipt
+            .logs
+            .into_iter()
+            .map(receipt::Log::try_from)
+            .collect::<Result<Vec<_>, ProtocolError>>()?;
 
         let receipt = receipt::Receipt {
-            state_root:  protocol_primitive::Hash::try_from(state_root)?,
-            epoch_id:    receipt.epoch_id,
-            tx_hash:     protocol_primitive::Hash::try_from(tx_hash)?,
+            state_root: protocol_primitive::Hash::try_from(state_root)?,
+            epoch_id: receipt.epoch_id,
+            tx_hash: protocol_primitive::Hash::try_from(tx_hash)?,
             cycles_used: protocol_primitive::Fee::try_from(cycles_used)?,
-            result:      receipt::ReceiptResult::try_from(result)?,
+            result: receipt::ReceiptResult::try_from(result)?,
+            logs,
         };
 
         Ok(receipt)
     }
 }
 
+// StateDiff
+
+impl From<receipt::BalanceDiff> for BalanceDiff {
+    fn from(diff: receipt::BalanceDiff) -> BalanceDiff {
+        BalanceDiff {
+            address:  diff.address.as_bytes().to_vec(),
+            asset_id: Some(AssetID::from(diff.asset_id)),
+            before:   Some(Balance::from(diff.before)),
+            after:    Some(Balance::from(diff.after)),
+        }
+    }
+}
+
+impl TryFrom<BalanceDiff> for receipt::BalanceDiff {
+    type Error = ProtocolError;
+
+    fn try_from(diff: BalanceDiff) -> Result<receipt::BalanceDiff, Self::Error> {
+        let asset_id = field!(diff.asset_id, "BalanceDiff", "asset_id")?;
+        let before = field!(diff.before, "BalanceDiff", "before")?;
+        let after = field!(diff.after, "BalanceDiff", "after")?;
+
+        let diff = receipt::BalanceDiff {
+            address:  protocol_primitive::Address::from_bytes(Bytes::from(diff.address))?,
+            asset_id: protocol_primitive::AssetID::try_from(asset_id)?,
+            before:   protocol_primitive::Balance::try_from(before)?,
+            after:    protocol_primitive::Balance::try_from(after)?,
+        };
+
+        Ok(diff)
+    }
+}
+
+impl From<receipt::StorageDiff> for StorageDiff {
+    fn from(diff: receipt::StorageDiff) -> StorageDiff {
+        StorageDiff {
+            address: diff.address.as_bytes().to_vec(),
+            key:     diff.key.to_vec(),
+            before:  diff.before.to_vec(),
+            after:   diff.after.to_vec(),
+        }
+    }
+}
+
+impl TryFrom<StorageDiff> for receipt::StorageDiff {
+    type Error = ProtocolError;
+
+    fn try_from(diff: StorageDiff) -> Result<receipt::StorageDiff, Self::Error> {
+        let diff = receipt::StorageDiff {
+            address: protocol_primitive::Address::from_bytes(Bytes::from(diff.address))?,
+            key:     Bytes::from(diff.key),
+            before:  Bytes::from(diff.before),
+            after:   Bytes::from(diff.after),
+        };
+
+        Ok(diff)
+    }
+}
+
+impl From<receipt::StateDiff> for StateDiff {
+    fn from(diff: receipt::StateDiff) -> StateDiff {
+        StateDiff {
+            tx_hash:  Some(Hash::from(diff.tx_hash)),
+            balances: diff.balances.into_iter().map(BalanceDiff::from).collect(),
+            storages: diff.storages.into_iter().map(StorageDiff::from).collect(),
+        }
+    }
+}
+
+impl TryFrom<StateDiff> for receipt::StateDiff {
+    type Error = ProtocolError;
+
+    fn try_from(diff: StateDiff) -> Result<receipt::StateDiff, Self::Error> {
+        let tx_hash = field!(diff.tx_hash, "StateDiff", "tx_hash")?;
+        let balances = diff
+            .balances
+            .into_iter()
+            .map(receipt::BalanceDiff::try_from)
+            .collect::<Result<Vec<_>, ProtocolError>>()?;
+        let storages = diff
+            .storages
+            .into_iter()
+            .map(receipt::StorageDiff::try_from)
+            .collect::<Result<Vec<_>, ProtocolError>>()?;
+
+        let diff = receipt::StateDiff {
+            tx_hash: protocol_primitive::Hash::try_from(tx_hash)?,
+            balances,
+            storages,
+        };
+
+        Ok(diff)
+    }
+}
+
 // #################
 // Codec
 // #################
 
-impl_default_bytes_codec_for!(receipt, [Receipt]);
+impl_default_bytes_codec_for!(receipt, [Receipt, StateDiff]);
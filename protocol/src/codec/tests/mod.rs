@@ -39,12 +39,31 @@ fn test_codec() {
     test!(receipt, ReceiptResult, mock_result, ReceiptType::Deploy);
     test!(receipt, ReceiptResult, mock_result, ReceiptType::Call);
     test!(receipt, ReceiptResult, mock_result, ReceiptType::Fail);
+    test!(
+        receipt,
+        ReceiptResult,
+        mock_result,
+        ReceiptType::BatchTransfer
+    );
     test!(receipt, Receipt, mock_receipt, ReceiptType::Transfer);
+    test!(receipt, StateDiff, mock_state_diff);
 
     test!(transaction, TransactionAction, mock_action, AType::Transfer);
     test!(transaction, TransactionAction, mock_action, AType::Approve);
     test!(transaction, TransactionAction, mock_action, AType::Deploy);
+    test!(
+        transaction,
+        TransactionAction,
+        mock_action,
+        AType::DeployAsset
+    );
     test!(transaction, TransactionAction, mock_action, AType::Call);
+    test!(
+        transaction,
+        TransactionAction,
+        mock_action,
+        AType::BatchTransfer
+    );
     test!(transaction, RawTransaction, mock_raw_tx, AType::Approve);
     test!(transaction, SignedTransaction, mock_sign_tx, AType::Deploy);
 
@@ -43,6 +43,21 @@ pub struct Approve {
     pub max: Option<Balance>,
 }
 
+#[derive(Clone, Message)]
+pub struct AssetParams {
+    #[prost(string, tag = "1")]
+    pub name: String,
+
+    #[prost(string, tag = "2")]
+    pub symbol: String,
+
+    #[prost(message, tag = "3")]
+    pub supply: Option<Balance>,
+
+    #[prost(uint64, tag = "4")]
+    pub decimals: u64,
+}
+
 #[derive(Clone, Message)]
 pub struct Deploy {
     #[prost(bytes, tag = "1")]
@@ -50,6 +65,9 @@ pub struct Deploy {
 
     #[prost(enumeration = "ContractType", tag = "2")]
     pub contract_type: i32,
+
+    #[prost(message, tag = "3")]
+    pub asset_params: Option<AssetParams>,
 }
 
 #[derive(Clone, Message)]
@@ -67,6 +85,13 @@ pub struct Call {
     pub carrying_asset: Option<CarryingAsset>,
 }
 
+// The outputs are encoded as the transfer actions.
+#[derive(Clone, Message)]
+pub struct BatchTransfer {
+    #[prost(message, repeated, tag = "1")]
+    pub outputs: Vec<Transfer>,
+}
+
 #[derive(Clone, Oneof)]
 pub enum TransactionAction {
     #[prost(message, tag = "5")]
@@ -80,6 +105,9 @@ pub enum TransactionAction {
 
     #[prost(message, tag = "8")]
     Call(Call),
+
+    #[prost(message, tag = "9")]
+    BatchTransfer(BatchTransfer),
 }
 
 #[derive(Clone, Message)]
@@ -96,7 +124,7 @@ pub struct RawTransaction {
     #[prost(message, tag = "4")]
     pub fee: Option<Fee>,
 
-    #[prost(oneof = "TransactionAction", tags = "5, 6, 7, 8")]
+    #[prost(oneof = "TransactionAction", tags = "5, 6, 7, 8, 9")]
     pub action: Option<TransactionAction>,
 }
 
@@ -143,6 +171,59 @@ impl TryFrom<CarryingAsset> for transaction::CarryingAsset {
     }
 }
 
+// TransferOutput
+
+impl From<transaction::TransferOutput> for Transfer {
+    fn from(output: transaction::TransferOutput) -> Transfer {
+        Transfer {
+            receiver:       Some(UserAddress::from(output.receiver)),
+            carrying_asset: Some(CarryingAsset::from(output.carrying_asset)),
+        }
+    }
+}
+
+impl TryFrom<Transfer> for transaction::TransferOutput {
+    type Error = ProtocolError;
+
+    fn try_from(transfer: Transfer) -> Result<transaction::TransferOutput, Self::Error> {
+        let receiver = field!(transfer.receiver, "TransferOutput", "receiver")?;
+        let carrying_asset = field!(transfer.carrying_asset, "TransferOutput", "carrying_asset")?;
+
+        Ok(transaction::TransferOutput {
+            receiver:       protocol_primitive::UserAddress::try_from(receiver)?,
+            carrying_asset: transaction::CarryingAsset::try_from(carrying_asset)?,
+        })
+    }
+}
+
+// AssetParams
+
+impl From<transaction::AssetParams> for AssetParams {
+    fn from(params: transaction::AssetParams) -> AssetParams {
+        AssetParams {
+            name:     params.name,
+            symbol:   params.symbol,
+            supply:   Some(Balance::from(params.supply)),
+            decimals: params.decimals,
+        }
+    }
+}
+
+impl TryFrom<AssetParams> for transaction::AssetParams {
+    type Error = ProtocolError;
+
+    fn try_from(params: AssetParams) -> Result<transaction::AssetParams, Self::Error> {
+        let supply = field!(params.supply, "AssetParams", "supply")?;
+
+        Ok(transaction::AssetParams {
+            name:     params.name,
+            symbol:   params.symbol,
+            supply:   protocol_primitive::Balance::try_from(supply)?,
+            decimals: params.decimals,
+        })
+    }
+}
+
 // TransactionAction
 
 impl From<transaction::TransactionAction> for TransactionAction {
@@ -178,10 +259,12 @@ impl From<transaction::TransactionAction> for TransactionAction {
             transaction::TransactionAction::Deploy {
                 code,
                 contract_type,
+                asset_params,
             } => {
                 let deploy = Deploy {
                     code:          code.to_vec(),
                     contract_type: contract_type as i32,
+                    asset_params:  asset_params.map(AssetParams::from),
                 };
 
                 TransactionAction::Deploy(deploy)
@@ -212,6 +295,13 @@ impl From<transaction::TransactionAction> for TransactionAction {
 
                 TransactionAction::Call(call)
             }
+            transaction::TransactionAction::BatchTransfer { outputs } => {
+                let batch_transfer = BatchTransfer {
+                    outputs: outputs.into_iter().map(Transfer::from).collect(),
+                };
+
+                TransactionAction::BatchTransfer(batch_transfer)
+            }
         }
     }
 }
@@ -258,9 +348,15 @@ impl TryFrom<TransactionAction> for transaction::TransactionAction {
                     _ => return Err(CodecError::InvalidContractType(deploy.contract_type).into()),
                 };
 
+                let asset_params = match deploy.asset_params {
+                    Some(params) => Some(transaction::AssetParams::try_from(params)?),
+                    None => None,
+                };
+
                 let action = transaction::TransactionAction::Deploy {
                     code: Bytes::from(deploy.code),
                     contract_type,
+                    asset_params,
                 };
 
                 Ok(action)
@@ -285,6 +381,15 @@ impl TryFrom<TransactionAction> for transaction::TransactionAction {
 
                 Ok(action)
             }
+            TransactionAction::BatchTransfer(batch_transfer) => {
+                let outputs = batch_transfer
+                    .outputs
+                    .into_iter()
+                    .map(transaction::TransferOutput::try_from)
+                    .collect::<ProtocolResult<Vec<_>>>()?;
+
+                Ok(transaction::TransactionAction::BatchTransfer { outputs })
+            }
         }
     }
 }
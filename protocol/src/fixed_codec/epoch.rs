@@ -44,27 +44,30 @@ impl rlp::Decodable for Proof {
 
 impl rlp::Encodable for Validator {
     fn rlp_append(&self, s: &mut rlp::RlpStream) {
-        s.begin_list(3)
+        s.begin_list(4)
             .append(&self.address)
             .append(&self.propose_weight)
-            .append(&self.vote_weight);
+            .append(&self.vote_weight)
+            .append(&self.bls_pub_key.to_vec());
     }
 }
 
 impl rlp::Decodable for Validator {
     fn decode(r: &rlp::Rlp) -> Result<Self, rlp::DecoderError> {
-        if !r.is_list() && r.size() != 3 {
+        if !r.is_list() && r.size() != 4 {
             return Err(rlp::DecoderError::RlpIncorrectListLen);
         }
 
         let address = rlp::decode(r.at(0)?.as_raw())?;
         let propose_weight = r.at(1)?.as_val()?;
         let vote_weight = r.at(2)?.as_val()?;
+        let bls_pub_key = Bytes::from(r.at(3)?.data()?);
 
         Ok(Validator {
             address,
             propose_weight,
             vote_weight,
+            bls_pub_key,
         })
     }
 }
@@ -1,33 +1,41 @@
 use bytes::Bytes;
 
 use crate::fixed_codec::{FixedCodecError, ProtocolFixedCodec};
-use crate::types::genesis::{Genesis, GenesisStateAlloc, GenesisStateAsset, GenesisSystemToken};
+use crate::types::genesis::{
+    Genesis, GenesisAsset, GenesisContract, GenesisParams, GenesisStateAlloc, GenesisStateAsset,
+    GenesisSystemToken, GenesisValidator,
+};
 use crate::{impl_default_fixed_codec_for, ProtocolResult};
 
 impl_default_fixed_codec_for!(genesis, [
     Genesis,
     GenesisStateAlloc,
     GenesisStateAsset,
-    GenesisSystemToken
+    GenesisSystemToken,
+    GenesisValidator,
+    GenesisParams,
+    GenesisAsset,
+    GenesisContract
 ]);
 
 impl rlp::Encodable for GenesisSystemToken {
     fn rlp_append(&self, s: &mut rlp::RlpStream) {
-        s.begin_list(4)
+        s.begin_list(5)
             .append(&self.code.as_bytes())
             .append(&self.name.as_bytes())
             .append(&self.supply)
-            .append(&self.symbol.as_bytes());
+            .append(&self.symbol.as_bytes())
+            .append(&self.decimals);
     }
 }
 
 impl rlp::Decodable for GenesisSystemToken {
     fn decode(r: &rlp::Rlp) -> Result<Self, rlp::DecoderError> {
-        if !r.is_list() && r.size() != 4 {
+        if !r.is_list() && r.size() != 5 {
             return Err(rlp::DecoderError::RlpIncorrectListLen);
         }
 
-        let mut values = Vec::with_capacity(4);
+        let mut values = Vec::with_capacity(5);
 
         for val in r {
             let data = val.data()?;
@@ -41,12 +49,14 @@ impl rlp::Decodable for GenesisSystemToken {
         let supply: u64 = r.at(2)?.as_val()?;
         let symbol = String::from_utf8(values[3].to_vec())
             .map_err(|_| rlp::DecoderError::RlpInvalidLength)?;
+        let decimals: u64 = r.at(4)?.as_val()?;
 
         Ok(GenesisSystemToken {
             code,
             name,
             supply,
             symbol,
+            decimals,
         })
     }
 }
@@ -96,19 +106,147 @@ impl rlp::Decodable for GenesisStateAlloc {
     }
 }
 
-impl rlp::Encodable for Genesis {
+impl rlp::Encodable for GenesisValidator {
+    fn rlp_append(&self, s: &mut rlp::RlpStream) {
+        s.begin_list(5)
+            .append(&self.address.as_bytes())
+            .append(&self.propose_weight)
+            .append(&self.vote_weight)
+            .append(&self.bls_pub_key.as_bytes())
+            .append(&self.bls_pop.as_bytes());
+    }
+}
+
+impl rlp::Decodable for GenesisValidator {
+    fn decode(r: &rlp::Rlp) -> Result<Self, rlp::DecoderError> {
+        if !r.is_list() && r.size() != 5 {
+            return Err(rlp::DecoderError::RlpIncorrectListLen);
+        }
+
+        let address = String::from_utf8(r.at(0)?.data()?.to_vec())
+            .map_err(|_| rlp::DecoderError::RlpInvalidLength)?;
+        let propose_weight = r.at(1)?.as_val()?;
+        let vote_weight = r.at(2)?.as_val()?;
+        let bls_pub_key = String::from_utf8(r.at(3)?.data()?.to_vec())
+            .map_err(|_| rlp::DecoderError::RlpInvalidLength)?;
+        let bls_pop = String::from_utf8(r.at(4)?.data()?.to_vec())
+            .map_err(|_| rlp::DecoderError::RlpInvalidLength)?;
+
+        Ok(GenesisValidator {
+            address,
+            propose_weight,
+            vote_weight,
+            bls_pub_key,
+            bls_pop,
+        })
+    }
+}
+
+impl rlp::Encodable for GenesisParams {
     fn rlp_append(&self, s: &mut rlp::RlpStream) {
         s.begin_list(4)
+            .append(&self.cycles_limit)
+            .append(&self.cycles_price)
+            .append(&self.interval)
+            .append(&self.timeout_gap);
+    }
+}
+
+impl rlp::Decodable for GenesisParams {
+    fn decode(r: &rlp::Rlp) -> Result<Self, rlp::DecoderError> {
+        if !r.is_list() && r.size() != 4 {
+            return Err(rlp::DecoderError::RlpIncorrectListLen);
+        }
+
+        let cycles_limit = r.at(0)?.as_val()?;
+        let cycles_price = r.at(1)?.as_val()?;
+        let interval = r.at(2)?.as_val()?;
+        let timeout_gap = r.at(3)?.as_val()?;
+
+        Ok(GenesisParams {
+            cycles_limit,
+            cycles_price,
+            timeout_gap,
+            interval,
+        })
+    }
+}
+
+impl rlp::Encodable for GenesisAsset {
+    fn rlp_append(&self, s: &mut rlp::RlpStream) {
+        s.begin_list(5)
+            .append(&self.code.as_bytes())
+            .append(&self.name.as_bytes())
+            .append(&self.supply)
+            .append(&self.symbol.as_bytes())
+            .append(&self.decimals);
+    }
+}
+
+impl rlp::Decodable for GenesisAsset {
+    fn decode(r: &rlp::Rlp) -> Result<Self, rlp::DecoderError> {
+        if !r.is_list() && r.size() != 5 {
+            return Err(rlp::DecoderError::RlpIncorrectListLen);
+        }
+
+        let code = String::from_utf8(r.at(0)?.data()?.to_vec())
+            .map_err(|_| rlp::DecoderError::RlpInvalidLength)?;
+        let name = String::from_utf8(r.at(1)?.data()?.to_vec())
+            .map_err(|_| rlp::DecoderError::RlpInvalidLength)?;
+        let supply: u64 = r.at(2)?.as_val()?;
+        let symbol = String::from_utf8(r.at(3)?.data()?.to_vec())
+            .map_err(|_| rlp::DecoderError::RlpInvalidLength)?;
+        let decimals: u64 = r.at(4)?.as_val()?;
+
+        Ok(GenesisAsset {
+            code,
+            name,
+            symbol,
+            supply,
+            decimals,
+        })
+    }
+}
+
+impl rlp::Encodable for GenesisContract {
+    fn rlp_append(&self, s: &mut rlp::RlpStream) {
+        s.begin_list(2)
+            .append(&self.code.as_bytes())
+            .append(&self.library);
+    }
+}
+
+impl rlp::Decodable for GenesisContract {
+    fn decode(r: &rlp::Rlp) -> Result<Self, rlp::DecoderError> {
+        if !r.is_list() && r.size() != 2 {
+            return Err(rlp::DecoderError::RlpIncorrectListLen);
+        }
+
+        let code = String::from_utf8(r.at(0)?.data()?.to_vec())
+            .map_err(|_| rlp::DecoderError::RlpInvalidLength)?;
+        let library = r.at(1)?.as_val()?;
+
+        Ok(GenesisContract { code, library })
+    }
+}
+
+impl rlp::Encodable for Genesis {
+    fn rlp_append(&self, s: &mut rlp::RlpStream) {
+        s.begin_list(8)
             .append(&self.prevhash.as_bytes())
             .append_list(&self.state_alloc)
             .append(&self.system_token)
-            .append(&self.timestamp);
+            .append(&self.timestamp)
+            .append_list(&self.validators)
+            .append(&self.params)
+            .append_list(&self.assets)
+            .append_list(&self.contracts);
     }
 }
 
 impl rlp::Decodable for Genesis {
     fn decode(r: &rlp::Rlp) -> Result<Self, rlp::DecoderError> {
-        if !r.is_list() && r.size() != 4 {
+        if !r.is_list() && r.size() != 8 {
             return Err(rlp::DecoderError::RlpIncorrectListLen);
         }
 
@@ -117,12 +255,20 @@ impl rlp::Decodable for Genesis {
         let state_alloc: Vec<GenesisStateAlloc> = rlp::decode_list(r.at(1)?.as_raw());
         let system_token = rlp::decode(r.at(2)?.as_raw())?;
         let timestamp = r.at(3)?.as_val()?;
+        let validators: Vec<GenesisValidator> = rlp::decode_list(r.at(4)?.as_raw());
+        let params = rlp::decode(r.at(5)?.as_raw())?;
+        let assets: Vec<GenesisAsset> = rlp::decode_list(r.at(6)?.as_raw());
+        let contracts: Vec<GenesisContract> = rlp::decode_list(r.at(7)?.as_raw());
 
         Ok(Genesis {
             timestamp,
             prevhash,
             system_token,
             state_alloc,
+            validators,
+            params,
+            assets,
+            contracts,
         })
     }
 }
@@ -0,0 +1,144 @@
+use bytes::Bytes;
+
+use crate::fixed_codec::{FixedCodecError, ProtocolFixedCodec};
+use crate::types::governance::{ChainParams, GovernanceAction, GovernanceProposal, ValidatorSet};
+use crate::types::{Hash, UserAddress, Validator};
+use crate::{impl_default_fixed_codec_for, ProtocolResult};
+
+impl_default_fixed_codec_for!(governance, [
+    ValidatorSet,
+    ChainParams,
+    GovernanceAction,
+    GovernanceProposal
+]);
+
+const UPDATE_VALIDATORS_ACTION_FLAG: u8 = 0;
+const UPDATE_PARAMS_ACTION_FLAG: u8 = 1;
+
+impl rlp::Encodable for ValidatorSet {
+    fn rlp_append(&self, s: &mut rlp::RlpStream) {
+        s.begin_list(2)
+            .append(&self.version)
+            .append_list(&self.validators);
+    }
+}
+
+impl rlp::Decodable for ValidatorSet {
+    fn decode(r: &rlp::Rlp) -> Result<Self, rlp::DecoderError> {
+        if !r.is_list() && r.size() != 2 {
+            return Err(rlp::DecoderError::RlpIncorrectListLen);
+        }
+
+        let version = r.at(0)?.as_val()?;
+        let validators: Vec<Validator> = rlp::decode_list(r.at(1)?.as_raw());
+
+        Ok(ValidatorSet {
+            version,
+            validators,
+        })
+    }
+}
+
+impl rlp::Encodable for ChainParams {
+    fn rlp_append(&self, s: &mut rlp::RlpStream) {
+        s.begin_list(4)
+            .append(&self.cycles_limit)
+            .append(&self.cycles_price)
+            .append(&self.interval)
+            .append(&self.timeout_gap);
+    }
+}
+
+impl rlp::Decodable for ChainParams {
+    fn decode(r: &rlp::Rlp) -> Result<Self, rlp::DecoderError> {
+        if !r.is_list() && r.size() != 4 {
+            return Err(rlp::DecoderError::RlpIncorrectListLen);
+        }
+
+        let cycles_limit = r.at(0)?.as_val()?;
+        let cycles_price = r.at(1)?.as_val()?;
+        let interval = r.at(2)?.as_val()?;
+        let timeout_gap = r.at(3)?.as_val()?;
+
+        Ok(ChainParams {
+            cycles_limit,
+            cycles_price,
+            interval,
+            timeout_gap,
+        })
+    }
+}
+
+impl rlp::Encodable for GovernanceAction {
+    fn rlp_append(&self, s: &mut rlp::RlpStream) {
+        match self {
+            GovernanceAction::UpdateValidators(validators) => {
+                s.begin_list(2)
+                    .append(&UPDATE_VALIDATORS_ACTION_FLAG)
+                    .append_list(validators);
+            }
+            GovernanceAction::UpdateParams(params) => {
+                s.begin_list(2)
+                    .append(&UPDATE_PARAMS_ACTION_FLAG)
+                    .append(params);
+            }
+        }
+    }
+}
+
+impl rlp::Decodable for GovernanceAction {
+    fn decode(r: &rlp::Rlp) -> Result<Self, rlp::DecoderError> {
+        if !r.is_list() && r.size() != 2 {
+            return Err(rlp::DecoderError::RlpIncorrectListLen);
+        }
+
+        let flag: u8 = r.at(0)?.as_val()?;
+        match flag {
+            UPDATE_VALIDATORS_ACTION_FLAG => {
+                let validators: Vec<Validator> = rlp::decode_list(r.at(1)?.as_raw());
+                Ok(GovernanceAction::UpdateValidators(validators))
+            }
+            UPDATE_PARAMS_ACTION_FLAG => {
+                let params: ChainParams = rlp::decode(r.at(1)?.as_raw())?;
+                Ok(GovernanceAction::UpdateParams(params))
+            }
+            _ => Err(rlp::DecoderError::Custom("invalid governance action flag")),
+        }
+    }
+}
+
+impl rlp::Encodable for GovernanceProposal {
+    fn rlp_append(&self, s: &mut rlp::RlpStream) {
+        s.begin_list(6)
+            .append(&self.id)
+            .append(&self.proposer)
+            .append(&self.action)
+            .append(&self.validator_version)
+            .append_list(&self.approvals)
+            .append(&self.executed);
+    }
+}
+
+impl rlp::Decodable for GovernanceProposal {
+    fn decode(r: &rlp::Rlp) -> Result<Self, rlp::DecoderError> {
+        if !r.is_list() && r.size() != 6 {
+            return Err(rlp::DecoderError::RlpIncorrectListLen);
+        }
+
+        let id: Hash = rlp::decode(r.at(0)?.as_raw())?;
+        let proposer: UserAddress = rlp::decode(r.at(1)?.as_raw())?;
+        let action: GovernanceAction = rlp::decode(r.at(2)?.as_raw())?;
+        let validator_version = r.at(3)?.as_val()?;
+        let approvals: Vec<UserAddress> = rlp::decode_list(r.at(4)?.as_raw());
+        let executed = r.at(5)?.as_val()?;
+
+        Ok(GovernanceProposal {
+            id,
+            proposer,
+            action,
+            validator_version,
+            approvals,
+            executed,
+        })
+    }
+}
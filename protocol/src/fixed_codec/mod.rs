@@ -2,6 +2,8 @@
 mod r#macro;
 pub mod epoch;
 pub mod genesis;
+pub mod governance;
+pub mod multisig;
 pub mod primitive;
 pub mod receipt;
 #[cfg(test)]
@@ -0,0 +1,87 @@
+use bytes::Bytes;
+
+use crate::fixed_codec::{FixedCodecError, ProtocolFixedCodec};
+use crate::types::multisig::{MultisigOwner, MultisigProposal, MultisigWallet};
+use crate::types::{Hash, RawTransaction, UserAddress};
+use crate::{impl_default_fixed_codec_for, ProtocolResult};
+
+impl_default_fixed_codec_for!(multisig, [MultisigOwner, MultisigWallet, MultisigProposal]);
+
+impl rlp::Encodable for MultisigOwner {
+    fn rlp_append(&self, s: &mut rlp::RlpStream) {
+        s.begin_list(2).append(&self.address).append(&self.weight);
+    }
+}
+
+impl rlp::Decodable for MultisigOwner {
+    fn decode(r: &rlp::Rlp) -> Result<Self, rlp::DecoderError> {
+        if !r.is_list() && r.size() != 2 {
+            return Err(rlp::DecoderError::RlpIncorrectListLen);
+        }
+
+        let address: UserAddress = rlp::decode(r.at(0)?.as_raw())?;
+        let weight = r.at(1)?.as_val()?;
+
+        Ok(MultisigOwner { address, weight })
+    }
+}
+
+impl rlp::Encodable for MultisigWallet {
+    fn rlp_append(&self, s: &mut rlp::RlpStream) {
+        s.begin_list(3)
+            .append(&self.address)
+            .append_list(&self.owners)
+            .append(&self.threshold);
+    }
+}
+
+impl rlp::Decodable for MultisigWallet {
+    fn decode(r: &rlp::Rlp) -> Result<Self, rlp::DecoderError> {
+        if !r.is_list() && r.size() != 3 {
+            return Err(rlp::DecoderError::RlpIncorrectListLen);
+        }
+
+        let address: UserAddress = rlp::decode(r.at(0)?.as_raw())?;
+        let owners: Vec<MultisigOwner> = rlp::decode_list(r.at(1)?.as_raw());
+        let threshold = r.at(2)?.as_val()?;
+
+        Ok(MultisigWallet {
+            address,
+            owners,
+            threshold,
+        })
+    }
+}
+
+impl rlp::Encodable for MultisigProposal {
+    fn rlp_append(&self, s: &mut rlp::RlpStream) {
+        s.begin_list(5)
+            .append(&self.id)
+            .append(&self.wallet)
+            .append(&self.raw)
+            .append_list(&self.approvals)
+            .append(&self.executed);
+    }
+}
+
+impl rlp::Decodable for MultisigProposal {
+    fn decode(r: &rlp::Rlp) -> Result<Self, rlp::DecoderError> {
+        if !r.is_list() && r.size() != 5 {
+            return Err(rlp::DecoderError::RlpIncorrectListLen);
+        }
+
+        let id: Hash = rlp::decode(r.at(0)?.as_raw())?;
+        let wallet: UserAddress = rlp::decode(r.at(1)?.as_raw())?;
+        let raw: RawTransaction = rlp::decode(r.at(2)?.as_raw())?;
+        let approvals: Vec<UserAddress> = rlp::decode_list(r.at(3)?.as_raw());
+        let executed = r.at(4)?.as_val()?;
+
+        Ok(MultisigProposal {
+            id,
+            wallet,
+            raw,
+            approvals,
+            executed,
+        })
+    }
+}
@@ -47,19 +47,21 @@ impl rlp::Decodable for Hash {
 
 impl rlp::Encodable for Asset {
     fn rlp_append(&self, s: &mut rlp::RlpStream) {
-        s.begin_list(6)
+        s.begin_list(8)
             .append(&self.id)
             .append(&self.manage_contract)
             .append(&self.name.as_bytes())
             .append(&self.storage_root)
             .append(&self.supply.to_bytes_be())
-            .append(&self.symbol.as_bytes());
+            .append(&self.symbol.as_bytes())
+            .append(&self.decimals)
+            .append(&self.issuer);
     }
 }
 
 impl rlp::Decodable for Asset {
     fn decode(r: &rlp::Rlp) -> Result<Self, rlp::DecoderError> {
-        if !r.is_list() && r.size() != 6 {
+        if !r.is_list() && r.size() != 8 {
             return Err(rlp::DecoderError::RlpIncorrectListLen);
         }
 
@@ -71,6 +73,8 @@ impl rlp::Decodable for Asset {
         let supply = Balance::from_bytes_be(r.at(4)?.data()?);
         let symbol = String::from_utf8(r.at(5)?.data()?.to_vec())
             .map_err(|_| rlp::DecoderError::RlpInvalidLength)?;
+        let decimals = r.at(6)?.as_val()?;
+        let issuer = rlp::decode(r.at(7)?.as_raw())?;
 
         Ok(Asset {
             id,
@@ -79,6 +83,8 @@ impl rlp::Decodable for Asset {
             storage_root,
             supply,
             symbol,
+            decimals,
+            issuer,
         })
     }
 }
@@ -161,7 +167,7 @@ impl rlp::Encodable for Account {
     fn rlp_append(&self, s: &mut rlp::RlpStream) {
         match self {
             Account::User(user) => {
-                s.begin_list(3);
+                s.begin_list(4);
                 s.append(&USER_ACCOUNT_FLAG);
 
                 let mut asset_list = Vec::with_capacity(user.assets.len());
@@ -178,6 +184,19 @@ impl rlp::Encodable for Account {
 
                 s.append_list(&asset_list);
                 s.append(&user.nonce);
+
+                let mut nonce_list = Vec::with_capacity(user.used_nonces.len());
+
+                for (nonce, timeout) in user.used_nonces.iter() {
+                    let used_nonce = FixedUsedNonce {
+                        nonce:   nonce.clone(),
+                        timeout: *timeout,
+                    };
+
+                    nonce_list.push(used_nonce);
+                }
+
+                s.append_list(&nonce_list);
             }
             Account::Contract(contract) => {
                 s.begin_list(4);
@@ -220,7 +239,19 @@ impl rlp::Decodable for Account {
                     });
                 }
 
-                Ok(Account::User(UserAccount { nonce, assets }))
+                let nonce_list: Vec<FixedUsedNonce> = rlp::decode_list(r.at(3)?.as_raw());
+
+                let mut used_nonces = BTreeMap::new();
+
+                for v in nonce_list.into_iter() {
+                    used_nonces.insert(v.nonce, v.timeout);
+                }
+
+                Ok(Account::User(UserAccount {
+                    nonce,
+                    assets,
+                    used_nonces,
+                }))
             }
             CONTRACT_ACCOUNT_FLAG => {
                 let asset_list: Vec<FixedContractAsset> = rlp::decode_list(r.at(1)?.as_raw());
@@ -296,6 +327,27 @@ impl rlp::Decodable for FixedUserAsset {
     }
 }
 
+#[derive(Clone, Debug)]
+pub struct FixedUsedNonce {
+    pub nonce:   Hash,
+    pub timeout: u64,
+}
+
+impl rlp::Encodable for FixedUsedNonce {
+    fn rlp_append(&self, s: &mut rlp::RlpStream) {
+        s.begin_list(2).append(&self.nonce).append(&self.timeout);
+    }
+}
+
+impl rlp::Decodable for FixedUsedNonce {
+    fn decode(r: &rlp::Rlp) -> Result<Self, rlp::DecoderError> {
+        let nonce = rlp::decode(r.at(0)?.as_raw())?;
+        let timeout = r.at(1)?.as_val()?;
+
+        Ok(FixedUsedNonce { nonce, timeout })
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct FixedUserAssetApproved {
     pub contract_address: ContractAddress,
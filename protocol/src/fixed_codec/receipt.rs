@@ -1,8 +1,8 @@
 use bytes::Bytes;
 
 use crate::fixed_codec::{FixedCodecError, ProtocolFixedCodec};
-use crate::types::primitive::{Balance, ContractType, Fee};
-use crate::types::receipt::{Receipt, ReceiptResult};
+use crate::types::primitive::{Balance, ContractType, Fee, Hash};
+use crate::types::receipt::{Log, Receipt, ReceiptResult, TransferOutputResult};
 use crate::{impl_default_fixed_codec_for, ProtocolResult};
 
 // Impl ProtocolFixedCodec trait for types
@@ -10,18 +10,19 @@ impl_default_fixed_codec_for!(receipt, [Receipt, ReceiptResult]);
 
 impl rlp::Encodable for Receipt {
     fn rlp_append(&self, s: &mut rlp::RlpStream) {
-        s.begin_list(5)
+        s.begin_list(6)
             .append(&self.cycles_used)
             .append(&self.epoch_id)
             .append(&self.result)
             .append(&self.state_root)
-            .append(&self.tx_hash);
+            .append(&self.tx_hash)
+            .append_list(&self.logs);
     }
 }
 
 impl rlp::Decodable for Receipt {
     fn decode(r: &rlp::Rlp) -> Result<Self, rlp::DecoderError> {
-        if !r.is_list() && r.size() != 5 {
+        if !r.is_list() && r.size() != 6 {
             return Err(rlp::DecoderError::RlpIncorrectListLen);
         }
 
@@ -30,6 +31,7 @@ impl rlp::Decodable for Receipt {
         let result: ReceiptResult = rlp::decode(r.at(2)?.as_raw())?;
         let state_root = rlp::decode(r.at(3)?.as_raw())?;
         let tx_hash = rlp::decode(r.at(4)?.as_raw())?;
+        let logs: Vec<Log> = rlp::decode_list(r.at(5)?.as_raw());
 
         Ok(Receipt {
             state_root,
@@ -37,6 +39,34 @@ impl rlp::Decodable for Receipt {
             tx_hash,
             cycles_used,
             result,
+            logs,
+        })
+    }
+}
+
+impl rlp::Encodable for Log {
+    fn rlp_append(&self, s: &mut rlp::RlpStream) {
+        s.begin_list(3)
+            .append(&self.address)
+            .append(&self.data.to_vec())
+            .append_list(&self.topics);
+    }
+}
+
+impl rlp::Decodable for Log {
+    fn decode(r: &rlp::Rlp) -> Result<Self, rlp::DecoderError> {
+        if !r.is_list() && r.size() != 3 {
+            return Err(rlp::DecoderError::RlpIncorrectListLen);
+        }
+
+        let address = rlp::decode(r.at(0)?.as_raw())?;
+        let data = Bytes::from(r.at(1)?.data()?);
+        let topics: Vec<Hash> = rlp::decode_list(r.at(2)?.as_raw());
+
+        Ok(Log {
+            address,
+            topics,
+            data,
         })
     }
 }
@@ -45,6 +75,8 @@ const TRANSFER_RESULT_FLAG: u8 = 0;
 const DEPLOY_RESULT_FLAG: u8 = 1;
 const CALL_RESULT_FLAG: u8 = 2;
 const FAIL_RESULT_FLAG: u8 = 3;
+const APPROVE_RESULT_FLAG: u8 = 4;
+const BATCH_TRANSFER_RESULT_FLAG: u8 = 5;
 
 impl rlp::Encodable for ReceiptResult {
     fn rlp_append(&self, s: &mut rlp::RlpStream) {
@@ -62,6 +94,17 @@ impl rlp::Encodable for ReceiptResult {
                     .append(&before_amount.to_bytes_be())
                     .append(receiver);
             }
+            ReceiptResult::Approve {
+                spender,
+                asset_id,
+                max,
+            } => {
+                s.begin_list(4)
+                    .append(&APPROVE_RESULT_FLAG)
+                    .append(asset_id)
+                    .append(&max.to_bytes_be())
+                    .append(spender);
+            }
             ReceiptResult::Deploy {
                 contract,
                 contract_type,
@@ -93,7 +136,11 @@ impl rlp::Encodable for ReceiptResult {
                     .append(&system.as_bytes())
                     .append(&user.as_bytes());
             }
-            _ => {}
+            ReceiptResult::BatchTransfer { outputs } => {
+                s.begin_list(2)
+                    .append(&BATCH_TRANSFER_RESULT_FLAG)
+                    .append_list(outputs);
+            }
         }
     }
 }
@@ -116,6 +163,17 @@ impl rlp::Decodable for ReceiptResult {
                     after_amount,
                 })
             }
+            APPROVE_RESULT_FLAG => {
+                let asset_id = rlp::decode(r.at(1)?.as_raw())?;
+                let max = Balance::from_bytes_be(r.at(2)?.data()?);
+                let spender = rlp::decode(r.at(3)?.as_raw())?;
+
+                Ok(ReceiptResult::Approve {
+                    spender,
+                    asset_id,
+                    max,
+                })
+            }
             DEPLOY_RESULT_FLAG => {
                 let contract = rlp::decode(r.at(1)?.as_raw())?;
                 let contract_type_flag: u8 = r.at(2)?.as_val()?;
@@ -152,7 +210,43 @@ impl rlp::Decodable for ReceiptResult {
 
                 Ok(ReceiptResult::Fail { system, user })
             }
+            BATCH_TRANSFER_RESULT_FLAG => {
+                let outputs: Vec<TransferOutputResult> = r.at(1)?.as_list()?;
+
+                Ok(ReceiptResult::BatchTransfer { outputs })
+            }
             _ => Err(rlp::DecoderError::RlpListLenWithZeroPrefix),
         }
     }
 }
+
+// The fields are in the same order as the transfer result.
+impl rlp::Encodable for TransferOutputResult {
+    fn rlp_append(&self, s: &mut rlp::RlpStream) {
+        s.begin_list(4)
+            .append(&self.after_amount.to_bytes_be())
+            .append(&self.asset_id)
+            .append(&self.before_amount.to_bytes_be())
+            .append(&self.receiver);
+    }
+}
+
+impl rlp::Decodable for TransferOutputResult {
+    fn decode(r: &rlp::Rlp) -> Result<Self, rlp::DecoderError> {
+        if !r.is_list() && r.size() != 4 {
+            return Err(rlp::DecoderError::RlpIncorrectListLen);
+        }
+
+        let after_amount = Balance::from_bytes_be(r.at(0)?.data()?);
+        let asset_id = rlp::decode(r.at(1)?.as_raw())?;
+        let before_amount = Balance::from_bytes_be(r.at(2)?.data()?);
+        let receiver = rlp::decode(r.at(3)?.as_raw())?;
+
+        Ok(TransferOutputResult {
+            receiver,
+            asset_id,
+            before_amount,
+            after_amount,
+        })
+    }
+}
@@ -27,8 +27,16 @@ fn test_fixed_codec() {
     test_eq!(primitive, Account, mock_account_contract);
 
     test_eq!(transaction, RawTransaction, mock_raw_tx, AType::Transfer);
+    test_eq!(transaction, RawTransaction, mock_raw_tx, AType::Approve);
     test_eq!(transaction, RawTransaction, mock_raw_tx, AType::Deploy);
+    test_eq!(transaction, RawTransaction, mock_raw_tx, AType::DeployAsset);
     test_eq!(transaction, RawTransaction, mock_raw_tx, AType::Call);
+    test_eq!(
+        transaction,
+        RawTransaction,
+        mock_raw_tx,
+        AType::BatchTransfer
+    );
 
     test_eq!(
         transaction,
@@ -36,7 +44,14 @@ fn test_fixed_codec() {
         mock_sign_tx,
         AType::Transfer
     );
+    test_eq!(transaction, SignedTransaction, mock_sign_tx, AType::Approve);
     test_eq!(transaction, SignedTransaction, mock_sign_tx, AType::Deploy);
+    test_eq!(
+        transaction,
+        SignedTransaction,
+        mock_sign_tx,
+        AType::DeployAsset
+    );
     test_eq!(transaction, SignedTransaction, mock_sign_tx, AType::Call);
 
     test_eq!(epoch, Proof, mock_proof);
@@ -47,11 +62,20 @@ fn test_fixed_codec() {
     test_eq!(epoch, EpochId, mock_epoch_id);
 
     test_eq!(receipt, Receipt, mock_receipt, ReceiptType::Transfer);
+    test_eq!(receipt, Receipt, mock_receipt, ReceiptType::Approve);
     test_eq!(receipt, Receipt, mock_receipt, ReceiptType::Deploy);
     test_eq!(receipt, Receipt, mock_receipt, ReceiptType::Call);
     test_eq!(receipt, Receipt, mock_receipt, ReceiptType::Fail);
+    test_eq!(receipt, Receipt, mock_receipt, ReceiptType::BatchTransfer);
 
     test_eq!(genesis, Genesis, mock_genesis);
+
+    test_eq!(governance, ValidatorSet, mock_validator_set);
+    test_eq!(governance, ChainParams, mock_chain_params);
+    test_eq!(governance, GovernanceProposal, mock_governance_proposal);
+
+    test_eq!(multisig, MultisigWallet, mock_multisig_wallet);
+    test_eq!(multisig, MultisigProposal, mock_multisig_proposal);
 }
 
 #[test]
@@ -7,14 +7,22 @@ use num_traits::FromPrimitive;
 use rand::random;
 
 use crate::types::epoch::{Epoch, EpochHeader, EpochId, Pill, Proof, Validator};
-use crate::types::genesis::{Genesis, GenesisStateAlloc, GenesisStateAsset, GenesisSystemToken};
+use crate::types::genesis::{
+    Genesis, GenesisAsset, GenesisContract, GenesisParams, GenesisStateAlloc, GenesisStateAsset,
+    GenesisSystemToken, GenesisValidator,
+};
+use crate::types::governance::{ChainParams, GovernanceAction, GovernanceProposal, ValidatorSet};
+use crate::types::multisig::{MultisigOwner, MultisigProposal, MultisigWallet};
 use crate::types::primitive::{
-    Account, Asset, AssetID, AssetInfo, Balance, ContractAccount, ContractAddress, ContractType,
-    Fee, Hash, MerkleRoot, UserAccount, UserAddress,
+    Account, Address, Asset, AssetID, AssetInfo, Balance, ContractAccount, ContractAddress,
+    ContractType, Fee, Hash, MerkleRoot, UserAccount, UserAddress,
+};
+use crate::types::receipt::{
+    BalanceDiff, Log, Receipt, ReceiptResult, StateDiff, StorageDiff, TransferOutputResult,
 };
-use crate::types::receipt::{Receipt, ReceiptResult};
 use crate::types::transaction::{
-    CarryingAsset, RawTransaction, SignedTransaction, TransactionAction,
+    AssetParams, CarryingAsset, RawTransaction, SignedTransaction, TransactionAction,
+    TransferOutput,
 };
 
 pub enum ReceiptType {
@@ -23,13 +31,16 @@ pub enum ReceiptType {
     Deploy,
     Call,
     Fail,
+    BatchTransfer,
 }
 
 pub enum AType {
     Transfer,
     Approve,
     Deploy,
+    DeployAsset,
     Call,
+    BatchTransfer,
 }
 
 // #####################
@@ -66,8 +77,10 @@ pub fn mock_asset() -> Asset {
         name:            "test".to_string(),
         symbol:          "MT".to_string(),
         supply:          mock_balance(),
+        decimals:        8,
         manage_contract: mock_contract_address(),
         storage_root:    mock_merkle_root(),
+        issuer:          Address::User(mock_account_address()),
     }
 }
 
@@ -87,9 +100,13 @@ pub fn mock_account_contract() -> Account {
 }
 
 pub fn mock_user_account() -> UserAccount {
+    let mut used_nonces = BTreeMap::new();
+    used_nonces.insert(mock_hash(), 10);
+
     UserAccount {
-        nonce:  8,
+        nonce: 8,
         assets: BTreeMap::default(),
+        used_nonces,
     }
 }
 
@@ -138,6 +155,16 @@ pub fn mock_result(rtype: ReceiptType) -> ReceiptResult {
             system: "system".to_string(),
             user:   "user".to_string(),
         },
+        ReceiptType::BatchTransfer => ReceiptResult::BatchTransfer {
+            outputs: (0..3)
+                .map(|_| TransferOutputResult {
+                    receiver:      mock_account_address(),
+                    asset_id:      mock_asset_id(),
+                    before_amount: mock_balance(),
+                    after_amount:  mock_balance(),
+                })
+                .collect(),
+        },
     }
 }
 
@@ -148,6 +175,37 @@ pub fn mock_receipt(rtype: ReceiptType) -> Receipt {
         tx_hash:     mock_hash(),
         cycles_used: mock_fee(),
         result:      mock_result(rtype),
+        logs:        vec![mock_log(), mock_log()],
+    }
+}
+
+pub fn mock_state_diff() -> StateDiff {
+    StateDiff {
+        tx_hash:  mock_hash(),
+        balances: (0..3)
+            .map(|_| BalanceDiff {
+                address:  Address::User(mock_account_address()),
+                asset_id: mock_asset_id(),
+                before:   mock_balance(),
+                after:    mock_balance(),
+            })
+            .collect(),
+        storages: (0..3)
+            .map(|_| StorageDiff {
+                address: Address::Contract(mock_contract_address()),
+                key:     get_random_bytes(32),
+                before:  get_random_bytes(64),
+                after:   get_random_bytes(64),
+            })
+            .collect(),
+    }
+}
+
+pub fn mock_log() -> Log {
+    Log {
+        address: Address::Contract(mock_contract_address()),
+        topics:  vec![mock_hash(), mock_hash()],
+        data:    get_random_bytes(32),
     }
 }
 
@@ -172,6 +230,17 @@ pub fn mock_action(atype: AType) -> TransactionAction {
         AType::Deploy => TransactionAction::Deploy {
             code:          get_random_bytes(100),
             contract_type: ContractType::Library,
+            asset_params:  None,
+        },
+        AType::DeployAsset => TransactionAction::Deploy {
+            code:          get_random_bytes(100),
+            contract_type: ContractType::Asset,
+            asset_params:  Some(AssetParams {
+                name:     "test".to_string(),
+                symbol:   "MT".to_string(),
+                supply:   mock_balance(),
+                decimals: 8,
+            }),
         },
         AType::Call => TransactionAction::Call {
             contract:       mock_contract_address(),
@@ -182,6 +251,17 @@ pub fn mock_action(atype: AType) -> TransactionAction {
                 amount:   mock_balance(),
             }),
         },
+        AType::BatchTransfer => TransactionAction::BatchTransfer {
+            outputs: (0..3)
+                .map(|_| TransferOutput {
+                    receiver:       mock_account_address(),
+                    carrying_asset: CarryingAsset {
+                        asset_id: mock_asset_id(),
+                        amount:   mock_balance(),
+                    },
+                })
+                .collect(),
+        },
     }
 }
 
@@ -213,6 +293,7 @@ pub fn mock_validator() -> Validator {
         address:        mock_account_address(),
         propose_weight: 1u8,
         vote_weight:    1u8,
+        bls_pub_key:    get_random_bytes(48),
     }
 }
 
@@ -277,10 +358,11 @@ pub fn mock_genesis() -> Genesis {
         timestamp:    99,
         prevhash:     "prevhashtest".to_string(),
         system_token: GenesisSystemToken {
-            code:   "codetest".to_string(),
-            name:   "nametest".to_string(),
-            symbol: "symbol".to_string(),
-            supply: 7,
+            code:     "codetest".to_string(),
+            name:     "nametest".to_string(),
+            symbol:   "symbol".to_string(),
+            supply:   7,
+            decimals: 8,
         },
         state_alloc:  vec![
             GenesisStateAlloc {
@@ -310,6 +392,92 @@ pub fn mock_genesis() -> Genesis {
                 ],
             },
         ],
+        validators:   vec![GenesisValidator {
+            address:        "test".to_string(),
+            propose_weight: 1,
+            vote_weight:    2,
+            bls_pub_key:    "test".to_string(),
+            bls_pop:        "test".to_string(),
+        }],
+        params:       GenesisParams {
+            cycles_limit: 99,
+            cycles_price: 2,
+            timeout_gap:  20,
+            interval:     3000,
+        },
+        assets:       vec![GenesisAsset {
+            code:     "codetest".to_string(),
+            name:     "nametest".to_string(),
+            symbol:   "symbol".to_string(),
+            supply:   7,
+            decimals: 8,
+        }],
+        contracts:    vec![GenesisContract {
+            code:    "codetest".to_string(),
+            library: true,
+        }],
+    }
+}
+
+// #####################
+// Mock Governance
+// #####################
+
+pub fn mock_validator_set() -> ValidatorSet {
+    ValidatorSet {
+        version:    1,
+        validators: vec![mock_validator(), mock_validator()],
+    }
+}
+
+pub fn mock_chain_params() -> ChainParams {
+    ChainParams {
+        cycles_limit: 99_999_999,
+        cycles_price: 1,
+        interval:     3000,
+        timeout_gap:  20,
+    }
+}
+
+pub fn mock_governance_proposal() -> GovernanceProposal {
+    GovernanceProposal {
+        id:                mock_hash(),
+        proposer:          mock_account_address(),
+        action:            GovernanceAction::UpdateValidators(vec![mock_validator()]),
+        validator_version: 1,
+        approvals:         vec![mock_account_address()],
+        executed:          false,
+    }
+}
+
+// #####################
+// Mock Multisig
+// #####################
+
+pub fn mock_multisig_wallet() -> MultisigWallet {
+    MultisigWallet {
+        address:   mock_account_address(),
+        owners:    vec![
+            MultisigOwner {
+                address: mock_account_address(),
+                weight:  1,
+            },
+            MultisigOwner {
+                address: mock_account_address(),
+                weight:  2,
+            },
+        ],
+        threshold: 2,
+    }
+}
+
+pub fn mock_multisig_proposal() -> MultisigProposal {
+    MultisigProposal {
+        id:        mock_hash(),
+        wallet:    mock_account_address(),
+        raw:       mock_raw_tx(AType::Call),
+        approvals: vec![mock_account_address(), mock_account_address()],
+        executed:  true,
     }
 }
 
@@ -3,7 +3,8 @@ use bytes::Bytes;
 use crate::fixed_codec::{FixedCodecError, ProtocolFixedCodec};
 use crate::types::primitive::{Balance, ContractAddress, ContractType, Fee, Hash, UserAddress};
 use crate::types::transaction::{
-    CarryingAsset, RawTransaction, SignedTransaction, TransactionAction,
+    AssetParams, CarryingAsset, RawTransaction, SignedTransaction, TransactionAction,
+    TransferOutput,
 };
 use crate::{impl_default_fixed_codec_for, ProtocolResult};
 
@@ -11,9 +12,12 @@ use crate::{impl_default_fixed_codec_for, ProtocolResult};
 impl_default_fixed_codec_for!(transaction, [RawTransaction, SignedTransaction]);
 
 const TRANSFER_ACTION_FLAG: u8 = 0;
-const DEPLOY_ACTION_FLAG: u8 = 1;
+const DEPLOY_ACTION_WITHOUT_PARAMS_FLAG: u8 = 1;
 const CALL_ACTION_WITH_ASSET_FLAG: u8 = 2;
 const CALL_ACTION_WITHOUT_ASSET_FLAG: u8 = 3;
+const APPROVE_ACTION_FLAG: u8 = 4;
+const DEPLOY_ACTION_WITH_PARAMS_FLAG: u8 = 5;
+const BATCH_TRANSFER_ACTION_FLAG: u8 = 6;
 
 impl rlp::Encodable for RawTransaction {
     fn rlp_append(&self, s: &mut rlp::RlpStream) {
@@ -37,12 +41,41 @@ impl rlp::Encodable for RawTransaction {
                 s.append(&carrying_asset.asset_id.as_bytes().to_vec());
                 s.append(&receiver.as_bytes().to_vec());
             }
+            TransactionAction::Approve {
+                spender,
+                asset_id,
+                max,
+            } => {
+                s.begin_list(9);
+                s.append(&APPROVE_ACTION_FLAG);
+
+                // Append tx basic fields
+                s.append(&self.chain_id.as_bytes().to_vec());
+                s.append(&self.fee.asset_id.as_bytes().to_vec());
+                s.append(&self.fee.cycle);
+                s.append(&self.nonce.as_bytes().to_vec());
+                s.append(&self.timeout);
+
+                // Append tx action fields
+                s.append(&max.to_bytes_be());
+                s.append(&asset_id.as_bytes().to_vec());
+                s.append(&spender.as_bytes().to_vec());
+            }
             TransactionAction::Deploy {
                 code,
                 contract_type,
+                asset_params,
             } => {
-                s.begin_list(8);
-                s.append(&DEPLOY_ACTION_FLAG);
+                match &asset_params {
+                    Some(_) => {
+                        s.begin_list(12);
+                        s.append(&DEPLOY_ACTION_WITH_PARAMS_FLAG);
+                    }
+                    None => {
+                        s.begin_list(8);
+                        s.append(&DEPLOY_ACTION_WITHOUT_PARAMS_FLAG);
+                    }
+                }
 
                 // Append tx basic fields
                 s.append(&self.chain_id.as_bytes().to_vec());
@@ -61,6 +94,13 @@ impl rlp::Encodable for RawTransaction {
                     ContractType::Native => 3,
                 };
                 s.append(&type_flag);
+
+                if let Some(params) = &asset_params {
+                    s.append(&params.name.as_bytes());
+                    s.append(&params.symbol.as_bytes());
+                    s.append(&params.supply.to_bytes_be());
+                    s.append(&params.decimals);
+                }
             }
             TransactionAction::Call {
                 contract,
@@ -102,7 +142,20 @@ impl rlp::Encodable for RawTransaction {
                 s.append(&contract.as_bytes().to_vec());
                 s.append(&method.as_bytes());
             }
-            _ => {}
+            TransactionAction::BatchTransfer { outputs } => {
+                s.begin_list(7);
+                s.append(&BATCH_TRANSFER_ACTION_FLAG);
+
+                // Append tx basic fields
+                s.append(&self.chain_id.as_bytes().to_vec());
+                s.append(&self.fee.asset_id.as_bytes().to_vec());
+                s.append(&self.fee.cycle);
+                s.append(&self.nonce.as_bytes().to_vec());
+                s.append(&self.timeout);
+
+                // Append tx action fields
+                s.append_list(outputs);
+            }
         }
     }
 }
@@ -135,7 +188,28 @@ impl rlp::Decodable for RawTransaction {
                     action,
                 })
             }
-            DEPLOY_ACTION_FLAG => {
+            APPROVE_ACTION_FLAG => {
+                // Decode tx basic fields
+                let (chain_id, fee, nonce, timeout) = help_decode_raw_tx(r)?;
+
+                // Decode tx action fields
+                let action = TransactionAction::Approve {
+                    spender:  ContractAddress::from_bytes(Bytes::from(r.at(8)?.data()?))
+                        .map_err(|_| rlp::DecoderError::RlpInvalidLength)?,
+                    asset_id: Hash::from_bytes(Bytes::from(r.at(7)?.data()?))
+                        .map_err(|_| rlp::DecoderError::RlpInvalidLength)?,
+                    max:      Balance::from_bytes_be(r.at(6)?.data()?),
+                };
+
+                Ok(RawTransaction {
+                    chain_id,
+                    nonce,
+                    timeout,
+                    fee,
+                    action,
+                })
+            }
+            DEPLOY_ACTION_WITH_PARAMS_FLAG | DEPLOY_ACTION_WITHOUT_PARAMS_FLAG => {
                 // Decode tx basic fields
                 let (chain_id, fee, nonce, timeout) = help_decode_raw_tx(r)?;
 
@@ -151,9 +225,23 @@ impl rlp::Decodable for RawTransaction {
                     _ => return Err(rlp::DecoderError::Custom("invalid contract type flag")),
                 };
 
+                let asset_params = if flag == DEPLOY_ACTION_WITH_PARAMS_FLAG {
+                    Some(AssetParams {
+                        name:     String::from_utf8(r.at(8)?.data()?.to_vec())
+                            .map_err(|_| rlp::DecoderError::RlpInvalidLength)?,
+                        symbol:   String::from_utf8(r.at(9)?.data()?.to_vec())
+                            .map_err(|_| rlp::DecoderError::RlpInvalidLength)?,
+                        supply:   Balance::from_bytes_be(r.at(10)?.data()?),
+                        decimals: r.at(11)?.as_val()?,
+                    })
+                } else {
+                    None
+                };
+
                 let action = TransactionAction::Deploy {
                     code,
                     contract_type,
+                    asset_params,
                 };
 
                 Ok(RawTransaction {
@@ -221,11 +309,55 @@ impl rlp::Decodable for RawTransaction {
                     })
                 }
             }
+            BATCH_TRANSFER_ACTION_FLAG => {
+                // Decode tx basic fields
+                let (chain_id, fee, nonce, timeout) = help_decode_raw_tx(r)?;
+
+                // Decode tx action fields
+                let outputs: Vec<TransferOutput> = r.at(6)?.as_list()?;
+                let action = TransactionAction::BatchTransfer { outputs };
+
+                Ok(RawTransaction {
+                    chain_id,
+                    nonce,
+                    timeout,
+                    fee,
+                    action,
+                })
+            }
             _ => Err(rlp::DecoderError::RlpListLenWithZeroPrefix),
         }
     }
 }
 
+// The fields are in the same order as the transfer action.
+impl rlp::Encodable for TransferOutput {
+    fn rlp_append(&self, s: &mut rlp::RlpStream) {
+        s.begin_list(3)
+            .append(&self.carrying_asset.amount.to_bytes_be())
+            .append(&self.carrying_asset.asset_id.as_bytes().to_vec())
+            .append(&self.receiver.as_bytes().to_vec());
+    }
+}
+
+impl rlp::Decodable for TransferOutput {
+    fn decode(r: &rlp::Rlp) -> Result<Self, rlp::DecoderError> {
+        if !r.is_list() && r.size() != 3 {
+            return Err(rlp::DecoderError::RlpIncorrectListLen);
+        }
+
+        Ok(TransferOutput {
+            receiver:       UserAddress::from_bytes(Bytes::from(r.at(2)?.data()?))
+                .map_err(|_| rlp::DecoderError::RlpInvalidLength)?,
+            carrying_asset: CarryingAsset {
+                asset_id: Hash::from_bytes(Bytes::from(r.at(1)?.data()?))
+                    .map_err(|_| rlp::DecoderError::RlpInvalidLength)?,
+                amount:   Balance::from_bytes_be(r.at(0)?.data()?),
+            },
+        })
+    }
+}
+
 impl rlp::Encodable for SignedTransaction {
     fn rlp_append(&self, s: &mut rlp::RlpStream) {
         s.begin_list(4)
@@ -28,6 +28,9 @@ pub enum ProtocolErrorKind {
 
     // types
     Types,
+
+    // main
+    Main,
 }
 
 // refer to https://github.com/rust-lang/rust/blob/a17951c4f80eb5208030f91fdb4ae93919fa6b12/src/libstd/io/error.rs#L73
@@ -1,7 +1,12 @@
 use async_trait::async_trait;
+use bytes::Bytes;
 
+use crate::traits::executor::ExecutorReadResp;
 use crate::traits::Context;
-use crate::types::{Address, AssetID, Balance, Epoch, Hash, Receipt, SignedTransaction};
+use crate::types::{
+    Address, AssetID, Balance, ContractAddress, Epoch, Hash, Receipt, SignedTransaction, StateDiff,
+    StateProof,
+};
 use crate::ProtocolResult;
 
 #[async_trait]
@@ -16,6 +21,13 @@ pub trait APIAdapter: Send + Sync {
 
     async fn get_receipt_by_tx_hash(&self, ctx: Context, tx_hash: Hash) -> ProtocolResult<Receipt>;
 
+    // Only found if the node traces the state.
+    async fn get_state_diff_by_tx_hash(
+        &self,
+        ctx: Context,
+        tx_hash: Hash,
+    ) -> ProtocolResult<StateDiff>;
+
     async fn get_balance(
         &self,
         ctx: Context,
@@ -23,4 +35,37 @@ pub trait APIAdapter: Send + Sync {
         id: &AssetID,
         epoch_id: Option<u64>,
     ) -> ProtocolResult<Balance>;
+
+    async fn get_account_proof(
+        &self,
+        ctx: Context,
+        address: &Address,
+        epoch_id: Option<u64>,
+    ) -> ProtocolResult<StateProof>;
+
+    async fn get_asset_proof(
+        &self,
+        ctx: Context,
+        id: &AssetID,
+        epoch_id: Option<u64>,
+    ) -> ProtocolResult<StateProof>;
+
+    // Execute the transaction on the state of the epoch without committing,
+    // which is used to estimate the cycles before sending it.
+    async fn simulate(
+        &self,
+        ctx: Context,
+        signed_tx: SignedTransaction,
+        epoch_id: Option<u64>,
+    ) -> ProtocolResult<ExecutorReadResp>;
+
+    async fn read_call(
+        &self,
+        ctx: Context,
+        caller: &Address,
+        contract: &ContractAddress,
+        method: &str,
+        args: Vec<Bytes>,
+        epoch_id: Option<u64>,
+    ) -> ProtocolResult<ExecutorReadResp>;
 }
@@ -2,8 +2,8 @@ use async_trait::async_trait;
 use creep::Context;
 
 use crate::types::{
-    Address, Bloom, Epoch, Hash, MerkleRoot, Proof, Receipt, SignedTransaction, UserAddress,
-    Validator,
+    Address, Bloom, ChainParams, Epoch, Hash, MerkleRoot, Proof, Receipt, SignedTransaction,
+    StateDiff, UserAddress, Validator, ValidatorSet,
 };
 use crate::{traits::executor::ExecutorExecResp, traits::mempool::MixedTxHashes, ProtocolResult};
 
@@ -32,6 +32,7 @@ pub struct CurrentConsensusStatus {
     pub receipt_root:       Vec<MerkleRoot>,
     pub cycles_used:        u64,
     pub proof:              Proof,
+    pub validator_version:  u64,
     pub validators:         Vec<Validator>,
     pub consensus_interval: u64,
 }
@@ -100,12 +101,31 @@ pub trait ConsensusAdapter: Send + Sync {
     /// Flush the given transactions in the mempool.
     async fn flush_mempool(&self, ctx: Context, txs: Vec<Hash>) -> ProtocolResult<()>;
 
+    /// Set the latest committed epoch and the state after executing it to the
+    /// mempool.
+    fn set_mempool_args(&self, epoch_id: u64, state_root: MerkleRoot, timeout_gap: u64);
+
     /// Save an epoch to the database.
     async fn save_epoch(&self, ctx: Context, epoch: Epoch) -> ProtocolResult<()>;
 
+    /// Save the state root after executing an epoch to the database.
+    async fn save_executed_state_root(
+        &self,
+        ctx: Context,
+        epoch_hash: Hash,
+        state_root: MerkleRoot,
+    ) -> ProtocolResult<()>;
+
     /// Save some receipts to the database.
     async fn save_receipts(&self, ctx: Context, receipts: Vec<Receipt>) -> ProtocolResult<()>;
 
+    /// Save the state diffs traced by the executor to the database.
+    async fn save_state_diffs(
+        &self,
+        ctx: Context,
+        state_diffs: Vec<StateDiff>,
+    ) -> ProtocolResult<()>;
+
     ///
     async fn save_proof(&self, ctx: Context, proof: Proof) -> ProtocolResult<()>;
 
@@ -138,6 +158,50 @@ pub trait ConsensusAdapter: Send + Sync {
         end: &str,
     ) -> ProtocolResult<Vec<SignedTransaction>>;
 
+    /// Pull the latest proof from other nodes.
+    async fn pull_proof(&self, ctx: Context, end: &str) -> ProtocolResult<Proof>;
+
+    /// Pull the epochs from `start` to `stop` with their signed transactions
+    /// from other nodes. The response may end before `stop`.
+    async fn pull_epoch_range(
+        &self,
+        ctx: Context,
+        start: u64,
+        stop: u64,
+        end: &str,
+    ) -> ProtocolResult<Vec<(Epoch, Vec<SignedTransaction>)>>;
+
+    /// Pull the epochs from `start` to `stop` without their transactions from
+    /// other nodes. The response may end before `stop`.
+    async fn pull_headers(
+        &self,
+        ctx: Context,
+        start: u64,
+        stop: u64,
+        end: &str,
+    ) -> ProtocolResult<Vec<Epoch>>;
+
+    /// Pull the trie nodes of the state of the given state root from the
+    /// peers and save them. The nodes already saved are not pulled again.
+    async fn pull_state(
+        &self,
+        peers: Vec<Context>,
+        state_root: MerkleRoot,
+        end: &str,
+    ) -> ProtocolResult<()>;
+
+    /// Report a peer which serves bad data to the network, which removes the
+    /// peer.
+    fn report_bad(&self, ctx: Context, reason: &str);
+
+    /// Get the validator set and the chain params kept in the state of the
+    /// given state root.
+    async fn get_governance(
+        &self,
+        node_info: NodeInfo,
+        state_root: MerkleRoot,
+    ) -> ProtocolResult<(ValidatorSet, ChainParams)>;
+
     /// Get an epoch corresponding to the given epoch ID.
     async fn get_epoch_by_id(&self, ctx: Context, epoch_id: u64) -> ProtocolResult<Epoch>;
 }
@@ -1,5 +1,10 @@
+use bytes::Bytes;
+
 use crate::traits::executor::{ContractSchema, RcInvokeContext};
-use crate::types::{Account, Address, Asset, AssetID, Balance, ContractAddress, MerkleRoot};
+use crate::types::{
+    Account, Address, Asset, AssetID, Balance, ContractAddress, Hash, MerkleRoot, TransferOutput,
+    TransferOutputResult,
+};
 use crate::ProtocolResult;
 
 // As the world state access layer, the ContractStateAdapter provides `cache`
@@ -33,12 +38,13 @@ pub trait ContractStateAdapter {
 
 /// BankContract is the registration and query center for asset.
 ///
-/// It only does two things
+/// It does three things
 /// 1. Responsible for generating a unique ID for the asset and writing the
 /// asset's information to the chain.
 /// 2. Query the basic information of the asset by asset id.
+/// 3. Track the supply of the asset when it is minted or burned.
 pub trait BankContract<Adapter: ContractStateAdapter> {
-    // Register an asset.
+    // Register an asset, the caller becomes the issuer of it.
     // The asset id is generated by: AssetID = Hash(ChainID + AssetContractAddress).
     //
     // NOTE: After the asset is successfully registered, the `world state` will not
@@ -50,16 +56,73 @@ pub trait BankContract<Adapter: ContractStateAdapter> {
         name: String,
         symbol: String,
         supply: Balance,
+        decimals: u64,
     ) -> ProtocolResult<Asset>;
 
     fn get_asset(&self, ictx: RcInvokeContext, id: &AssetID) -> ProtocolResult<Asset>;
+
+    // Increase the supply of the asset, only the issuer or the manage contract
+    // of the asset is allowed. The caller must credit the minted amount to
+    // the accounts to keep the supply equal to the total balances.
+    fn mint(
+        &mut self,
+        ictx: RcInvokeContext,
+        id: &AssetID,
+        amount: Balance,
+    ) -> ProtocolResult<Asset>;
+
+    // Decrease the supply of the asset, the same as `mint`, the burned amount
+    // must be debited from the accounts.
+    fn burn(
+        &mut self,
+        ictx: RcInvokeContext,
+        id: &AssetID,
+        amount: Balance,
+    ) -> ProtocolResult<Asset>;
 }
 
 pub trait AccountContract<Adapter: ContractStateAdapter> {
     fn transfer(&mut self, ictx: RcInvokeContext, to: &Address) -> ProtocolResult<()>;
 
+    // Pay every output from the caller in order, the amounts in the results
+    // are the caller's balance before and after each output.
+    fn batch_transfer(
+        &mut self,
+        ictx: RcInvokeContext,
+        outputs: &[TransferOutput],
+    ) -> ProtocolResult<Vec<TransferOutputResult>>;
+
+    // Allow the `spender` contract to spend at most `max` of the caller's asset.
+    // Approving the same spender again overwrites the previous allowance.
+    fn approve(
+        &mut self,
+        ictx: RcInvokeContext,
+        spender: &ContractAddress,
+        id: &AssetID,
+        max: Balance,
+    ) -> ProtocolResult<()>;
+
+    // Move `amount` of asset from a user account to `to` on behalf of the
+    // caller, the caller must be a contract approved by `from` and the
+    // accumulated spending can not exceed the approved `max`.
+    fn transfer_from(
+        &mut self,
+        ictx: RcInvokeContext,
+        from: &Address,
+        to: &Address,
+        id: &AssetID,
+        amount: Balance,
+    ) -> ProtocolResult<()>;
+
     fn inc_nonce(&mut self, ictx: RcInvokeContext) -> ProtocolResult<()>;
 
+    // Record the transaction nonce consumed by the caller, which is kept until
+    // the `timeout` epoch. The expired nonces are dropped at the same time.
+    fn use_nonce(&mut self, ictx: RcInvokeContext, nonce: Hash, timeout: u64)
+        -> ProtocolResult<()>;
+
+    fn is_nonce_used(&self, address: &Address, nonce: &Hash) -> ProtocolResult<bool>;
+
     fn create_account(&mut self, address: &Address) -> ProtocolResult<Account>;
 
     fn add_balance(
@@ -82,3 +145,14 @@ pub trait AccountContract<Adapter: ContractStateAdapter> {
 
     fn get_nonce(&self, address: &Address) -> ProtocolResult<u64>;
 }
+
+// NativeContract is a contract built into the executor, the `Dispatcher`
+// routes `TransactionAction::Call` to it by the contract address.
+pub trait NativeContract {
+    fn call(
+        &mut self,
+        ictx: RcInvokeContext,
+        method: &str,
+        args: Vec<Bytes>,
+    ) -> ProtocolResult<Bytes>;
+}
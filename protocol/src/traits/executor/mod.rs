@@ -8,12 +8,22 @@ use bytes::Bytes;
 
 use crate::fixed_codec::ProtocolFixedCodec;
 use crate::types::{
-    Address, AssetID, Balance, Bloom, CarryingAsset, ContractAddress, Fee, Genesis, Hash,
-    MerkleRoot, Receipt, SignedTransaction,
+    Address, AssetID, Balance, Bloom, CarryingAsset, ChainParams, ContractAddress, Fee, Genesis,
+    Hash, Log, MerkleRoot, Receipt, ReceiptResult, SignedTransaction, StateDiff, StateProof,
+    ValidatorSet,
 };
 use crate::ProtocolResult;
 
-pub trait TrieDB = cita_trie::DB;
+// The storage of the MPT nodes. `commit_epoch` is called once the state of an
+// epoch has been committed, the implementation may prune the history states
+// on it.
+pub trait TrieDB: cita_trie::DB {
+    fn commit_epoch(&self, _epoch_id: u64) -> ProtocolResult<()> {
+        Ok(())
+    }
+}
+
+impl TrieDB for cita_trie::MemoryDB {}
 
 #[derive(Clone, Debug)]
 pub struct ExecutorExecResp {
@@ -21,6 +31,19 @@ pub struct ExecutorExecResp {
     pub all_cycles_used: Vec<Fee>,
     pub logs_bloom:      Bloom,
     pub state_root:      MerkleRoot,
+    // The validators and the chain params of the next epoch.
+    pub validator_set: ValidatorSet,
+    pub params:        ChainParams,
+    // Empty unless the state is traced, one for each of the receipts.
+    pub state_diffs: Vec<StateDiff>,
+}
+
+// The result of an execution which is not committed, `cycles_used` is the
+// count of cycles before multiplied by the cycles price.
+#[derive(Clone, Debug)]
+pub struct ExecutorReadResp {
+    pub result:      ReceiptResult,
+    pub cycles_used: u64,
 }
 
 pub trait ExecutorFactory<DB: TrieDB>: Send + Sync {
@@ -32,6 +55,15 @@ pub trait ExecutorFactory<DB: TrieDB>: Send + Sync {
         cycles_price: u64,
         coinbase: Address,
     ) -> ProtocolResult<Box<dyn Executor>>;
+
+    // Whether the nonce has been used by `address` under the state root. Only
+    // the account is read, no executor is built for it.
+    fn is_nonce_used(
+        state_root: MerkleRoot,
+        db: Arc<DB>,
+        address: &Address,
+        nonce: &Hash,
+    ) -> ProtocolResult<bool>;
 }
 
 pub trait Executor {
@@ -39,7 +71,40 @@ pub trait Executor {
 
     fn exec(&mut self, signed_txs: Vec<SignedTransaction>) -> ProtocolResult<ExecutorExecResp>;
 
+    // Record the balances changed by each transaction in the following `exec`,
+    // they are returned as the `state_diffs`.
+    fn enable_state_trace(&mut self);
+
+    // Execute the transaction without charging fee or committing any state
+    // change, the signature of the transaction is not verified.
+    fn simulate(&mut self, signed_tx: SignedTransaction) -> ProtocolResult<ExecutorReadResp>;
+
+    // Call the contract as `caller` without committing any state change.
+    fn read_call(
+        &mut self,
+        caller: &Address,
+        contract: &ContractAddress,
+        method: &str,
+        args: Vec<Bytes>,
+    ) -> ProtocolResult<ExecutorReadResp>;
+
     fn get_balance(&self, address: &Address, id: &AssetID) -> ProtocolResult<Balance>;
+
+    fn is_nonce_used(&self, address: &Address, nonce: &Hash) -> ProtocolResult<bool>;
+
+    // Prove the account of `address` under the committed state root, the
+    // account is kept by the native account contract.
+    fn get_account_proof(&self, address: &Address) -> ProtocolResult<StateProof>;
+
+    // Prove the asset of `id` under the committed state root, the asset is
+    // kept by the native bank contract.
+    fn get_asset_proof(&self, id: &AssetID) -> ProtocolResult<StateProof>;
+
+    // The chain params kept in the committed state.
+    fn get_chain_params(&self) -> ProtocolResult<ChainParams>;
+
+    // The validator set kept in the committed state.
+    fn get_validator_set(&self) -> ProtocolResult<ValidatorSet>;
 }
 
 #[derive(Clone, Debug)]
@@ -53,6 +118,7 @@ pub struct InvokeContext {
     pub caller:         Address,
     pub coinbase:       Address,
     pub carrying_asset: Option<CarryingAsset>,
+    pub logs:           Vec<Log>,
 }
 
 pub type RcInvokeContext = Rc<RefCell<InvokeContext>>;
@@ -1,7 +1,7 @@
 use async_trait::async_trait;
 use creep::Context;
 
-use crate::types::{Hash, SignedTransaction};
+use crate::types::{Hash, MerkleRoot, SignedTransaction};
 use crate::ProtocolResult;
 
 #[allow(dead_code)]
@@ -41,6 +41,10 @@ pub trait MemPool: Send + Sync {
         ctx: Context,
         propose_tx_hashes: Vec<Hash>,
     ) -> ProtocolResult<()>;
+
+    // Set the latest committed epoch and the state after executing it, the
+    // transactions are checked against them until the next epoch is committed.
+    fn set_args(&self, epoch_id: u64, state_root: MerkleRoot, timeout_gap: u64);
 }
 
 #[async_trait]
@@ -60,4 +64,9 @@ pub trait MemPoolAdapter: Send + Sync {
     async fn check_storage_exist(&self, ctx: Context, tx_hash: Hash) -> ProtocolResult<()>;
 
     async fn get_latest_epoch_id(&self, ctx: Context) -> ProtocolResult<u64>;
+
+    // The timeout gap kept in the latest state.
+    async fn get_timeout_gap(&self, ctx: Context) -> ProtocolResult<u64>;
+
+    fn set_args(&self, epoch_id: u64, state_root: MerkleRoot, timeout_gap: u64);
 }
@@ -9,7 +9,7 @@ pub mod executor;
 pub use api::APIAdapter;
 pub use consensus::{Consensus, ConsensusAdapter, CurrentConsensusStatus, MessageTarget, NodeInfo};
 pub use mempool::{MemPool, MemPoolAdapter, MixedTxHashes};
-pub use network::{Gossip, MessageCodec, MessageHandler, Priority, Rpc};
+pub use network::{Gossip, MessageCodec, MessageHandler, PeerTrust, Priority, Rpc};
 pub use storage::{Storage, StorageAdapter, StorageBatchModify, StorageCategory, StorageSchema};
 
 pub use creep::{Cloneable, Context};
@@ -80,6 +80,12 @@ pub trait Rpc: Send + Sync {
         M: MessageCodec;
 }
 
+pub trait PeerTrust: Send + Sync {
+    /// Report the peer of the session in the context which serves bad data,
+    /// the peer is removed.
+    fn report_bad(&self, ctx: Context, reason: &str);
+}
+
 #[async_trait]
 pub trait MessageHandler: Sync + Send + 'static {
     type Message: MessageCodec;
@@ -3,8 +3,8 @@ use derive_more::Display;
 
 use crate::codec::ProtocolCodec;
 use crate::types::epoch::{Epoch, Proof};
-use crate::types::receipt::Receipt;
-use crate::types::{Hash, SignedTransaction};
+use crate::types::receipt::{Receipt, StateDiff};
+use crate::types::{Hash, MerkleRoot, SignedTransaction};
 use crate::ProtocolResult;
 
 #[derive(Debug, Copy, Clone, Display)]
@@ -12,6 +12,7 @@ pub enum StorageCategory {
     Epoch,
     Receipt,
     SignedTransaction,
+    StateDiff,
 }
 
 pub trait StorageSchema {
@@ -29,8 +30,20 @@ pub trait Storage: Send + Sync {
 
     async fn insert_receipts(&self, receipts: Vec<Receipt>) -> ProtocolResult<()>;
 
+    async fn insert_state_diffs(&self, state_diffs: Vec<StateDiff>) -> ProtocolResult<()>;
+
     async fn update_latest_proof(&self, proof: Proof) -> ProtocolResult<()>;
 
+    async fn update_genesis_hash(&self, genesis_hash: Hash) -> ProtocolResult<()>;
+
+    // The header of an epoch carries the state root before executing it, the
+    // root after executing it is kept by the hash of the epoch.
+    async fn insert_executed_state_root(
+        &self,
+        epoch_hash: Hash,
+        state_root: MerkleRoot,
+    ) -> ProtocolResult<()>;
+
     async fn get_transaction_by_hash(&self, tx_hash: Hash) -> ProtocolResult<SignedTransaction>;
 
     async fn get_transactions(&self, hashes: Vec<Hash>) -> ProtocolResult<Vec<SignedTransaction>>;
@@ -45,7 +58,13 @@ pub trait Storage: Send + Sync {
 
     async fn get_receipts(&self, hash: Vec<Hash>) -> ProtocolResult<Vec<Receipt>>;
 
+    async fn get_state_diff(&self, tx_hash: Hash) -> ProtocolResult<StateDiff>;
+
     async fn get_latest_proof(&self) -> ProtocolResult<Proof>;
+
+    async fn get_genesis_hash(&self) -> ProtocolResult<Hash>;
+
+    async fn get_executed_state_root(&self, epoch_hash: Hash) -> ProtocolResult<MerkleRoot>;
 }
 
 pub enum StorageBatchModify<S: StorageSchema> {
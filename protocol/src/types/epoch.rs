@@ -40,6 +40,7 @@ pub struct Validator {
     pub address:        UserAddress,
     pub propose_weight: u8,
     pub vote_weight:    u8,
+    pub bls_pub_key:    Bytes,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -2,10 +2,56 @@ use serde_derive::Deserialize;
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
 pub struct Genesis {
-    pub timestamp:    u64,
-    pub prevhash:     String,
+    pub timestamp: u64,
+    pub prevhash: String,
     pub system_token: GenesisSystemToken,
-    pub state_alloc:  Vec<GenesisStateAlloc>,
+    pub state_alloc: Vec<GenesisStateAlloc>,
+    pub validators: Vec<GenesisValidator>,
+    pub params: GenesisParams,
+    #[serde(default)]
+    pub assets: Vec<GenesisAsset>,
+    #[serde(default)]
+    pub contracts: Vec<GenesisContract>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct GenesisValidator {
+    pub address:        String,
+    pub propose_weight: u8,
+    pub vote_weight:    u8,
+    pub bls_pub_key:    String,
+    // The signature of the bls private key proving the possession of it.
+    pub bls_pop: String,
+}
+
+// The chain rules, every node must use the same values.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct GenesisParams {
+    pub cycles_limit: u64,
+    pub cycles_price: u64,
+    pub timeout_gap:  u64,
+    pub interval:     u64,
+}
+
+// An asset registered at genesis besides the system token, the balances are
+// allocated in `state_alloc`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct GenesisAsset {
+    pub code: String,
+    pub name: String,
+    pub symbol: String,
+    pub supply: u64,
+    #[serde(default)]
+    pub decimals: u64,
+}
+
+// A contract deployed at genesis, the address is derived from the code with
+// nonce 0, so the code must be unique.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct GenesisContract {
+    pub code: String,
+    #[serde(default)]
+    pub library: bool,
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
@@ -14,6 +60,8 @@ pub struct GenesisStateAlloc {
     pub assets:  Vec<GenesisStateAsset>,
 }
 
+// The balance is hex encoded, the balances of an asset must add up to the
+// supply of it.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
 pub struct GenesisStateAsset {
     pub asset_id: String,
@@ -26,6 +74,9 @@ pub struct GenesisSystemToken {
     pub name:   String,
     pub symbol: String,
     pub supply: u64,
+    // Zero if it is not given, as in the genesis files before it was added.
+    #[serde(default)]
+    pub decimals: u64,
 }
 
 #[cfg(test)]
@@ -51,6 +102,34 @@ mod tests {
                         "balance": "0xfff"
                     }]
                 }
+            ],
+            "validators": [
+                {
+                    "address": "0xfffff",
+                    "propose_weight": 1,
+                    "vote_weight": 1,
+                    "bls_pub_key": "0xffff",
+                    "bls_pop": "0xffff"
+                }
+            ],
+            "params": {
+                "cycles_limit": 99999999,
+                "cycles_price": 1,
+                "timeout_gap": 20,
+                "interval": 3000
+            },
+            "assets": [
+                {
+                    "code": "ee",
+                    "name": "Extra token",
+                    "symbol": "ET",
+                    "supply": 1000
+                }
+            ],
+            "contracts": [
+                {
+                    "code": "aa"
+                }
             ]
         }"#;
 
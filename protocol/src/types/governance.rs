@@ -0,0 +1,40 @@
+use crate::types::epoch::Validator;
+use crate::types::primitive::{Hash, UserAddress};
+
+// The validators of the consensus, the version is increased by every change of
+// them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidatorSet {
+    pub version:    u64,
+    pub validators: Vec<Validator>,
+}
+
+// The chain rules which every node must agree on, they are kept in the state
+// so that they can only be changed by the validators.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChainParams {
+    pub cycles_limit: u64,
+    pub cycles_price: u64,
+    pub interval:     u64,
+    pub timeout_gap:  u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GovernanceAction {
+    UpdateValidators(Vec<Validator>),
+    UpdateParams(ChainParams),
+}
+
+// A change of the chain proposed by a validator, it takes effect once the
+// validators approving it hold more than 2/3 of the vote weight. The proposal
+// can only be approved under the version of the validator set it is made
+// under.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GovernanceProposal {
+    pub id:                Hash,
+    pub proposer:          UserAddress,
+    pub action:            GovernanceAction,
+    pub validator_version: u64,
+    pub approvals:         Vec<UserAddress>,
+    pub executed:          bool,
+}
@@ -1,6 +1,9 @@
 pub(crate) mod epoch;
 pub(crate) mod genesis;
+pub(crate) mod governance;
+pub(crate) mod multisig;
 pub(crate) mod primitive;
+pub(crate) mod proof;
 pub(crate) mod receipt;
 pub(crate) mod transaction;
 
@@ -12,14 +15,25 @@ use crate::{ProtocolError, ProtocolErrorKind};
 
 pub use epoch::{Epoch, EpochHeader, EpochId, Pill, Proof, Validator};
 pub use ethbloom::{Bloom, BloomRef, Input as BloomInput};
-pub use genesis::{Genesis, GenesisStateAlloc, GenesisStateAsset};
+pub use genesis::{
+    Genesis, GenesisAsset, GenesisContract, GenesisParams, GenesisStateAlloc, GenesisStateAsset,
+    GenesisSystemToken, GenesisValidator,
+};
+pub use governance::{ChainParams, GovernanceAction, GovernanceProposal, ValidatorSet};
+pub use multisig::{MultisigOwner, MultisigProposal, MultisigWallet};
 pub use primitive::{
     Account, Address, ApprovedInfo, Asset, AssetID, AssetInfo, Balance, ContractAccount,
     ContractAddress, ContractType, Fee, Hash, MerkleRoot, UserAccount, UserAddress,
     GENESIS_EPOCH_ID,
 };
-pub use receipt::{Receipt, ReceiptResult};
-pub use transaction::{CarryingAsset, RawTransaction, SignedTransaction, TransactionAction};
+pub use proof::StateProof;
+pub use receipt::{
+    BalanceDiff, Log, Receipt, ReceiptResult, StateDiff, StorageDiff, TransferOutputResult,
+};
+pub use transaction::{
+    AssetParams, CarryingAsset, RawTransaction, SignedTransaction, TransactionAction,
+    TransferOutput,
+};
 
 #[derive(Debug, Display, From)]
 pub enum TypesError {
@@ -31,6 +45,15 @@ pub enum TypesError {
 
     #[display(fmt = "{:?} is an invalid address", address)]
     InvalidAddress { address: String },
+
+    #[display(fmt = "{:?}", error)]
+    Trie { error: cita_trie::TrieError },
+
+    #[display(fmt = "State root expect {:?}, get {:?}.", expect, real)]
+    StateRootMismatch {
+        expect: MerkleRoot,
+        real:   MerkleRoot,
+    },
 }
 
 impl Error for TypesError {}
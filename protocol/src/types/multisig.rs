@@ -0,0 +1,29 @@
+use crate::types::primitive::{Hash, UserAddress};
+use crate::types::transaction::RawTransaction;
+
+// An owner of a multi-signature wallet with the weight of its signature.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MultisigOwner {
+    pub address: UserAddress,
+    pub weight:  u64,
+}
+
+// A M-of-N wallet, a transaction of the wallet is allowed once the total weight
+// of the owners signing it reaches the `threshold`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MultisigWallet {
+    pub address:   UserAddress,
+    pub owners:    Vec<MultisigOwner>,
+    pub threshold: u64,
+}
+
+// A transaction of the wallet waiting for the approvals of the owners, the id
+// is the hash of the transaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MultisigProposal {
+    pub id:        Hash,
+    pub wallet:    UserAddress,
+    pub raw:       RawTransaction,
+    pub approvals: Vec<UserAddress>,
+    pub executed:  bool,
+}
@@ -342,8 +342,11 @@ pub struct Asset {
     pub name:            String,
     pub symbol:          String,
     pub supply:          Balance,
+    pub decimals:        u64,
     pub manage_contract: ContractAddress,
     pub storage_root:    MerkleRoot,
+    // The account registering the asset, which can mint and burn it.
+    pub issuer: Address,
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
@@ -358,10 +361,13 @@ pub enum Account {
     Contract(ContractAccount),
 }
 
+// `used_nonces` maps the consumed transaction nonces to their timeout, a nonce
+// is forgotten once the timeout is passed.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct UserAccount {
-    pub nonce:  u64,
-    pub assets: BTreeMap<AssetID, AssetInfo>,
+    pub nonce:       u64,
+    pub assets:      BTreeMap<AssetID, AssetInfo>,
+    pub used_nonces: BTreeMap<Hash, u64>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -0,0 +1,97 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use cita_trie::{MemoryDB, PatriciaTrie, Trie};
+use hasher::HasherKeccak;
+
+use crate::fixed_codec::ProtocolFixedCodec;
+use crate::types::{Account, Address, Asset, AssetID, MerkleRoot, TypesError};
+use crate::ProtocolResult;
+
+// The world state is a two-level MPT, the state trie maps the address of a
+// contract to the root of its storage trie, and the storage trie maps the
+// fixed encoded key to the fixed encoded value.
+//
+// `contract_proof` proves the storage root of the contract under the
+// `state_root`, and `storage_proof` proves the key under the storage root.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StateProof {
+    pub state_root:     MerkleRoot,
+    pub contract_proof: Vec<Bytes>,
+    pub storage_proof:  Vec<Bytes>,
+}
+
+impl StateProof {
+    // Verify the value of `key` in the storage of `contract` against a trusted
+    // `state_root`, which usually comes from an `EpochHeader`. Returns `None`
+    // if the proof shows that the key does not exist.
+    pub fn verify(
+        &self,
+        state_root: &MerkleRoot,
+        contract: &Address,
+        key: &Bytes,
+    ) -> ProtocolResult<Option<Bytes>> {
+        if &self.state_root != state_root {
+            return Err(TypesError::StateRootMismatch {
+                expect: state_root.clone(),
+                real:   self.state_root.clone(),
+            }
+            .into());
+        }
+
+        let storage_root =
+            match verify_trie_proof(state_root, &contract.as_bytes(), &self.contract_proof)? {
+                Some(root) => MerkleRoot::from_bytes(root)?,
+                None => return Ok(None),
+            };
+
+        verify_trie_proof(&storage_root, key, &self.storage_proof)
+    }
+
+    // Verify the account of `address` which is stored by the `account_contract`.
+    pub fn verify_account(
+        &self,
+        state_root: &MerkleRoot,
+        account_contract: &Address,
+        address: &Address,
+    ) -> ProtocolResult<Option<Account>> {
+        let key = address.encode_fixed()?;
+
+        match self.verify(state_root, account_contract, &key)? {
+            Some(value) => Ok(Some(Account::decode_fixed(value)?)),
+            None => Ok(None),
+        }
+    }
+
+    // Verify the asset of `id` which is stored by the `bank_contract`.
+    pub fn verify_asset(
+        &self,
+        state_root: &MerkleRoot,
+        bank_contract: &Address,
+        id: &AssetID,
+    ) -> ProtocolResult<Option<Asset>> {
+        let key = id.encode_fixed()?;
+
+        match self.verify(state_root, bank_contract, &key)? {
+            Some(value) => Ok(Some(Asset::decode_fixed(value)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+fn verify_trie_proof(
+    root: &MerkleRoot,
+    key: &Bytes,
+    proof: &[Bytes],
+) -> ProtocolResult<Option<Bytes>> {
+    let trie = PatriciaTrie::new(
+        Arc::new(MemoryDB::new(false)),
+        Arc::new(HasherKeccak::new()),
+    );
+    let proof = proof.iter().map(|node| node.to_vec()).collect();
+
+    let value = trie
+        .verify_proof(&root.as_bytes(), key, proof)
+        .map_err(TypesError::from)?;
+    Ok(value.map(Bytes::from))
+}
@@ -1,7 +1,8 @@
 use bytes::Bytes;
 
 use crate::types::{
-    AssetID, Balance, Bloom, ContractAddress, ContractType, Fee, Hash, MerkleRoot, UserAddress,
+    Address, AssetID, Balance, Bloom, ContractAddress, ContractType, Fee, Hash, MerkleRoot,
+    UserAddress,
 };
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -11,6 +12,16 @@ pub struct Receipt {
     pub tx_hash:     Hash,
     pub cycles_used: Fee,
     pub result:      ReceiptResult,
+    pub logs:        Vec<Log>,
+}
+
+// An event emitted by the contract `address`, the `topics` are folded into
+// the logs bloom so that the events can be filtered without re-executing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Log {
+    pub address: Address,
+    pub topics:  Vec<Hash>,
+    pub data:    Bytes,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -39,4 +50,46 @@ pub enum ReceiptResult {
         system: String,
         user:   String,
     },
+    BatchTransfer {
+        outputs: Vec<TransferOutputResult>,
+    },
+}
+
+// The result of an output of the batch transfer, the same as
+// `ReceiptResult::Transfer`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransferOutputResult {
+    pub receiver:      UserAddress,
+    pub asset_id:      AssetID,
+    pub before_amount: Balance,
+    pub after_amount:  Balance,
+}
+
+// The state changed by a transaction, it is recorded only when the executor
+// traces the state. `balances` is the decoded view of the balances in the
+// account contract, while `storages` lists every raw write to the storage of
+// the native contracts and the vm contracts, the accounts included.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StateDiff {
+    pub tx_hash:  Hash,
+    pub balances: Vec<BalanceDiff>,
+    pub storages: Vec<StorageDiff>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BalanceDiff {
+    pub address:  Address,
+    pub asset_id: AssetID,
+    pub before:   Balance,
+    pub after:    Balance,
+}
+
+// The fixed encoded value of a key in the storage of a contract, `before` is
+// empty if the key is new.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StorageDiff {
+    pub address: Address,
+    pub key:     Bytes,
+    pub before:  Bytes,
+    pub after:   Bytes,
 }
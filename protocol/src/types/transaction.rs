@@ -27,6 +27,7 @@ pub enum TransactionAction {
     Deploy {
         code:          Bytes,
         contract_type: ContractType,
+        asset_params:  Option<AssetParams>,
     },
     Call {
         contract:       ContractAddress,
@@ -34,6 +35,10 @@ pub enum TransactionAction {
         args:           Vec<Bytes>,
         carrying_asset: Option<CarryingAsset>,
     },
+    // Pay many receivers at once, the outputs are applied atomically.
+    BatchTransfer {
+        outputs: Vec<TransferOutput>,
+    },
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -42,6 +47,21 @@ pub struct CarryingAsset {
     pub amount:   Balance,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransferOutput {
+    pub receiver:       UserAddress,
+    pub carrying_asset: CarryingAsset,
+}
+
+// The parameters of the asset issued by deploying an `Asset` contract.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AssetParams {
+    pub name:     String,
+    pub symbol:   String,
+    pub supply:   Balance,
+    pub decimals: u64,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct SignedTransaction {
     pub raw:       RawTransaction,
@@ -4,6 +4,7 @@ use std::path::PathBuf;
 use serde_derive::Deserialize;
 
 use core_consensus::DurationConfig;
+use core_executor::trie::DEFAULT_KEEP_EPOCHS;
 use core_mempool::{DEFAULT_BROADCAST_TXS_INTERVAL, DEFAULT_BROADCAST_TXS_SIZE};
 
 #[derive(Debug, Deserialize)]
@@ -35,8 +36,7 @@ fn default_broadcast_txs_interval() -> u64 {
 
 #[derive(Debug, Deserialize)]
 pub struct ConfigMempool {
-    pub timeout_gap: u64,
-    pub pool_size:   u64,
+    pub pool_size: u64,
 
     #[serde(default = "default_broadcast_txs_size")]
     pub broadcast_txs_size: usize,
@@ -44,18 +44,31 @@ pub struct ConfigMempool {
     pub broadcast_txs_interval: u64,
 }
 
+// With `fast_sync` enabled, a node far behind pulls a recent state instead of
+// executing all the epochs before it.
 #[derive(Debug, Deserialize)]
 pub struct ConfigConsensus {
-    pub cycles_limit:  u64,
-    pub cycles_price:  u64,
-    pub interval:      u64,
-    pub duration:      DurationConfig,
-    pub verifier_list: Vec<String>,
+    pub duration: DurationConfig,
+
+    #[serde(default)]
+    pub fast_sync: bool,
+}
+
+fn default_keep_epochs() -> u64 {
+    DEFAULT_KEEP_EPOCHS
 }
 
+// With `light` enabled, the states older than the last `keep_epochs` epochs
+// are pruned. With `trace_state` enabled, the balances and the contract
+// storages changed by every transaction are saved along with the receipts.
 #[derive(Debug, Deserialize)]
 pub struct ConfigExecutor {
     pub light: bool,
+
+    #[serde(default = "default_keep_epochs")]
+    pub keep_epochs: u64,
+    #[serde(default)]
+    pub trace_state: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -63,7 +76,8 @@ pub struct Config {
     // chain id
     pub chain_id: String,
     // crypto
-    pub privkey: String,
+    pub privkey:     String,
+    pub bls_privkey: String,
     // db config
     pub data_path: PathBuf,
 
@@ -89,9 +103,9 @@ impl Config {
         path_state
     }
 
-    // pub fn data_path_for_bft_wal(&self) -> PathBuf {
-    //     let mut path_state = self.data_path.clone();
-    //     path_state.push("bft_wal");
-    //     path_state
-    // }
+    pub fn data_path_for_bft_wal(&self) -> PathBuf {
+        let mut path_state = self.data_path.clone();
+        path_state.push("bft_wal");
+        path_state
+    }
 }
@@ -3,25 +3,35 @@
 mod config;
 
 use std::convert::TryFrom;
+use std::error::Error;
 use std::fs::File;
+use std::io::{BufReader, BufWriter};
 use std::path::Path;
 use std::sync::Arc;
 
 use bytes::Bytes;
+use cita_trie::MemoryDB;
+use derive_more::Display;
 
-use common_crypto::{PrivateKey, PublicKey, Secp256k1, Secp256k1PrivateKey};
+use common_crypto::{
+    BLS12381PrivateKey, Crypto, PrivateKey, PublicKey, Secp256k1, Secp256k1PrivateKey, Signature,
+    BLS12381,
+};
 use core_api::adapter::DefaultAPIAdapter;
 use core_api::config::GraphQLConfig;
 use core_consensus::adapter::OverlordConsensusAdapter;
 use core_consensus::consensus::OverlordConsensus;
-use core_consensus::fixed_types::ConsensusRpcResponse;
+use core_consensus::fixed_types::{ConsensusRpcResponse, FixedTrieNodes};
 use core_consensus::message::{
     ProposalMessageHandler, QCMessageHandler, RichEpochIDMessageHandler, RpcHandler,
-    VoteMessageHandler, END_GOSSIP_AGGREGATED_VOTE, END_GOSSIP_RICH_EPOCH_ID,
-    END_GOSSIP_SIGNED_PROPOSAL, END_GOSSIP_SIGNED_VOTE, RPC_RESP_SYNC_PULL, RPC_SYNC_PULL,
+    StateRpcHandler, VoteMessageHandler, END_GOSSIP_AGGREGATED_VOTE, END_GOSSIP_RICH_EPOCH_ID,
+    END_GOSSIP_SIGNED_PROPOSAL, END_GOSSIP_SIGNED_VOTE, RPC_RESP_SYNC_PULL,
+    RPC_RESP_SYNC_PULL_STATE, RPC_SYNC_PULL, RPC_SYNC_PULL_STATE,
 };
+use core_consensus::wal::ConsensusWal;
+use core_executor::snapshot::{export_snapshot, import_snapshot};
 use core_executor::trie::RocksTrieDB;
-use core_executor::TransactionExecutorFactory;
+use core_executor::{bls_pop_hash, TransactionExecutorFactory};
 use core_mempool::{
     DefaultMemPoolAdapter, HashMemPool, MsgPushTxs, NewTxsHandler, END_GOSSIP_NEW_TXS,
     END_RESP_PULL_TXS, END_RPC_PULL_TXS,
@@ -29,15 +39,19 @@ use core_mempool::{
 use core_network::{NetworkConfig, NetworkService};
 use core_storage::{adapter::rocks::RocksAdapter, ImplStorage};
 
-use protocol::traits::executor::ExecutorFactory;
-use protocol::traits::{CurrentConsensusStatus, NodeInfo, Storage};
+use protocol::traits::executor::{ExecutorFactory, TrieDB};
+use protocol::traits::{CurrentConsensusStatus, MemPool, NodeInfo, Storage};
 use protocol::types::{
     Address, Bloom, Epoch, EpochHeader, Genesis, Hash, MerkleRoot, Proof, UserAddress, Validator,
 };
-use protocol::{fixed_codec::ProtocolFixedCodec, ProtocolResult};
+use protocol::{fixed_codec::ProtocolFixedCodec, ProtocolError, ProtocolErrorKind, ProtocolResult};
 
 use crate::config::Config;
 
+// The coinbase of the executor building the genesis state, which is also the
+// proposer of the genesis epoch when there is no validator.
+const GENESIS_COINBASE: &str = "100000000000000000000000000000000000000000";
+
 #[runtime::main(runtime_tokio::Tokio)]
 async fn main() {
     common_logger::init(common_logger::Flag::Main);
@@ -53,43 +67,61 @@ async fn main() {
             clap::Arg::from_usage("-g --genesis=[FILE] 'a required file for the genesis json'")
                 .default_value("./devtools/chain/genesis.json"),
         )
+        .arg(clap::Arg::from_usage(
+            "--export-snapshot=[FILE] 'export the state of the latest epoch to a snapshot file'",
+        ))
+        .arg(clap::Arg::from_usage(
+            "--import-snapshot=[FILE] 'rebuild the state from a snapshot file'",
+        ))
+        .arg(clap::Arg::from_usage(
+            "--bls-pop 'print the bls public key and the proof of possession of it for a validator'",
+        ))
         .get_matches();
     let args_config = matches.value_of("config").unwrap();
     let cfg: Config = common_config_parser::parse(args_config).unwrap();
     log::info!("Go with config: {:?}", cfg);
 
-    // init genesis
+    if let Some(snapshot_path) = matches.value_of("export-snapshot") {
+        handle_export_snapshot(&cfg, snapshot_path).await.unwrap();
+        return;
+    }
+    if let Some(snapshot_path) = matches.value_of("import-snapshot") {
+        handle_import_snapshot(&cfg, snapshot_path).await.unwrap();
+        return;
+    }
+    if matches.is_present("bls-pop") {
+        print_bls_pop(&cfg).unwrap();
+        return;
+    }
+
+    // Read genesis.
     let genesis_path = matches.value_of("genesis").unwrap();
     log::info!("Genesis path: {}", genesis_path);
-    handle_init(&cfg, genesis_path).await.unwrap();
+    let mut r = File::open(genesis_path).unwrap();
+    let genesis: Genesis = serde_json::from_reader(&mut r).unwrap();
+    log::info!("Genesis data: {:?}", genesis);
 
-    start(&cfg).await.unwrap();
+    // init genesis
+    handle_init(&cfg, &genesis).await.unwrap();
+
+    start(&cfg, &genesis).await.unwrap();
 }
 
-async fn handle_init(cfg: &Config, genesis_path: impl AsRef<Path>) -> ProtocolResult<()> {
+async fn handle_init(cfg: &Config, genesis: &Genesis) -> ProtocolResult<()> {
     let chain_id = Hash::from_hex(&cfg.chain_id).unwrap();
 
-    // self private key
-    let my_privkey =
-        Secp256k1PrivateKey::try_from(hex::decode(cfg.privkey.clone()).unwrap().as_ref()).unwrap();
-    let my_pubkey = my_privkey.pub_key();
-    let my_address = UserAddress::from_pubkey_bytes(my_pubkey.to_bytes()).unwrap();
-
-    // Read genesis.
-    let mut r = File::open(genesis_path).unwrap();
-    let genesis: Genesis = serde_json::from_reader(&mut r).unwrap();
-    log::info!("Genesis data: {:?}", genesis);
-
     // Init Block db
     let path_block = cfg.data_path_for_block();
     log::info!("Data path for block: {:?}", path_block);
     let rocks_adapter = Arc::new(RocksAdapter::new(path_block).unwrap());
     let storage = Arc::new(ImplStorage::new(Arc::clone(&rocks_adapter)));
 
+    let genesis_hash = Hash::digest(genesis.encode_fixed()?);
+
     match storage.get_latest_epoch().await {
         Ok(_) => {
             log::info!("The Genesis block has been initialized.");
-            return Ok(());
+            return check_genesis(cfg, genesis, genesis_hash, storage.as_ref()).await;
         }
         Err(e) => {
             if !e.to_string().contains("GetNone") {
@@ -100,37 +132,34 @@ async fn handle_init(cfg: &Config, genesis_path: impl AsRef<Path>) -> ProtocolRe
 
     // Init trie db
     let path_state = cfg.data_path_for_state();
-    let trie_db = Arc::new(RocksTrieDB::new(path_state, cfg.executor.light).unwrap());
+    let trie_db = Arc::new(
+        RocksTrieDB::new(path_state, cfg.executor.light, cfg.executor.keep_epochs).unwrap(),
+    );
 
     // Init genesis
-    let genesis_state_root = {
-        let mut executor = TransactionExecutorFactory::from_root(
-            chain_id.clone(),
-            MerkleRoot::from_empty(),
-            Arc::clone(&trie_db),
-            0,
-            cfg.consensus.cycles_price,
-            Address::User(my_address),
-        )?;
+    let genesis_state_root = create_genesis_state(&chain_id, genesis, Arc::clone(&trie_db))?;
 
-        executor.create_genesis(&genesis)?
+    // The first validator stands for the proposer of the genesis block.
+    let validators = genesis_validators(genesis)?;
+    let proposer = match validators.first() {
+        Some(validator) => validator.address.clone(),
+        None => UserAddress::from_hex(GENESIS_COINBASE)?,
     };
 
     // Build genesis block.
     let genesis_epoch_header = EpochHeader {
-        chain_id:          chain_id.clone(),
-        epoch_id:          0,
-        pre_hash:          Hash::from_empty(),
-        timestamp:         genesis.timestamp,
-        logs_bloom:        Bloom::default(),
-        order_root:        Hash::from_empty(),
-        confirm_root:      vec![],
-        state_root:        genesis_state_root,
-        receipt_root:      vec![Hash::from_empty()],
-        cycles_used:       0,
-        proposer:          UserAddress::from_hex("100000000000000000000000000000000000000000")
-            .unwrap(),
-        proof:             Proof {
+        chain_id: chain_id.clone(),
+        epoch_id: 0,
+        pre_hash: Hash::from_empty(),
+        timestamp: genesis.timestamp,
+        logs_bloom: Bloom::default(),
+        order_root: Hash::from_empty(),
+        confirm_root: vec![],
+        state_root: genesis_state_root,
+        receipt_root: vec![Hash::from_empty()],
+        cycles_used: 0,
+        proposer,
+        proof: Proof {
             epoch_id:   0,
             round:      0,
             epoch_hash: Hash::from_empty(),
@@ -138,7 +167,7 @@ async fn handle_init(cfg: &Config, genesis_path: impl AsRef<Path>) -> ProtocolRe
             bitmap:     Bytes::new(),
         },
         validator_version: 0,
-        validators:        vec![],
+        validators,
     };
     let latest_proof = genesis_epoch_header.proof.clone();
     storage
@@ -149,10 +178,181 @@ async fn handle_init(cfg: &Config, genesis_path: impl AsRef<Path>) -> ProtocolRe
         .await
         .unwrap();
     storage.update_latest_proof(latest_proof).await.unwrap();
+    storage.update_genesis_hash(genesis_hash).await?;
+    Ok(())
+}
+
+// Refuse to run on the data of another chain, the genesis hash, the chain id
+// and the genesis state root must match the genesis epoch in the db.
+async fn check_genesis<S: Storage>(
+    cfg: &Config,
+    genesis: &Genesis,
+    genesis_hash: Hash,
+    storage: &S,
+) -> ProtocolResult<()> {
+    let chain_id = Hash::from_hex(&cfg.chain_id)?;
+    let genesis_header = storage.get_epoch_by_epoch_id(0).await?.header;
+
+    if genesis_header.chain_id != chain_id {
+        return Err(MainError::ChainIdMismatch {
+            expect: chain_id,
+            real:   genesis_header.chain_id,
+        }
+        .into());
+    }
+
+    match storage.get_genesis_hash().await {
+        Ok(stored_hash) => {
+            if stored_hash != genesis_hash {
+                return Err(MainError::GenesisMismatch {
+                    expect: stored_hash,
+                    real:   genesis_hash,
+                }
+                .into());
+            }
+        }
+        // The db is initialized before the genesis hash is persisted.
+        Err(e) if e.to_string().contains("GetNone") => {
+            log::warn!("The genesis hash is not found, persist {:?}", genesis_hash);
+            storage.update_genesis_hash(genesis_hash).await?;
+        }
+        Err(e) => return Err(e),
+    }
+
+    // Replay the genesis in memory, the db is not touched.
+    let genesis_state_root =
+        create_genesis_state(&chain_id, genesis, Arc::new(MemoryDB::new(false)))?;
+    if genesis_state_root != genesis_header.state_root {
+        return Err(MainError::StateRootMismatch {
+            expect: genesis_header.state_root,
+            real:   genesis_state_root,
+        }
+        .into());
+    }
+
+    // The state of the latest epoch must be in the state db.
+    let latest_header = storage.get_latest_epoch().await?.header;
+    let trie_db = Arc::new(RocksTrieDB::new(
+        cfg.data_path_for_state(),
+        cfg.executor.light,
+        cfg.executor.keep_epochs,
+    )?);
+    TransactionExecutorFactory::from_root(
+        chain_id,
+        latest_header.state_root.clone(),
+        trie_db,
+        latest_header.epoch_id,
+        genesis.params.cycles_price,
+        Address::User(latest_header.proposer.clone()),
+    )
+    .map_err(|_| MainError::StateNotFound {
+        state_root: latest_header.state_root,
+    })?;
+
     Ok(())
 }
 
-async fn start(cfg: &Config) -> ProtocolResult<()> {
+// Build the genesis state in `db`. The state must be the same on every node,
+// so the executor runs with a fixed coinbase instead of the address of the
+// node.
+fn create_genesis_state<DB: 'static + TrieDB>(
+    chain_id: &Hash,
+    genesis: &Genesis,
+    db: Arc<DB>,
+) -> ProtocolResult<MerkleRoot> {
+    let mut executor = TransactionExecutorFactory::from_root(
+        chain_id.clone(),
+        MerkleRoot::from_empty(),
+        db,
+        0,
+        genesis.params.cycles_price,
+        Address::User(UserAddress::from_hex(GENESIS_COINBASE)?),
+    )?;
+
+    executor.create_genesis(genesis)
+}
+
+async fn handle_export_snapshot(cfg: &Config, path: impl AsRef<Path>) -> ProtocolResult<()> {
+    let rocks_adapter = Arc::new(RocksAdapter::new(cfg.data_path_for_block()).unwrap());
+    let storage = Arc::new(ImplStorage::new(Arc::clone(&rocks_adapter)));
+    let trie_db = Arc::new(
+        RocksTrieDB::new(
+            cfg.data_path_for_state(),
+            cfg.executor.light,
+            cfg.executor.keep_epochs,
+        )
+        .unwrap(),
+    );
+
+    let epoch = storage.get_latest_epoch().await?;
+    log::info!(
+        "Export the snapshot of epoch {}, state root {:?}",
+        epoch.header.epoch_id,
+        epoch.header.state_root
+    );
+
+    let file = File::create(path).unwrap();
+    export_snapshot(trie_db, epoch.header.state_root, BufWriter::new(file))
+}
+
+async fn handle_import_snapshot(cfg: &Config, path: impl AsRef<Path>) -> ProtocolResult<()> {
+    let trie_db = Arc::new(
+        RocksTrieDB::new(
+            cfg.data_path_for_state(),
+            cfg.executor.light,
+            cfg.executor.keep_epochs,
+        )
+        .unwrap(),
+    );
+
+    let file = File::open(path).unwrap();
+    let state_root = import_snapshot(trie_db, BufReader::new(file))?;
+    log::info!("Import the snapshot, state root {:?}", state_root);
+    Ok(())
+}
+
+// A validator registered by the genesis or the governance proves the
+// possession of the bls private key, the proof is bound to its address.
+fn print_bls_pop(cfg: &Config) -> ProtocolResult<()> {
+    let privkey =
+        Secp256k1PrivateKey::try_from(hex::decode(cfg.privkey.clone()).unwrap().as_ref()).unwrap();
+    let address = UserAddress::from_pubkey_bytes(privkey.pub_key().to_bytes())?;
+    let bls_privkey =
+        BLS12381PrivateKey::try_from(hex::decode(cfg.bls_privkey.clone()).unwrap().as_ref())
+            .unwrap();
+    let bls_pub_key = bls_privkey.pub_key().to_bytes();
+
+    let pop_hash = bls_pop_hash(&address, &bls_pub_key);
+    let pop = BLS12381::sign_message(&pop_hash.as_bytes(), &bls_privkey.to_bytes())
+        .unwrap()
+        .to_bytes();
+
+    println!("address: {}", address.as_hex());
+    println!("bls_pub_key: {}", hex::encode(bls_pub_key));
+    println!("bls_pop: {}", hex::encode(pop));
+    Ok(())
+}
+
+fn genesis_validators(genesis: &Genesis) -> ProtocolResult<Vec<Validator>> {
+    genesis
+        .validators
+        .iter()
+        .map(|v| {
+            Ok(Validator {
+                address:        UserAddress::from_hex(&v.address)?,
+                propose_weight: v.propose_weight,
+                vote_weight:    v.vote_weight,
+                bls_pub_key:    Bytes::from(hex::decode(&v.bls_pub_key).map_err(|_| {
+                    MainError::InvalidBlsPubKey {
+                        address: v.address.clone(),
+                    }
+                })?),
+            })
+        })
+        .collect()
+}
+
+async fn start(cfg: &Config, genesis: &Genesis) -> ProtocolResult<()> {
     let chain_id = Hash::from_hex(&cfg.chain_id).unwrap();
 
     // self private key
@@ -160,6 +360,10 @@ async fn start(cfg: &Config) -> ProtocolResult<()> {
         Secp256k1PrivateKey::try_from(hex::decode(cfg.privkey.clone()).unwrap().as_ref()).unwrap();
     let my_pubkey = my_privkey.pub_key();
     let my_address = UserAddress::from_pubkey_bytes(my_pubkey.to_bytes()).unwrap();
+    // The consensus votes are signed by the bls private key.
+    let my_bls_privkey =
+        BLS12381PrivateKey::try_from(hex::decode(cfg.bls_privkey.clone()).unwrap().as_ref())
+            .unwrap();
 
     // Init Block db
     let path_block = cfg.data_path_for_block();
@@ -188,18 +392,25 @@ async fn start(cfg: &Config) -> ProtocolResult<()> {
         .listen(cfg.network.listening_address)
         .unwrap();
 
+    // Init trie db
+    let path_state = cfg.data_path_for_state();
+    let trie_db = Arc::new(
+        RocksTrieDB::new(path_state, cfg.executor.light, cfg.executor.keep_epochs).unwrap(),
+    );
+
     // Init mempool
     let current_epoch = storage.get_latest_epoch().await.unwrap();
-    let mempool_adapter = DefaultMemPoolAdapter::<Secp256k1, _, _>::new(
-        network_service.handle(),
-        Arc::clone(&storage),
-        cfg.mempool.timeout_gap,
-        cfg.mempool.broadcast_txs_size,
-        cfg.mempool.broadcast_txs_interval,
-    );
+    let mempool_adapter =
+        DefaultMemPoolAdapter::<Secp256k1, _, _, TransactionExecutorFactory, _>::new(
+            network_service.handle(),
+            Arc::clone(&storage),
+            Arc::clone(&trie_db),
+            chain_id.clone(),
+            cfg.mempool.broadcast_txs_size,
+            cfg.mempool.broadcast_txs_interval,
+        );
     let mempool = Arc::new(HashMemPool::new(
         cfg.mempool.pool_size as usize,
-        cfg.mempool.timeout_gap,
         mempool_adapter,
     ));
 
@@ -223,10 +434,6 @@ async fn start(cfg: &Config) -> ProtocolResult<()> {
         .register_rpc_response::<MsgPushTxs>(END_RESP_PULL_TXS)
         .unwrap();
 
-    // Init trie db
-    let path_state = cfg.data_path_for_state();
-    let trie_db = Arc::new(RocksTrieDB::new(path_state, cfg.executor.light).unwrap());
-
     // Init Consensus
     let consensus_adapter = Arc::new(OverlordConsensusAdapter::<
         TransactionExecutorFactory,
@@ -241,6 +448,7 @@ async fn start(cfg: &Config) -> ProtocolResult<()> {
         Arc::clone(&mempool),
         Arc::clone(&storage),
         Arc::clone(&trie_db),
+        cfg.executor.trace_state,
     ));
 
     let node_info = NodeInfo {
@@ -250,36 +458,63 @@ async fn start(cfg: &Config) -> ProtocolResult<()> {
     let current_header = &current_epoch.header;
     let prevhash = Hash::digest(current_epoch.encode_fixed()?);
 
+    // The header carries the state before executing the epoch, the next epoch
+    // runs on the state after executing it. The genesis epoch is not executed.
+    let state_root = if current_header.epoch_id == 0 {
+        current_header.state_root.clone()
+    } else {
+        storage.get_executed_state_root(prevhash.clone()).await?
+    };
+
+    // The validators and the chain params may be changed by the governance,
+    // read them from the state rather than the header or the genesis.
+    let (validator_set, params) = {
+        let executor = TransactionExecutorFactory::from_root(
+            chain_id.clone(),
+            state_root.clone(),
+            Arc::clone(&trie_db),
+            current_header.epoch_id,
+            genesis.params.cycles_price,
+            Address::User(current_header.proposer.clone()),
+        )?;
+        (executor.get_validator_set()?, executor.get_chain_params()?)
+    };
+
+    mempool.set_args(
+        current_header.epoch_id,
+        state_root.clone(),
+        params.timeout_gap,
+    );
+
     let current_consensus_status = CurrentConsensusStatus {
-        cycles_price:       cfg.consensus.cycles_price,
-        cycles_limit:       cfg.consensus.cycles_limit,
-        epoch_id:           current_epoch.header.epoch_id + 1,
-        prev_hash:          prevhash,
-        logs_bloom:         current_header.logs_bloom,
-        order_root:         Hash::from_empty(),
-        confirm_root:       vec![Hash::from_empty()],
-        state_root:         current_header.state_root.clone(),
-        receipt_root:       vec![Hash::from_empty()],
-        cycles_used:        current_header.cycles_used,
-        proof:              current_header.proof.clone(),
-        validators:         cfg
-            .consensus
-            .verifier_list
-            .iter()
-            .map(|v| Validator {
-                address:        UserAddress::from_hex(v).unwrap(),
-                propose_weight: 1,
-                vote_weight:    1,
-            })
-            .collect(),
-        consensus_interval: cfg.consensus.interval,
+        cycles_price: params.cycles_price,
+        cycles_limit: params.cycles_limit,
+        epoch_id: current_epoch.header.epoch_id + 1,
+        prev_hash: prevhash,
+        logs_bloom: current_header.logs_bloom,
+        order_root: Hash::from_empty(),
+        confirm_root: vec![Hash::from_empty()],
+        state_root,
+        receipt_root: vec![Hash::from_empty()],
+        cycles_used: current_header.cycles_used,
+        proof: current_header.proof.clone(),
+        validator_version: validator_set.version,
+        validators: validator_set.validators,
+        consensus_interval: params.interval,
     };
 
+    // The wal keeps the votes of the node across restarts.
+    let path_wal = cfg.data_path_for_bft_wal();
+    log::info!("Data path for bft wal: {:?}", path_wal);
+    let consensus_wal = ConsensusWal::new(path_wal)?;
+
     let overlord_consensus = Arc::new(OverlordConsensus::new(
         current_consensus_status,
         node_info,
-        my_privkey,
+        my_bls_privkey,
         consensus_adapter,
+        consensus_wal,
+        cfg.consensus.fast_sync,
     ));
 
     // register consensus
@@ -321,6 +556,18 @@ async fn start(cfg: &Config) -> ProtocolResult<()> {
     network_service
         .register_rpc_response::<ConsensusRpcResponse>(RPC_RESP_SYNC_PULL)
         .unwrap();
+    network_service
+        .register_endpoint_handler(
+            RPC_SYNC_PULL_STATE,
+            Box::new(StateRpcHandler::new(
+                Arc::new(network_service.handle()),
+                Arc::clone(&trie_db),
+            )),
+        )
+        .unwrap();
+    network_service
+        .register_rpc_response::<FixedTrieNodes>(RPC_RESP_SYNC_PULL_STATE)
+        .unwrap();
 
     // Run network
     runtime::spawn(network_service);
@@ -341,9 +588,104 @@ async fn start(cfg: &Config) -> ProtocolResult<()> {
 
     // Run consensus
     overlord_consensus
-        .run(cfg.consensus.interval, Some(cfg.consensus.duration.clone()))
+        .run(params.interval, Some(cfg.consensus.duration.clone()))
         .await
         .unwrap();
 
     Ok(())
 }
+
+#[derive(Debug, Display)]
+pub enum MainError {
+    #[display(
+        fmt = "genesis hash mismatch, expect {:?}, get {:?}, check the genesis file",
+        expect,
+        real
+    )]
+    GenesisMismatch { expect: Hash, real: Hash },
+
+    #[display(
+        fmt = "chain id mismatch, expect {:?}, get {:?}, check the data path",
+        expect,
+        real
+    )]
+    ChainIdMismatch { expect: Hash, real: Hash },
+
+    #[display(
+        fmt = "genesis state root mismatch, expect {:?}, get {:?}",
+        expect,
+        real
+    )]
+    StateRootMismatch {
+        expect: MerkleRoot,
+        real:   MerkleRoot,
+    },
+
+    #[display(fmt = "state {:?} not found, check the data path", state_root)]
+    StateNotFound { state_root: MerkleRoot },
+
+    #[display(fmt = "invalid bls public key of validator {}", address)]
+    InvalidBlsPubKey { address: String },
+}
+
+impl Error for MainError {}
+
+impl From<MainError> for ProtocolError {
+    fn from(err: MainError) -> ProtocolError {
+        ProtocolError::new(ProtocolErrorKind::Main, Box::new(err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use protocol::types::GenesisValidator;
+
+    use super::*;
+
+    // Return the secp256k1 private key of the validator along with it, the
+    // keys are made from the seed.
+    fn mock_validator(seed: u8) -> (String, GenesisValidator) {
+        let privkey = Secp256k1PrivateKey::try_from([seed; 32].as_ref()).unwrap();
+        let address = UserAddress::from_pubkey_bytes(privkey.pub_key().to_bytes()).unwrap();
+        let bls_privkey = BLS12381PrivateKey::try_from([seed; 32].as_ref()).unwrap();
+        let bls_pub_key = bls_privkey.pub_key().to_bytes();
+        let pop_hash = bls_pop_hash(&address, &bls_pub_key);
+        let pop = BLS12381::sign_message(&pop_hash.as_bytes(), &bls_privkey.to_bytes())
+            .unwrap()
+            .to_bytes();
+
+        let validator = GenesisValidator {
+            address:        address.as_hex(),
+            propose_weight: 1,
+            vote_weight:    1,
+            bls_pub_key:    hex::encode(bls_pub_key),
+            bls_pop:        hex::encode(pop),
+        };
+        (hex::encode(privkey.to_bytes()), validator)
+    }
+
+    #[runtime::test(runtime_tokio::Tokio)]
+    async fn test_init_and_check_genesis() {
+        let mut cfg: Config = common_config_parser::parse("./devtools/chain/config.toml").unwrap();
+        let mut r = File::open("./devtools/chain/genesis.json").unwrap();
+        let mut genesis: Genesis = serde_json::from_reader(&mut r).unwrap();
+
+        let (_, first) = mock_validator(1);
+        let (privkey, second) = mock_validator(2);
+        genesis.validators = vec![first, second];
+
+        // The node is not the first validator, which proposes the genesis
+        // epoch.
+        cfg.privkey = privkey;
+        cfg.data_path =
+            std::env::temp_dir().join(format!("huobi_chain_genesis_{}", std::process::id()));
+
+        handle_init(&cfg, &genesis).await.unwrap();
+        // The genesis is checked when the node restarts on the data.
+        let res = handle_init(&cfg, &genesis).await;
+        fs::remove_dir_all(&cfg.data_path).unwrap();
+        res.unwrap();
+    }
+}